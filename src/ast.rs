@@ -1,9 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize, Serializer};
+
 use crate::config::AgentRole;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Status {
     Green,
     Yellow,
@@ -20,12 +24,16 @@ impl fmt::Display for Status {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum Value {
+    /// Raw literal as written, quotes included; decode with `parser::unquote`
     String(String),
     Status(Status),
     Bool(bool),
     Number(i64),
+    Float(f64),
+    Date(NaiveDate),
     Ident(String),
     Path(Vec<String>),
     FnRef(String),
@@ -34,34 +42,43 @@ pub enum Value {
     Block(Vec<(String, Value)>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct BogFile {
     pub annotations: Vec<Annotation>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
 pub enum Annotation {
     Repo(RepoAnnotation),
     File(FileAnnotation),
     Description(String),
     Health(HealthAnnotation),
     Fn(FnAnnotation),
+    /// `#[type(Name)]`, `#[trait(Name)]` or `#[const(Name)]`
+    Item(ItemAnnotation),
     Subsystem(SubsystemDecl),
     Skimsystem(SkimsystemDecl),
     Skim(SkimObservation),
     Policies(PoliciesAnnotation),
     ChangeRequests(Vec<ChangeRequest>),
     Pickled(PickledAnnotation),
+    /// `#[annotation(kind) { ... }]` in repo.bog: declares a user-defined kind
+    Schema(AnnotationSchema),
+    /// Any other kind; checked against its declared schema by the validator
+    Custom(CustomAnnotation),
+    /// `#[include("subsystems/*.bog")]` in repo.bog, relative to the including file
+    Include(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RepoAnnotation {
     pub name: String,
     pub version: String,
     pub updated: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FileAnnotation {
     pub owner: String,
     pub subsystem: String,
@@ -69,12 +86,13 @@ pub struct FileAnnotation {
     pub status: Status,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HealthAnnotation {
+    #[serde(serialize_with = "serialize_sorted")]
     pub dimensions: HashMap<String, Status>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FnAnnotation {
     pub name: String,
     pub status: Status,
@@ -85,14 +103,137 @@ pub struct FnAnnotation {
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Contract {
     pub inputs: Vec<(String, String)>,
     pub output: Option<String>,
     pub invariants: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+/// A function reference as written in `#[fn(...)]` or `fn(...)`: a bare name,
+/// `Type::method`, or `<Type as Trait>::method` for a trait impl.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FnPath {
+    pub self_type: Option<String>,
+    pub trait_name: Option<String>,
+    pub name: String,
+}
+
+impl FnPath {
+    /// Parse a reference, normalizing whitespace inside `<...>`. `<Type>::method`
+    /// is the same as `Type::method`.
+    pub fn parse(s: &str) -> Result<FnPath, String> {
+        let s = s.trim();
+        if let Some(rest) = s.strip_prefix('<') {
+            let (qualifier, name) = rest
+                .rsplit_once(">::")
+                .ok_or_else(|| format!("expected <Type as Trait>::method, got '{s}'"))?;
+            let words: Vec<&str> = qualifier.split_whitespace().collect();
+            let (self_type, trait_name) = match words.iter().position(|w| *w == "as") {
+                Some(i) => (words[..i].join(" "), Some(words[i + 1..].join(" "))),
+                None => (words.join(" "), None),
+            };
+            if self_type.is_empty() || trait_name.as_ref().is_some_and(String::is_empty) {
+                return Err(format!("expected <Type as Trait>::method, got '{s}'"));
+            }
+            return Ok(FnPath {
+                self_type: Some(tidy_type(&self_type)),
+                trait_name: trait_name.map(|t| tidy_type(&t)),
+                name: name.to_string(),
+            });
+        }
+        Ok(match s.rsplit_once("::") {
+            Some((self_type, name)) => FnPath {
+                self_type: Some(self_type.to_string()),
+                trait_name: None,
+                name: name.to_string(),
+            },
+            None => FnPath {
+                self_type: None,
+                trait_name: None,
+                name: s.to_string(),
+            },
+        })
+    }
+
+    pub fn is_qualified(&self) -> bool {
+        self.self_type.is_some()
+    }
+}
+
+/// Drop whitespace that is not needed between words, e.g. `Vec< T >` to `Vec<T>`.
+fn tidy_type(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace(" ::", "::")
+        .replace(":: ", "::")
+}
+
+fn is_plain_ident(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl fmt::Display for FnPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.self_type, &self.trait_name) {
+            (Some(ty), Some(tr)) => write!(f, "<{ty} as {tr}>::{}", self.name),
+            (Some(ty), None) if ty.split("::").all(is_plain_ident) => {
+                write!(f, "{ty}::{}", self.name)
+            }
+            (Some(ty), None) => write!(f, "<{ty}>::{}", self.name),
+            _ => f.write_str(&self.name),
+        }
+    }
+}
+
+/// A type, trait or constant in the sidecar's source file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemAnnotation {
+    pub kind: ItemKind,
+    pub name: String,
+    pub status: Status,
+    pub refs: Vec<String>,
+    pub invariants: Vec<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    /// Structs, enums, unions and type aliases
+    Type,
+    Trait,
+    /// Consts and statics
+    Const,
+}
+
+impl ItemKind {
+    pub fn from_annotation(name: &str) -> Option<ItemKind> {
+        match name {
+            "type" => Some(ItemKind::Type),
+            "trait" => Some(ItemKind::Trait),
+            "const" => Some(ItemKind::Const),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemKind::Type => write!(f, "type"),
+            ItemKind::Trait => write!(f, "trait"),
+            ItemKind::Const => write!(f, "const"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubsystemDecl {
     pub name: String,
     pub owner: String,
@@ -102,7 +243,7 @@ pub struct SubsystemDecl {
     pub model: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SkimsystemDecl {
     pub name: String,
     pub owner: String,
@@ -114,25 +255,27 @@ pub struct SkimsystemDecl {
     pub model: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IntegrationSpec {
     pub name: String,
     pub command: String,
     pub format: IntegrationFormat,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum IntegrationFormat {
     CargoDiagnostic,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SkimTargets {
     All,
     Named(Vec<String>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SkimObservation {
     pub skimsystem: String,
     pub status: Status,
@@ -140,18 +283,20 @@ pub struct SkimObservation {
     pub target: Option<SkimTarget>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SkimTarget {
     File,
     Fn(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PoliciesAnnotation {
+    #[serde(serialize_with = "serialize_sorted")]
     pub fields: HashMap<String, Value>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PickledAnnotation {
     pub id: String,
     pub agent: String,
@@ -162,7 +307,8 @@ pub struct PickledAnnotation {
     pub content: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PickledKind {
     /// A deliberate choice with rationale — the ADR core
     Decision,
@@ -188,7 +334,8 @@ impl fmt::Display for PickledKind {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PickledTag {
     /// Structure, design, interfaces, data model, patterns
     Architecture,
@@ -223,7 +370,7 @@ impl fmt::Display for PickledTag {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChangeRequest {
     pub id: String,
     pub from: String,
@@ -235,6 +382,168 @@ pub struct ChangeRequest {
     pub description: String,
 }
 
+/// The statuses a change request may have. `ChangeRequest.status` keeps the text
+/// as written; the validator checks it against this list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestStatus {
+    /// Filed, not yet picked up by the owner
+    Pending,
+    /// The owner agreed to make the change
+    Accepted,
+    InProgress,
+    Resolved,
+    /// The owner declined the change
+    Denied,
+}
+
+impl RequestStatus {
+    pub const ALL: [RequestStatus; 5] = [
+        RequestStatus::Pending,
+        RequestStatus::Accepted,
+        RequestStatus::InProgress,
+        RequestStatus::Resolved,
+        RequestStatus::Denied,
+    ];
+
+    pub fn from_name(name: &str) -> Option<RequestStatus> {
+        RequestStatus::ALL.into_iter().find(|s| s.to_string() == name)
+    }
+}
+
+impl fmt::Display for RequestStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestStatus::Pending => write!(f, "pending"),
+            RequestStatus::Accepted => write!(f, "accepted"),
+            RequestStatus::InProgress => write!(f, "in_progress"),
+            RequestStatus::Resolved => write!(f, "resolved"),
+            RequestStatus::Denied => write!(f, "denied"),
+        }
+    }
+}
+
+/// A user-defined annotation kind, declared in bog.toml or repo.bog.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnnotationSchema {
+    pub kind: String,
+    pub fields: Vec<FieldSpec>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSpec {
+    pub name: String,
+    pub ty: FieldType,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    String,
+    Number,
+    Bool,
+    Status,
+    Ident,
+    Path,
+    FnRef,
+    List,
+    Block,
+    Date,
+    Any,
+}
+
+impl FieldType {
+    pub const ALL: [FieldType; 11] = [
+        FieldType::String,
+        FieldType::Number,
+        FieldType::Bool,
+        FieldType::Status,
+        FieldType::Ident,
+        FieldType::Path,
+        FieldType::FnRef,
+        FieldType::List,
+        FieldType::Block,
+        FieldType::Date,
+        FieldType::Any,
+    ];
+
+    /// Whether a parsed value is acceptable for this type. Bare idents count as
+    /// strings and as single-segment paths, as they do for built-in fields.
+    pub fn matches(self, value: &Value) -> bool {
+        matches!(
+            (self, value),
+            (FieldType::Any, _)
+                | (FieldType::String, Value::String(_) | Value::Ident(_))
+                | (FieldType::Number, Value::Number(_) | Value::Float(_))
+                | (FieldType::Bool, Value::Bool(_))
+                | (FieldType::Status, Value::Status(_))
+                | (FieldType::Ident, Value::Ident(_))
+                | (FieldType::Path, Value::Path(_) | Value::Ident(_))
+                | (FieldType::FnRef, Value::FnRef(_))
+                | (FieldType::List, Value::List(_))
+                | (FieldType::Block, Value::Block(_))
+                | (FieldType::Date, Value::Date(_))
+        )
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FieldType::String => "string",
+            FieldType::Number => "number",
+            FieldType::Bool => "bool",
+            FieldType::Status => "status",
+            FieldType::Ident => "ident",
+            FieldType::Path => "path",
+            FieldType::FnRef => "fn_ref",
+            FieldType::List => "list",
+            FieldType::Block => "block",
+            FieldType::Date => "date",
+            FieldType::Any => "any",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::str::FromStr for FieldType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FieldType::ALL
+            .into_iter()
+            .find(|t| t.to_string() == s)
+            .ok_or_else(|| format!("unknown field type '{s}'"))
+    }
+}
+
+/// An annotation of a user-defined kind: `#[kind(name) { key = value, ... }]`,
+/// `#[kind(key = value, ...)]` or `#[kind { free text }]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomAnnotation {
+    pub kind: String,
+    pub name: Option<String>,
+    /// Parens and body fields, in source order
+    pub fields: Vec<(String, Value)>,
+    pub text: Option<String>,
+}
+
+impl CustomAnnotation {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+}
+
+/// Serialize a HashMap with sorted keys so exported output is stable.
+fn serialize_sorted<S: Serializer, V: Serialize>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
 /// Agent registry derived from repo.bog subsystem/skimsystem declarations.
 #[derive(Debug)]
 pub struct DerivedAgents {
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+
+use crate::ast::BogFile;
+use crate::config;
+use crate::lang::{self, LanguageBackend};
+use crate::parser::{self, ParseError};
+use crate::treesitter::{Symbol, TreeSitterError};
+
+/// Where the cache lives, relative to the project root.
+pub const CACHE_DIR: &str = ".bog/cache";
+
+/// Entries are only read back by the bog version that wrote them.
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Revision of what gets cached: bump it whenever parsing or symbol extraction
+/// changes its output, so entries written before the change are not served.
+const SCHEMA: u32 = 3;
+
+/// The directory under `CACHE_DIR` this build reads and writes.
+pub fn version_dir() -> String {
+    format!("{VERSION}-schema{SCHEMA}")
+}
+
+/// Parsed sidecars and extracted symbols, stored under `.bog/cache/<version>-schema<n>/` and
+/// keyed by a hash of the file content, so an edited file simply misses. Symbols are
+/// also kept in memory, since validation looks at each source file several times.
+/// The cache is best effort: entries that cannot be read or written are recomputed.
+#[derive(Debug, Default)]
+pub struct Cache {
+    dir: Option<PathBuf>,
+    symbols: Mutex<HashMap<String, Vec<Symbol>>>,
+}
+
+impl Cache {
+    /// The cache of a project, unless bog.toml sets `[cache] enabled = false`.
+    /// Entries of other bog versions or cache schemas are left alone, since another
+    /// bog binary may still be using them; `clean` removes them.
+    pub fn open(root: &Path) -> Cache {
+        let enabled = config::load_config(&root.join("bog.toml"))
+            .map(|c| c.cache.enabled)
+            .unwrap_or(true);
+        if !enabled {
+            return Cache::disabled();
+        }
+        Cache {
+            dir: Some(root.join(CACHE_DIR).join(version_dir())),
+            ..Cache::default()
+        }
+    }
+
+    /// A cache that only remembers symbols for the life of the process.
+    pub fn disabled() -> Cache {
+        Cache::default()
+    }
+
+    /// `parser::parse_bog`, from the cache when this content parsed before.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parse error; failed parses are not cached.
+    pub fn parse_bog(&self, content: &str) -> Result<BogFile, ParseError> {
+        let key = hash(content);
+        if let Some(bog) = self.load("sidecars", &key) {
+            return Ok(bog);
+        }
+        let bog = parser::parse_bog(content)?;
+        self.store("sidecars", &key, &bog);
+        Ok(bog)
+    }
+
+    /// `parser::parse_bog_recovering`, sharing cache entries with `parse_bog`.
+    pub fn parse_bog_recovering(&self, content: &str) -> (BogFile, Vec<ParseError>) {
+        let key = hash(content);
+        if let Some(bog) = self.load("sidecars", &key) {
+            return (bog, Vec::new());
+        }
+        let (bog, errors) = parser::parse_bog_recovering(content);
+        if errors.is_empty() {
+            self.store("sidecars", &key, &bog);
+        }
+        (bog, errors)
+    }
+
+    /// `backend.extract_symbols`, from memory or the cache when possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns the tree-sitter error; failures are not cached.
+    pub fn extract_symbols(
+        &self,
+        source: &str,
+        backend: &dyn LanguageBackend,
+    ) -> Result<Vec<Symbol>, TreeSitterError> {
+        // The same text can be valid in two languages, with different symbols
+        let key = hash(&format!("{}\n{source}", backend.name()));
+        if let Some(symbols) = self.symbols.lock().unwrap().get(&key) {
+            return Ok(symbols.clone());
+        }
+        let symbols = match self.load("symbols", &key) {
+            Some(symbols) => symbols,
+            None => {
+                let symbols = backend.extract_symbols(source)?;
+                self.store("symbols", &key, &symbols);
+                symbols
+            }
+        };
+        self.symbols.lock().unwrap().insert(key, symbols.clone());
+        Ok(symbols)
+    }
+
+    /// The symbols of a source file on disk, read by the backend for its extension;
+    /// `None` if no backend reads it or it cannot be read or parsed.
+    pub fn symbols(&self, source_path: &Path) -> Option<Vec<Symbol>> {
+        let backend = lang::backend_for(source_path)?;
+        let source = std::fs::read_to_string(source_path).ok()?;
+        self.extract_symbols(&source, backend).ok()
+    }
+
+    fn entry_path(&self, kind: &str, key: &str) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(kind).join(format!("{key}.json")))
+    }
+
+    fn load<T: DeserializeOwned>(&self, kind: &str, key: &str) -> Option<T> {
+        let text = std::fs::read_to_string(self.entry_path(kind, key)?).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    fn store<T: Serialize>(&self, kind: &str, key: &str, value: &T) {
+        let Some(path) = self.entry_path(kind, key) else {
+            return;
+        };
+        let Ok(json) = serde_json::to_string(value) else {
+            return;
+        };
+        // Write then rename, so a concurrent reader never sees half an entry
+        let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+        let written = path.parent().is_some_and(|dir| std::fs::create_dir_all(dir).is_ok())
+            && std::fs::write(&tmp, json).is_ok();
+        if !written || std::fs::rename(&tmp, &path).is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+    }
+}
+
+/// Remove the entries of every bog version and cache schema other than this
+/// build's, for `bog cache clean`. Returns the directories removed.
+///
+/// # Errors
+///
+/// Fails if the cache directory cannot be listed or an entry cannot be removed.
+pub fn clean(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(root.join(CACHE_DIR)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut removed = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_name().to_str() != Some(version_dir().as_str()) {
+            std::fs::remove_dir_all(entry.path())?;
+            removed.push(entry.path());
+        }
+    }
+    removed.sort();
+    Ok(removed)
+}
+
+fn hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::rust::RUST;
+
+    const SIDECAR: &str = r#"#[file(owner = "a", subsystem = "s", updated = "2026-01-01", status = green)]
+#[fn(login) { status = green, deps = [db::get], contract = { in = [(name, str)] } }]
+#[change_requests {
+  #[request(id = "r1", from = "a", target = fn(login), type = lint_warning, status = pending,
+    created = "2026-01-01", description = "x")]
+}]
+"#;
+
+    const SOURCE: &str = "pub fn login(name: &str) { db::get(name); }";
+
+    fn temp_root() -> PathBuf {
+        std::env::temp_dir().join(format!("bog-cache-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_cached_entries_round_trip() {
+        let root = temp_root();
+        let cache = Cache::open(&root);
+        let parsed = cache.parse_bog(SIDECAR).unwrap();
+        let symbols = cache.extract_symbols(SOURCE, &RUST).unwrap();
+
+        // A fresh cache reads the entries back from disk
+        let reopened = Cache::open(&root);
+        let entries = std::fs::read_dir(root.join(CACHE_DIR).join(version_dir()).join("symbols"))
+            .unwrap()
+            .count();
+        let (cached, errors) = reopened.parse_bog_recovering(SIDECAR);
+        let cached_symbols = reopened.extract_symbols(SOURCE, &RUST).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(entries, 1);
+        assert_eq!(cached, parsed);
+        assert!(errors.is_empty());
+        assert_eq!(cached_symbols.len(), symbols.len());
+        assert_eq!(cached_symbols[0].calls, ["db::get"]);
+        assert_eq!(cached_symbols[0].visibility.as_deref(), Some("pub"));
+    }
+
+    #[test]
+    fn test_stale_versions_and_bad_entries_are_ignored() {
+        let root = temp_root();
+        let old = root.join(CACHE_DIR).join("0.0.0-old");
+        std::fs::create_dir_all(&old).unwrap();
+        // Same bog version, but written before the last schema change
+        let old_schema = root.join(CACHE_DIR).join(format!("{VERSION}-schema{}", SCHEMA - 1));
+        std::fs::create_dir_all(&old_schema).unwrap();
+        let cache = Cache::open(&root);
+        let entry = cache.entry_path("sidecars", &hash(SIDECAR)).unwrap();
+        std::fs::create_dir_all(entry.parent().unwrap()).unwrap();
+        std::fs::write(&entry, "not json").unwrap();
+
+        let parsed = cache.parse_bog(SIDECAR);
+        let rewritten = std::fs::read_to_string(&entry).unwrap();
+        // Another bog binary may still use its entries, so only clean removes them
+        let kept = old.exists() && old_schema.exists();
+        let removed = clean(&root).unwrap();
+        let current_kept = entry.exists();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!(parsed.is_ok());
+        assert!(rewritten.starts_with('{'));
+        assert!(kept);
+        assert_eq!(removed, [old, old_schema]);
+        assert!(current_kept);
+        assert!(clean(&root).unwrap().is_empty());
+        assert!(Cache::disabled().parse_bog("#[fn(").is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChangesError {
+    #[error("Failed to run git: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("git {args} failed: {stderr}")]
+    Git { args: String, stderr: String },
+}
+
+/// The diff a scoped validation looks at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffBase {
+    /// The working tree, untracked files included, against a commit-ish
+    Since(String),
+    /// The index against HEAD, as a pre-commit hook sees it
+    Staged,
+}
+
+/// Paths touched by a diff, relative to the project root with `/` separators.
+/// Deleted and renamed-away paths are included, so their sidecars are checked too.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSet {
+    paths: BTreeSet<String>,
+}
+
+impl ChangeSet {
+    pub fn new<S: AsRef<str>>(paths: &[S]) -> ChangeSet {
+        ChangeSet {
+            paths: paths
+                .iter()
+                .map(|p| p.as_ref().trim_start_matches("./").to_string())
+                .collect(),
+        }
+    }
+
+    /// The files `base` touched under `root`, from the `git` CLI.
+    ///
+    /// # Errors
+    ///
+    /// Fails if git cannot be run, `root` is not in a repository or the ref is unknown.
+    pub fn from_git(root: &Path, base: &DiffBase) -> Result<ChangeSet, ChangesError> {
+        // Without rename detection a moved file shows up under both names
+        let diff = ["diff", "--name-only", "--relative", "--no-renames"];
+        let paths = match base {
+            DiffBase::Since(reference) => {
+                let mut paths = git(root, &[&diff[..], &[reference.as_str(), "--"]].concat())?;
+                paths.extend(git(root, &["ls-files", "--others", "--exclude-standard"])?);
+                paths
+            }
+            DiffBase::Staged => git(root, &[&diff[..], &["--cached", "--"]].concat())?,
+        };
+        Ok(ChangeSet::new(&paths))
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.paths.iter().map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    pub fn contains(&self, rel: &str) -> bool {
+        self.paths.contains(rel.trim_start_matches("./"))
+    }
+
+    /// Whether a path is in scope: it changed or, for a sidecar, the file it describes did.
+    pub fn touches(&self, rel: &str) -> bool {
+        self.contains(rel)
+            || rel
+                .strip_suffix(".bog")
+                .is_some_and(|source| self.contains(source))
+    }
+
+    /// Whether any changed path no longer exists under `root`.
+    pub fn has_deletions(&self, root: &Path) -> bool {
+        self.paths.iter().any(|p| !root.join(p).exists())
+    }
+}
+
+/// `path` relative to the project root with `/` separators, as in a `ChangeSet`
+pub fn relative_to(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Run git in `root` and return the lines it prints.
+fn git(root: &Path, args: &[&str]) -> Result<Vec<String>, ChangesError> {
+    let output = Command::new("git").args(args).current_dir(root).output()?;
+    if !output.status.success() {
+        return Err(ChangesError::Git {
+            args: args.join(" "),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sidecars_are_touched_through_their_source() {
+        let changes = ChangeSet::new(&["./src/a.rs", "src/b.rs.bog"]);
+        assert!(changes.touches("src/a.rs.bog"));
+        assert!(changes.touches("src/b.rs.bog"));
+        assert!(!changes.touches("src/c.rs.bog"));
+        assert!(changes.contains("./src/a.rs"));
+        assert_eq!(changes.paths().collect::<Vec<_>>(), ["src/a.rs", "src/b.rs.bog"]);
+    }
+}
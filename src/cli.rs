@@ -1,11 +1,20 @@
+use std::fmt::Write;
 use std::path::{Path, PathBuf};
 
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 
+use crate::cache;
+use crate::changes;
 use crate::context;
+use crate::deps;
+use crate::diagnostic;
+use crate::export;
+use crate::fix;
+use crate::formatter;
 use crate::health;
 use crate::orchestrate;
+use crate::report;
 use crate::stub;
 use crate::validator;
 
@@ -25,6 +34,24 @@ pub enum Command {
     Validate {
         /// Path to validate (defaults to current directory)
         path: Option<PathBuf>,
+
+        /// Rename drifted fn annotations, correct owners and rewrite deps lists before
+        /// validating; with --changed-since, only in the sidecars the diff touches
+        #[arg(long, conflicts_with = "staged")]
+        fix: bool,
+
+        /// Output format: text, json, sarif or junit
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        /// Only validate files changed since a git ref, uncommitted and untracked included
+        #[arg(long, value_name = "REF", conflicts_with = "staged")]
+        changed_since: Option<String>,
+
+        /// Only validate files changed in the git index. Their working-tree contents are
+        /// checked, so unstaged edits to a staged file count.
+        #[arg(long)]
+        staged: bool,
     },
 
     /// Show health status for all subsystems
@@ -90,6 +117,10 @@ pub enum Command {
         #[arg(long)]
         skims: bool,
 
+        /// Show only custom annotations (kinds declared in bog.toml or repo.bog)
+        #[arg(long)]
+        custom: bool,
+
         /// Filter pickled by kind (decision, reversal, context, observation, rationale)
         #[arg(long)]
         kind: Option<String>,
@@ -113,6 +144,49 @@ pub enum Command {
         list: bool,
     },
 
+    /// Rewrite .bog files in the canonical layout, keeping comments
+    Fmt {
+        /// File or directory to format (defaults to current directory)
+        path: Option<PathBuf>,
+
+        /// Report files that would change without writing them
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Dump repo.bog and every sidecar as structured data, keyed by path
+    Export {
+        /// Path to project root (defaults to current directory)
+        path: Option<PathBuf>,
+
+        /// Output format: json, yaml or toml
+        #[arg(long, default_value = "json")]
+        format: String,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Write sidecars back from a JSON export (replaces existing files)
+    Import {
+        /// JSON file produced by `bog export --format json`
+        input: PathBuf,
+
+        /// Path to project root (defaults to current directory)
+        path: Option<PathBuf>,
+    },
+
+    /// Manage the parse and symbol cache under .bog/cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+
+        /// Path to project root (defaults to current directory)
+        #[arg(short, long, global = true)]
+        path: Option<PathBuf>,
+    },
+
     /// Multi-agent orchestration: delegate work to subsystem agents
     Orchestrate {
         #[command(subcommand)]
@@ -124,6 +198,12 @@ pub enum Command {
     },
 }
 
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Remove cache entries written by other bog versions or cache schemas
+    Clean,
+}
+
 #[derive(Subcommand)]
 pub enum OrchestrateCommand {
     /// Run a free-form request through the dock → delegate → merge loop.
@@ -158,9 +238,34 @@ pub enum OrchestrateCommand {
 pub fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
     match cli.command {
         Command::Init => cmd_init(),
-        Command::Validate { path } => {
+        Command::Validate {
+            path,
+            fix,
+            format,
+            changed_since,
+            staged,
+        } => {
             let root = path.unwrap_or_else(|| PathBuf::from("."));
-            cmd_validate(&root)
+            let format = match format.as_str() {
+                "text" => None,
+                other => Some(other.parse::<report::ReportFormat>()?),
+            };
+            let base = match (changed_since, staged) {
+                (Some(reference), _) => Some(changes::DiffBase::Since(reference)),
+                (None, true) => Some(changes::DiffBase::Staged),
+                (None, false) => None,
+            };
+            let changes = match &base {
+                Some(base) => Some(changes::ChangeSet::from_git(&root, base)?),
+                None => None,
+            };
+            if fix {
+                cmd_validate_fix(&root, changes.as_ref(), format.is_some());
+            }
+            match format {
+                Some(format) => cmd_validate_report(&root, format, changes.as_ref()),
+                None => cmd_validate(&root, changes.as_ref()),
+            }
         }
         Command::Status { path } => {
             let root = path.unwrap_or_else(|| PathBuf::from("."));
@@ -192,6 +297,7 @@ pub fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             health,
             contracts,
             skims,
+            custom,
             kind,
             tag,
             format,
@@ -202,9 +308,9 @@ pub fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 (_, Some(s)) => context::ContextScope::Subsystem(s),
                 _ => context::ContextScope::All,
             };
-            let any_flag = pickled || requests || health || contracts || skims;
+            let any_flag = pickled || requests || health || contracts || skims || custom;
             let filter = if any_flag {
-                context::SectionFilter { pickled, requests, health, contracts, skims }
+                context::SectionFilter { pickled, requests, health, contracts, skims, custom }
             } else {
                 context::SectionFilter::all()
             };
@@ -218,6 +324,28 @@ pub fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 cmd_stub(&root)
             }
         }
+        Command::Export {
+            path,
+            format,
+            output,
+        } => {
+            let root = path.unwrap_or_else(|| PathBuf::from("."));
+            cmd_export(&root, &format, output.as_deref())
+        }
+        Command::Import { input, path } => {
+            let root = path.unwrap_or_else(|| PathBuf::from("."));
+            cmd_import(&root, &input)
+        }
+        Command::Fmt { path, check } => {
+            let root = path.unwrap_or_else(|| PathBuf::from("."));
+            cmd_fmt(&root, check)
+        }
+        Command::Cache { command, path } => {
+            let root = path.unwrap_or_else(|| PathBuf::from("."));
+            match command {
+                CacheCommand::Clean => cmd_cache_clean(&root),
+            }
+        }
         Command::Orchestrate { command, path } => {
             let root = path
                 .unwrap_or_else(|| PathBuf::from("."))
@@ -342,9 +470,20 @@ dimensions = ["test_coverage", "staleness", "complexity", "contract_compliance"]
     Ok(())
 }
 
-fn cmd_validate(root: &Path) -> Result<(), Box<dyn std::error::Error>> {
-    println!("{}", "Validating .bog files...".bold());
-    let report = validator::validate_project(root);
+fn cmd_validate(
+    root: &Path,
+    changes: Option<&changes::ChangeSet>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let report = match changes {
+        Some(changes) => {
+            println!("{}", "Validating .bog files touched by the diff...".bold());
+            validator::validate_changed(root, changes)
+        }
+        None => {
+            println!("{}", "Validating .bog files...".bold());
+            validator::validate_project(root)
+        }
+    };
 
     for warning in &report.warnings {
         println!("  {} {warning}", "warn:".yellow());
@@ -352,6 +491,17 @@ fn cmd_validate(root: &Path) -> Result<(), Box<dyn std::error::Error>> {
 
     for error in &report.errors {
         println!("  {} {error}", "error:".red());
+        if let validator::ValidationError::Parse {
+            file,
+            span: Some(span),
+            ..
+        } = error
+            && let Ok(source) = std::fs::read_to_string(file)
+        {
+            for line in diagnostic::render_snippet(&source, file, *span).lines() {
+                println!("  {}", line.blue());
+            }
+        }
     }
 
     println!(
@@ -372,6 +522,61 @@ fn cmd_validate(root: &Path) -> Result<(), Box<dyn std::error::Error>> {
     }
 }
 
+/// Machine-readable validation output on stdout; exits 1 if there are errors.
+fn cmd_validate_report(
+    root: &Path,
+    format: report::ReportFormat,
+    changes: Option<&changes::ChangeSet>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let validation = match changes {
+        Some(changes) => validator::validate_changed(root, changes),
+        None => validator::validate_project(root),
+    };
+    print!("{}", report::render(&validation, root, format)?);
+    if !validation.is_ok() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Apply the mechanical fixes: confident renames, owners from repo.bog and drifted
+/// deps lists. The summary goes to stderr when stdout carries a machine-readable
+/// report.
+fn cmd_validate_fix(root: &Path, changes: Option<&changes::ChangeSet>, to_stderr: bool) {
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", "Fixing sidecars...".bold());
+    let report = fix::fix_project(root, &deps::load_ignore(root), changes);
+    for (path, fixes) in &report.fixed {
+        let fixes: Vec<String> = fixes.iter().map(ToString::to_string).collect();
+        let _ = writeln!(
+            out,
+            "  {} {}: {}",
+            "fixed:".green(),
+            path.display(),
+            fixes.join(", ")
+        );
+    }
+    for (path, fix, reason) in &report.unapplied {
+        let _ = writeln!(
+            out,
+            "  {} {}: {fix} not applied: {reason}",
+            "warn:".yellow(),
+            path.display()
+        );
+    }
+    for (path, message) in &report.failed {
+        let _ = writeln!(out, "  {} {}: {message}", "error:".red(), path.display());
+    }
+    if report.fixed.is_empty() && report.unapplied.is_empty() && report.failed.is_empty() {
+        let _ = writeln!(out, "  Nothing to fix.");
+    }
+    if to_stderr {
+        eprintln!("{out}");
+    } else {
+        println!("{out}");
+    }
+}
+
 fn cmd_status(root: &Path) -> Result<(), Box<dyn std::error::Error>> {
     let repo_health = health::compute_health(root);
     let report = health::format_health_report(&repo_health);
@@ -407,6 +612,10 @@ fn cmd_check(root: &Path) -> Result<(), Box<dyn std::error::Error>> {
                     | validator::ValidationError::UndeclaredSkimsystem { .. }
                     | validator::ValidationError::SkimsystemTargetNotFound { .. }
                     | validator::ValidationError::SkimTargetFunctionMissing { .. }
+                    | validator::ValidationError::UnmatchedSubsystemGlob { .. }
+                    | validator::ValidationError::UnownedFile { .. }
+                    | validator::ValidationError::FileInMultipleSubsystems { .. }
+                    | validator::ValidationError::SkimTargetOwnsNoFiles { .. }
             )
         })
         .collect();
@@ -456,23 +665,19 @@ fn cmd_skim(
     }
 
     // Load principles from repo.bog for display
-    let repo_bog_path = root.join("repo.bog");
     let skimsystem_decls: Vec<crate::ast::SkimsystemDecl> =
-        if let Ok(content) = std::fs::read_to_string(&repo_bog_path) {
-            if let Ok(bog) = crate::parser::parse_bog(&content) {
-                bog.annotations
-                    .into_iter()
-                    .filter_map(|a| {
-                        if let crate::ast::Annotation::Skimsystem(sk) = a {
-                            Some(sk)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect()
-            } else {
-                Vec::new()
-            }
+        if let Ok(repo) = crate::include::load_repo_bog(root) {
+            repo.bog
+                .annotations
+                .into_iter()
+                .filter_map(|a| {
+                    if let crate::ast::Annotation::Skimsystem(sk) = a {
+                        Some(sk)
+                    } else {
+                        None
+                    }
+                })
+                .collect()
         } else {
             Vec::new()
         };
@@ -538,44 +743,32 @@ fn cmd_skim(
         // Verbose: show individual observations from .bog files
         if verbose {
             println!("    Details:");
-            let pattern = root.join("**/*.bog");
-            if let Ok(paths) = glob::glob(&pattern.to_string_lossy()) {
-                for bog_path in paths.flatten() {
-                    let rel = bog_path.strip_prefix(root).unwrap_or(&bog_path);
-                    if rel.components().any(|c| {
-                        matches!(c.as_os_str().to_str(), Some("target" | ".git"))
-                    }) {
-                        continue;
-                    }
-                    if bog_path.file_name().map(|n| n == "repo.bog").unwrap_or(false) {
-                        continue;
-                    }
-
-                    if let Ok(content) = std::fs::read_to_string(&bog_path) {
-                        if let Ok(bog) = crate::parser::parse_bog(&content) {
-                            for ann in &bog.annotations {
-                                if let crate::ast::Annotation::Skim(obs) = ann {
-                                    if obs.skimsystem == sk.name {
-                                        let file_rel = rel.to_string_lossy();
-                                        let dot = match obs.status {
-                                            crate::ast::Status::Green => "●".green(),
-                                            crate::ast::Status::Yellow => "●".yellow(),
-                                            crate::ast::Status::Red => "●".red(),
-                                        };
-                                        let target_str = match &obs.target {
-                                            Some(crate::ast::SkimTarget::Fn(name)) => {
-                                                format!(" -> fn({name})")
-                                            }
-                                            _ => String::new(),
-                                        };
-                                        println!(
-                                            "      {dot} {file_rel}{target_str}"
-                                        );
-                                        if let Some(notes) = &obs.notes {
-                                            println!("        \"{notes}\"");
-                                        }
-                                    }
+            for bog_path in crate::include::sidecar_files(root) {
+                let rel = bog_path.strip_prefix(root).unwrap_or(&bog_path);
+                if let Ok(content) = std::fs::read_to_string(&bog_path)
+                    && let Ok(bog) = crate::parser::parse_bog(&content)
+                {
+                    for ann in &bog.annotations {
+                        if let crate::ast::Annotation::Skim(obs) = ann
+                            && obs.skimsystem == sk.name
+                        {
+                            let file_rel = rel.to_string_lossy();
+                            let dot = match obs.status {
+                                crate::ast::Status::Green => "●".green(),
+                                crate::ast::Status::Yellow => "●".yellow(),
+                                crate::ast::Status::Red => "●".red(),
+                            };
+                            let target_str = match &obs.target {
+                                Some(crate::ast::SkimTarget::Fn(name)) => {
+                                    format!(" -> fn({name})")
                                 }
+                                _ => String::new(),
+                            };
+                            println!(
+                                "      {dot} {file_rel}{target_str}"
+                            );
+                            if let Some(notes) = &obs.notes {
+                                println!("        \"{notes}\"");
                             }
                         }
                     }
@@ -597,18 +790,16 @@ fn cmd_skim_run(
     use crate::integration;
 
     // Parse repo.bog to find the target skimsystem
-    let repo_bog_path = root.join("repo.bog");
-    let content = std::fs::read_to_string(&repo_bog_path)?;
-    let bog = crate::parser::parse_bog(&content)?;
+    let bog = crate::include::load_repo_bog(root)?.bog;
 
     let skimsystem = bog
         .annotations
         .into_iter()
         .find_map(|a| {
-            if let crate::ast::Annotation::Skimsystem(sk) = a {
-                if sk.name == name {
-                    return Some(sk);
-                }
+            if let crate::ast::Annotation::Skimsystem(sk) = a
+                && sk.name == name
+            {
+                return Some(sk);
             }
             None
         })
@@ -691,6 +882,78 @@ fn cmd_skim_run(
     Ok(())
 }
 
+fn cmd_fmt(root: &Path, check: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let report = formatter::format_path(root, check);
+
+    for (path, error) in &report.errors {
+        println!("  {} {}: {error}", "error:".red(), path.display());
+    }
+
+    let verb = if check { "would reformat" } else { "formatted" };
+    for path in &report.changed {
+        println!("  {verb} {}", path.display());
+    }
+
+    println!(
+        "\n  {} file(s) checked, {} {verb}.",
+        report.files_checked.to_string().bold(),
+        report.changed.len().to_string().bold()
+    );
+
+    if !report.errors.is_empty() || (check && !report.changed.is_empty()) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn cmd_cache_clean(root: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let removed = cache::clean(root)?;
+    for path in &removed {
+        println!("  removed {}", path.display());
+    }
+    println!(
+        "\n  {} {} stale cache dir(s) removed; kept {}.",
+        "ok:".green(),
+        removed.len(),
+        cache::version_dir()
+    );
+    Ok(())
+}
+
+fn cmd_export(
+    root: &Path,
+    format: &str,
+    output: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let format: export::ExportFormat = format.parse()?;
+    let project = export::export_project(root)?;
+    let rendered = export::render(&project, format)?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, rendered)?;
+            eprintln!(
+                "{} exported {} file(s) to {}",
+                "ok:".green(),
+                project.files.len(),
+                path.display()
+            );
+        }
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
+
+fn cmd_import(root: &Path, input: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let json = std::fs::read_to_string(input)?;
+    let written = export::import_project(root, &json)?;
+    for path in &written {
+        println!("  wrote {}", path.display());
+    }
+    println!("\n  {} {} file(s) imported.", "ok:".green(), written.len());
+    Ok(())
+}
+
 fn cmd_stub(root: &Path) -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "Generating stubs for unannotated functions...".bold());
 
@@ -718,6 +981,10 @@ fn cmd_stub(root: &Path) -> Result<(), Box<dyn std::error::Error>> {
 
     let report = stub::apply_stubs(root);
 
+    for (path, error) in &report.errors {
+        println!("  {} {}: {error}", "error:".red(), path.display());
+    }
+
     println!(
         "\n  {} stub(s) generated across {} file(s) ({} modified, {} created).",
         report.stubs_generated.to_string().bold(),
@@ -733,6 +1000,9 @@ fn cmd_stub(root: &Path) -> Result<(), Box<dyn std::error::Error>> {
         "stub = true".bold()
     );
 
+    if !report.errors.is_empty() {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
@@ -1,14 +1,53 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use serde::Deserialize;
 
+use crate::ast::{AnnotationSchema, FieldSpec, FieldType};
+
 #[derive(Debug, Deserialize)]
 pub struct BogConfig {
     pub bog: BogMeta,
+    /// Gitignore-style patterns for paths no command should scan, on top of
+    /// `walk::DEFAULT_EXCLUDE` and before `.bogignore`
+    #[serde(default)]
+    pub exclude: Vec<String>,
     #[serde(default)]
     pub tree_sitter: TreeSitterConfig,
     #[serde(default)]
     pub health: HealthConfig,
+    #[serde(default)]
+    pub deps: DepsConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    /// User-defined annotation kinds, keyed by kind name
+    #[serde(default)]
+    pub annotations: BTreeMap<String, AnnotationConfig>,
+}
+
+impl BogConfig {
+    /// Schemas for the `[annotations.<kind>]` tables, in kind order.
+    pub fn annotation_schemas(&self) -> Vec<AnnotationSchema> {
+        self.annotations
+            .iter()
+            .map(|(kind, decl)| {
+                let required = decl.required.iter().map(|(name, ty)| (name, ty, true));
+                let optional = decl.optional.iter().map(|(name, ty)| (name, ty, false));
+                AnnotationSchema {
+                    kind: kind.clone(),
+                    fields: required
+                        .chain(optional)
+                        .map(|(name, ty, required)| FieldSpec {
+                            name: name.clone(),
+                            ty: *ty,
+                            required,
+                        })
+                        .collect(),
+                    description: decl.description.clone(),
+                }
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,6 +77,55 @@ pub struct HealthConfig {
     pub dimensions: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DepsConfig {
+    /// Glob patterns over call paths that `deps` lists need not mention
+    #[serde(default = "default_dep_ignore")]
+    pub ignore: Vec<String>,
+}
+
+impl Default for DepsConfig {
+    fn default() -> Self {
+        DepsConfig {
+            ignore: default_dep_ignore(),
+        }
+    }
+}
+
+fn default_dep_ignore() -> Vec<String> {
+    crate::deps::DEFAULT_IGNORE
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CacheConfig {
+    /// Keep parsed sidecars and extracted symbols under `.bog/cache`
+    #[serde(default = "default_cache_enabled")]
+    pub enabled: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig { enabled: true }
+    }
+}
+
+fn default_cache_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct AnnotationConfig {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: BTreeMap<String, FieldType>,
+    #[serde(default)]
+    pub optional: BTreeMap<String, FieldType>,
+}
+
 pub fn load_config(path: &Path) -> Result<BogConfig, ConfigError> {
     let content = std::fs::read_to_string(path)?;
     let config: BogConfig = toml::from_str(&content)?;
@@ -2,12 +2,18 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use colored::Colorize;
+use rayon::prelude::*;
 use serde::Serialize;
 
 use crate::ast::{
     self, Annotation, BogFile, SkimTarget, SubsystemDecl, Value,
 };
+use crate::cache::Cache;
+use crate::diagnostic;
+use crate::include::{self, IncludeError};
+use crate::inline;
 use crate::parser;
+use crate::walk::Walker;
 
 // --- Error type ---
 
@@ -19,8 +25,8 @@ pub enum ContextError {
     UnknownSubsystem(String),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
-    #[error("Failed to parse repo.bog")]
-    RepoBogParse,
+    #[error("Failed to load repo.bog: {0}")]
+    RepoBogParse(#[from] IncludeError),
 }
 
 // --- Scoping & filtering ---
@@ -37,6 +43,7 @@ pub struct SectionFilter {
     pub health: bool,
     pub contracts: bool,
     pub skims: bool,
+    pub custom: bool,
 }
 
 impl SectionFilter {
@@ -47,6 +54,7 @@ impl SectionFilter {
             health: true,
             contracts: true,
             skims: true,
+            custom: true,
         }
     }
 }
@@ -93,7 +101,14 @@ pub struct FileContext {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub fn_contracts: Vec<FnContractOutput>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub item_contracts: Vec<ItemContractOutput>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub skim_observations: Vec<SkimObservationOutput>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub custom: Vec<CustomOutput>,
+    /// Annotations skipped because they failed to parse, as "line:col: message"
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parse_errors: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -137,6 +152,16 @@ pub struct FnContractOutput {
     pub deps: Vec<String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ItemContractOutput {
+    pub kind: String,
+    pub name: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub invariants: Vec<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ContractOutput {
     pub inputs: Vec<(String, String)>,
@@ -155,6 +180,16 @@ pub struct SkimObservationOutput {
     pub target: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct CustomOutput {
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub fields: Vec<(String, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
 // --- Loading ---
 
 /// Load annotation context from .bog files, scoped to an agent, subsystem, or all.
@@ -166,9 +201,7 @@ pub fn load_context(
     tag_filter: Option<&str>,
 ) -> Result<ContextOutput, ContextError> {
     // 1. Parse repo.bog
-    let repo_bog_path = root.join("repo.bog");
-    let repo_content = std::fs::read_to_string(&repo_bog_path)?;
-    let repo_bog = parser::parse_bog(&repo_content).map_err(|_| ContextError::RepoBogParse)?;
+    let repo_bog = include::load_repo_bog(root)?.bog;
 
     let subsystem_decls: Vec<SubsystemDecl> = repo_bog
         .annotations
@@ -185,42 +218,45 @@ pub fn load_context(
     // 2. Resolve scope
     let (scope_info, target_subsystems) = resolve_scope(&scope, &subsystem_decls, &repo_bog)?;
 
-    // 4. Discover and parse .bog sidecars within scope
-    let mut files = Vec::new();
-
-    for decl in &target_subsystems {
-        for pattern in &decl.files {
-            let full_pattern = root.join(pattern).to_string_lossy().to_string();
-            let Ok(paths) = glob::glob(&full_pattern) else {
-                continue;
-            };
-            for source_path in paths.flatten() {
-                let bog_path = PathBuf::from(format!("{}.bog", source_path.display()));
-                if !bog_path.exists() {
-                    continue;
-                }
-
-                let content = match std::fs::read_to_string(&bog_path) {
-                    Ok(c) => c,
-                    Err(_) => continue,
-                };
-                let bog = match parser::parse_bog(&content) {
-                    Ok(b) => b,
-                    Err(_) => continue,
-                };
-
-                let rel_path = source_path
-                    .strip_prefix(root)
-                    .unwrap_or(&source_path)
-                    .to_string_lossy()
-                    .to_string();
-
-                let file_ctx =
-                    extract_file_context(&rel_path, &decl.name, &bog, &filter, kind_filter, tag_filter);
-                files.push(file_ctx);
-            }
-        }
-    }
+    // 4. Discover and parse .bog sidecars within scope, in parallel
+    let walker = Walker::new(root);
+    let cache = Cache::open(root);
+    let sources: Vec<(&str, PathBuf)> = target_subsystems
+        .iter()
+        .flat_map(|decl| {
+            decl.files
+                .iter()
+                .flat_map(|pattern| walker.glob(pattern))
+                .map(|source_path| (decl.name.as_str(), source_path))
+        })
+        .collect();
+    let mut files: Vec<FileContext> = sources
+        .par_iter()
+        .filter_map(|(subsystem, source_path)| {
+            let bog_path = PathBuf::from(format!("{}.bog", source_path.display()));
+            let content = std::fs::read_to_string(&bog_path).ok()?;
+            let (mut bog, parse_errors) = cache.parse_bog_recovering(&content);
+            let inline_errors = inline::merge_from_source(&mut bog, source_path);
+
+            let rel_path = source_path
+                .strip_prefix(root)
+                .unwrap_or(source_path)
+                .to_string_lossy()
+                .to_string();
+
+            let mut file_ctx =
+                extract_file_context(&rel_path, subsystem, &bog, &filter, kind_filter, tag_filter);
+            file_ctx.parse_errors = parse_errors
+                .iter()
+                .map(|e| {
+                    let (line, col) = diagnostic::line_col(&content, e.span().start);
+                    format!("{line}:{col}: {}", e.message())
+                })
+                .chain(inline_errors.iter().map(|e| format!("{rel_path} {e}")))
+                .collect();
+            Some(file_ctx)
+        })
+        .collect();
 
     files.sort_by(|a, b| a.path.cmp(&b.path));
 
@@ -303,7 +339,9 @@ fn extract_file_context(
     let mut pickled = Vec::new();
     let mut change_requests = Vec::new();
     let mut fn_contracts = Vec::new();
+    let mut item_contracts = Vec::new();
     let mut skim_observations = Vec::new();
+    let mut custom = Vec::new();
 
     for ann in &bog.annotations {
         match ann {
@@ -371,6 +409,15 @@ fn extract_file_context(
                     deps: f.deps.clone(),
                 });
             }
+            Annotation::Item(i) if filter.contracts => {
+                item_contracts.push(ItemContractOutput {
+                    kind: i.kind.to_string(),
+                    name: i.name.clone(),
+                    status: i.status.to_string(),
+                    description: i.description.clone(),
+                    invariants: i.invariants.clone(),
+                });
+            }
             Annotation::Skim(obs) if filter.skims => {
                 skim_observations.push(SkimObservationOutput {
                     skimsystem: obs.skimsystem.clone(),
@@ -379,6 +426,18 @@ fn extract_file_context(
                     target: obs.target.as_ref().map(format_skim_target),
                 });
             }
+            Annotation::Custom(c) if filter.custom => {
+                custom.push(CustomOutput {
+                    kind: c.kind.clone(),
+                    name: c.name.clone(),
+                    fields: c
+                        .fields
+                        .iter()
+                        .map(|(k, v)| (k.clone(), format_value(v)))
+                        .collect(),
+                    text: c.text.clone(),
+                });
+            }
             _ => {}
         }
     }
@@ -393,7 +452,10 @@ fn extract_file_context(
         pickled,
         change_requests,
         fn_contracts,
+        item_contracts,
         skim_observations,
+        custom,
+        parse_errors: Vec::new(),
     }
 }
 
@@ -406,6 +468,14 @@ fn format_target(v: &Value) -> String {
     }
 }
 
+/// Render a custom field value, without quotes around strings.
+fn format_value(v: &Value) -> String {
+    match v {
+        Value::String(s) => parser::unquote(s),
+        other => other.to_string(),
+    }
+}
+
 fn format_skim_target(t: &SkimTarget) -> String {
     match t {
         SkimTarget::File => "file".to_string(),
@@ -459,11 +529,14 @@ pub fn format_context_text(output: &ContextOutput) -> String {
             let file_name = file.path.rsplit('/').next().unwrap_or(&file.path);
             out.push_str(&format!("\n  {}\n", file_name.bold()));
 
+            format_parse_errors_section(&mut out, file);
             format_health_section(&mut out, file);
             format_pickled_section(&mut out, file);
             format_requests_section(&mut out, file);
             format_contracts_section(&mut out, file);
+            format_item_contracts_section(&mut out, file);
             format_skims_section(&mut out, file);
+            format_custom_section(&mut out, file);
         }
 
         out.push('\n');
@@ -472,6 +545,15 @@ pub fn format_context_text(output: &ContextOutput) -> String {
     out
 }
 
+fn format_parse_errors_section(out: &mut String, file: &FileContext) {
+    for err in &file.parse_errors {
+        out.push_str(&format!(
+            "    {} skipped unparseable annotation at {err}\n",
+            "[warn]".yellow()
+        ));
+    }
+}
+
 fn format_health_section(out: &mut String, file: &FileContext) {
     let Some(h) = &file.health else { return };
     let dims: Vec<String> = h
@@ -579,6 +661,24 @@ fn format_contracts_section(out: &mut String, file: &FileContext) {
     }
 }
 
+fn format_item_contracts_section(out: &mut String, file: &FileContext) {
+    if file.item_contracts.is_empty() {
+        return;
+    }
+    out.push_str(&format!(
+        "    {} ({})\n",
+        "[item contracts]".dimmed(),
+        file.item_contracts.len()
+    ));
+    for item in &file.item_contracts {
+        let dot = format_status_dot(&item.status);
+        out.push_str(&format!("      {} {} — {dot}\n", item.kind, item.name));
+        for inv in &item.invariants {
+            out.push_str(&format!("        invariant: {inv}\n"));
+        }
+    }
+}
+
 fn format_skims_section(out: &mut String, file: &FileContext) {
     if file.skim_observations.is_empty() {
         return;
@@ -603,23 +703,17 @@ fn format_skims_section(out: &mut String, file: &FileContext) {
     }
 }
 
-/// Discover all .bog sidecar files under root, skipping target/, .git/, and repo.bog.
-pub fn discover_bog_files(root: &Path) -> Vec<PathBuf> {
-    let pattern = root.join("**/*.bog");
-    let Ok(paths) = glob::glob(&pattern.to_string_lossy()) else {
-        return Vec::new();
-    };
-
-    paths
-        .flatten()
-        .filter(|p| {
-            let rel = p.strip_prefix(root).unwrap_or(p);
-            !rel.components().any(|c| {
-                matches!(c.as_os_str().to_str(), Some("target" | ".git"))
-            }) && !p
-                .file_name()
-                .map(|n| n == "repo.bog")
-                .unwrap_or(false)
-        })
-        .collect()
+fn format_custom_section(out: &mut String, file: &FileContext) {
+    for c in &file.custom {
+        let name_str = c.name.as_ref().map(|n| format!(" {n}")).unwrap_or_default();
+        out.push_str(&format!("    {}{name_str}\n", format!("[{}]", c.kind).dimmed()));
+        for (key, value) in &c.fields {
+            out.push_str(&format!("      {key}: {value}\n"));
+        }
+        if let Some(text) = &c.text {
+            for line in text.lines() {
+                out.push_str(&format!("      {line}\n"));
+            }
+        }
+    }
 }
@@ -0,0 +1,278 @@
+use crate::ast::{Annotation, BogFile, Contract, FnAnnotation, FnPath, Status};
+use crate::treesitter::{self, Symbol};
+
+/// One way a `#[fn]` contract disagrees with the signature in source.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ContractDrift {
+    #[error("parameter '{name}: {ty}' is not in the contract")]
+    ParamAdded { name: String, ty: String },
+
+    #[error("contract parameter '{name}' is no longer in the signature")]
+    ParamRemoved { name: String },
+
+    #[error("parameter '{from}' was renamed to '{to}'")]
+    ParamRenamed { from: String, to: String },
+
+    #[error("parameter '{name}' is '{actual}' in source but '{declared}' in the contract")]
+    ParamType {
+        name: String,
+        declared: String,
+        actual: String,
+    },
+
+    #[error("returns '{actual}' in source but the contract says '{declared}'")]
+    ReturnType { declared: String, actual: String },
+}
+
+/// Compare a contract with the signature of the function it describes. Types are
+/// compared after normalization (see `normalize_type`); the return type is only
+/// checked when the contract declares `out`.
+pub fn check(contract: &Contract, symbol: &Symbol) -> Vec<ContractDrift> {
+    let mut drift = Vec::new();
+
+    // `self` is implied by the impl, so a contract may list it or not
+    let declared: Vec<(String, &str)> = contract
+        .inputs
+        .iter()
+        .map(|(name, ty)| (normalize_name(name), ty.as_str()))
+        .filter(|(name, _)| name != "self")
+        .collect();
+    let actual: Vec<(String, &str)> = symbol
+        .params
+        .iter()
+        .map(|(name, ty)| (normalize_name(name), ty.as_str()))
+        .collect();
+
+    let is_declared = |name: &str| declared.iter().any(|(d, _)| d == name);
+    let is_actual = |name: &str| actual.iter().any(|(a, _)| a == name);
+
+    // A declared name gone from the signature, with an undeclared name in the same
+    // position, is a rename
+    let mut renamed = Vec::new();
+    for (i, (name, ty)) in declared.iter().enumerate() {
+        if is_actual(name) {
+            continue;
+        }
+        match actual.get(i) {
+            Some((new_name, new_ty)) if !is_declared(new_name) => {
+                drift.push(ContractDrift::ParamRenamed {
+                    from: name.clone(),
+                    to: new_name.clone(),
+                });
+                renamed.push(new_name.as_str());
+                check_type(new_name, ty, new_ty, &mut drift);
+            }
+            _ => drift.push(ContractDrift::ParamRemoved { name: name.clone() }),
+        }
+    }
+
+    for (name, ty) in &actual {
+        if renamed.contains(&name.as_str()) {
+            continue;
+        }
+        match declared.iter().find(|(d, _)| d == name) {
+            Some((_, declared_ty)) => check_type(name, declared_ty, ty, &mut drift),
+            None => drift.push(ContractDrift::ParamAdded {
+                name: name.clone(),
+                ty: ty.to_string(),
+            }),
+        }
+    }
+
+    if let Some(declared) = &contract.output {
+        let actual = symbol.return_type.as_deref().unwrap_or("()");
+        if normalize_type(declared) != normalize_type(actual) {
+            drift.push(ContractDrift::ReturnType {
+                declared: declared.clone(),
+                actual: actual.to_string(),
+            });
+        }
+    }
+
+    drift
+}
+
+/// Drift for a `#[fn]` annotation against the file's symbols. Annotations without
+/// a contract, or whose reference does not resolve to exactly one function, have
+/// none; the validator reports unresolved references separately.
+pub fn check_fn(f: &FnAnnotation, symbols: &[Symbol]) -> Vec<ContractDrift> {
+    let Some(contract) = &f.contract else {
+        return Vec::new();
+    };
+    let Ok(path) = FnPath::parse(&f.name) else {
+        return Vec::new();
+    };
+    match treesitter::resolve_fn(symbols, &path).as_slice() {
+        [symbol] => check(contract, symbol),
+        _ => Vec::new(),
+    }
+}
+
+/// The `contract_compliance` health of a sidecar: green when every contract matches
+/// its signature, red when more than half have drifted, yellow in between. A
+/// contract whose fn does not resolve to exactly one function counts as drifted.
+/// `None` for a file without contracts.
+pub fn compliance(bog: &BogFile, symbols: &[Symbol]) -> Option<Status> {
+    let mut total = 0;
+    let mut drifted = 0;
+    for ann in &bog.annotations {
+        if let Annotation::Fn(f) = ann
+            && let Some(contract) = &f.contract
+        {
+            total += 1;
+            let resolved = FnPath::parse(&f.name)
+                .map(|path| treesitter::resolve_fn(symbols, &path))
+                .unwrap_or_default();
+            match resolved.as_slice() {
+                [symbol] if check(contract, symbol).is_empty() => {}
+                _ => drifted += 1,
+            }
+        }
+    }
+    match total {
+        0 => None,
+        _ if drifted == 0 => Some(Status::Green),
+        _ if drifted * 2 > total => Some(Status::Red),
+        _ => Some(Status::Yellow),
+    }
+}
+
+/// Canonical form of a type for comparison: whitespace is dropped (a single space
+/// is kept between words, as in `&mut T` or `dyn Trait`) and paths are reduced to
+/// their last segment, so `std::path::PathBuf` and `PathBuf` compare equal.
+/// References, `mut` and lifetimes are significant: `&str` and `str` differ.
+pub fn normalize_type(ty: &str) -> String {
+    let mut out = String::new();
+    let mut word = String::new();
+    let mut spaced = false;
+
+    for c in ty.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' || (c == '\'' && word.is_empty()) {
+            if word.is_empty() && spaced && out.ends_with(is_word_char) {
+                out.push(' ');
+            }
+            word.push(c);
+            continue;
+        }
+        flush_word(&mut word, &mut out);
+        spaced = c.is_whitespace();
+        if !spaced {
+            out.push(c);
+        }
+    }
+    flush_word(&mut word, &mut out);
+    out
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn flush_word(word: &mut String, out: &mut String) {
+    out.push_str(word.rsplit("::").next().unwrap_or(word));
+    word.clear();
+}
+
+fn normalize_name(name: &str) -> String {
+    let name = name.trim();
+    let name = name.strip_prefix("mut ").unwrap_or(name);
+    name.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+fn check_type(name: &str, declared: &str, actual: &str, drift: &mut Vec<ContractDrift>) {
+    if normalize_type(declared) != normalize_type(actual) {
+        drift.push(ContractDrift::ParamType {
+            name: name.to_string(),
+            declared: declared.to_string(),
+            actual: actual.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(source: &str) -> Symbol {
+        treesitter::extract_symbols(source).unwrap().remove(0)
+    }
+
+    fn contract(inputs: &[(&str, &str)], output: Option<&str>) -> Contract {
+        Contract {
+            inputs: inputs
+                .iter()
+                .map(|(n, t)| (n.to_string(), t.to_string()))
+                .collect(),
+            output: output.map(str::to_string),
+            invariants: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_type() {
+        assert_eq!(normalize_type("&'a std::path::Path"), "&'a Path");
+        assert_eq!(normalize_type("&mut  Vec<crate::ast::Symbol>"), "&mut Vec<Symbol>");
+        assert_eq!(
+            normalize_type("Result< Vec<String>, std::io::Error >"),
+            "Result<Vec<String>,Error>"
+        );
+        assert_eq!(normalize_type("Box<dyn std::error::Error>"), "Box<dyn Error>");
+        assert_ne!(normalize_type("Option<&str>"), normalize_type("Option<str>"));
+        assert_ne!(normalize_type("String"), normalize_type("&mut String"));
+        assert_ne!(normalize_type("&'a T"), normalize_type("T"));
+        assert_ne!(normalize_type("&T"), normalize_type("&mut T"));
+    }
+
+    #[test]
+    fn test_matching_contract_has_no_drift() {
+        let s = symbol("fn load(root: &std::path::Path, mut limit: usize) -> Option<String> { None }");
+        let c = contract(&[("root", "&Path"), ("limit", "usize")], Some("Option<String>"));
+        assert!(check(&c, &s).is_empty());
+    }
+
+    #[test]
+    fn test_drift_is_reported() {
+        let s = symbol("fn load(path: &Path, limit: u64, verbose: bool) -> bool { true }");
+        let c = contract(
+            &[("root", "&Path"), ("cache", "Cache"), ("limit", "usize")],
+            Some("Option<String>"),
+        );
+        let drift = check(&c, &s);
+        assert_eq!(
+            drift,
+            [
+                ContractDrift::ParamRenamed {
+                    from: "root".to_string(),
+                    to: "path".to_string()
+                },
+                ContractDrift::ParamRemoved {
+                    name: "cache".to_string()
+                },
+                ContractDrift::ParamType {
+                    name: "limit".to_string(),
+                    declared: "usize".to_string(),
+                    actual: "u64".to_string()
+                },
+                ContractDrift::ParamAdded {
+                    name: "verbose".to_string(),
+                    ty: "bool".to_string()
+                },
+                ContractDrift::ReturnType {
+                    declared: "Option<String>".to_string(),
+                    actual: "bool".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unresolved_contract_is_not_compliant() {
+        let symbols = treesitter::extract_symbols("fn load(root: &Path) {}").unwrap();
+        let bog = crate::parser::parse_bog(
+            r#"#[fn(load) { status = green, contract = { in = [(root, "&Path")] } }]
+#[fn(gone) { status = green, contract = { in = [(root, "&Path")] } }]"#,
+        )
+        .unwrap();
+        assert_eq!(compliance(&bog, &symbols), Some(Status::Yellow));
+    }
+}
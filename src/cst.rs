@@ -0,0 +1,565 @@
+use std::fmt;
+
+use pest::iterators::Pair;
+use pest::Parser;
+
+use crate::ast::FnPath;
+use crate::diagnostic::Span;
+use crate::parser::{BogParser, ParseError, Rule};
+
+/// Kind of a leaf token in the concrete syntax tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Spaces, tabs and newlines between grammar elements
+    Whitespace,
+    /// A `//` line comment (without the trailing newline)
+    Comment,
+    /// Grammar literals: `#[`, `]`, `(`, `)`, `{`, `}`, `[`, `=`, `,`
+    Punct,
+    /// Text matched by an atomic grammar rule (ident, string_literal, freeform_text, ...)
+    Leaf(Rule),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxToken {
+    pub kind: TokenKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+/// A lossless syntax node: concatenating every token below it reproduces the source exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxNode {
+    pub kind: Rule,
+    pub children: Vec<SyntaxElement>,
+}
+
+/// Parse .bog text into a lossless concrete syntax tree rooted at `Rule::bog_file`.
+pub fn parse_cst(input: &str) -> Result<SyntaxNode, ParseError> {
+    let mut pairs = BogParser::parse(Rule::bog_file, input)?;
+    let bog_file = pairs.next().unwrap();
+    Ok(build_node(bog_file, input))
+}
+
+/// Parse text holding exactly one annotation into its syntax node.
+pub fn parse_annotation_cst(input: &str) -> Result<SyntaxNode, ParseError> {
+    let root = parse_cst(input)?;
+    let mut annotations = root.into_annotations();
+    match (annotations.next(), annotations.next()) {
+        (Some(node), None) => Ok(node),
+        _ => Err(ParseError::InvalidValue {
+            field: "annotation".to_string(),
+            message: "expected exactly one annotation".to_string(),
+            span: Span::new(0, input.len()),
+        }),
+    }
+}
+
+/// Parse `key = value` text into a `kv_pair` node.
+pub fn parse_kv_pair_cst(input: &str) -> Result<SyntaxNode, ParseError> {
+    let annotation = parse_annotation_cst(&format!("#[x {{ {input} }}]"))?;
+    let pair = annotation
+        .child(Rule::body)
+        .and_then(|b| b.child(Rule::body_content))
+        .and_then(|c| c.child(Rule::kv_list))
+        .and_then(|l| l.child(Rule::kv_pair));
+    pair.cloned().ok_or_else(|| ParseError::InvalidValue {
+        field: "kv_pair".to_string(),
+        message: "expected `key = value`".to_string(),
+        span: Span::new(0, input.len()),
+    })
+}
+
+fn build_node(pair: Pair<Rule>, input: &str) -> SyntaxNode {
+    let kind = pair.as_rule();
+    let span = pair.as_span();
+    let mut cursor = span.start();
+    let mut children = Vec::new();
+
+    for inner in pair.into_inner() {
+        if inner.as_rule() == Rule::EOI {
+            continue;
+        }
+        let inner_span = inner.as_span();
+        lex_gap(&input[cursor..inner_span.start()], &mut children);
+        if is_leaf_rule(inner.as_rule()) {
+            children.push(SyntaxElement::Token(SyntaxToken {
+                kind: TokenKind::Leaf(inner.as_rule()),
+                text: inner.as_str().to_string(),
+            }));
+        } else {
+            children.push(SyntaxElement::Node(build_node(inner, input)));
+        }
+        cursor = inner_span.end();
+    }
+    lex_gap(&input[cursor..span.end()], &mut children);
+
+    SyntaxNode { kind, children }
+}
+
+fn is_leaf_rule(rule: Rule) -> bool {
+    matches!(
+        rule,
+        Rule::ident
+            | Rule::ident_path
+            | Rule::string_literal
+            | Rule::status_literal
+            | Rule::bool_literal
+            | Rule::date_literal
+            | Rule::number_literal
+            | Rule::fn_keyword
+            | Rule::fn_path
+            | Rule::freeform_text
+    )
+}
+
+/// Split text between grammar elements into whitespace, comment and punctuation tokens.
+fn lex_gap(gap: &str, out: &mut Vec<SyntaxElement>) {
+    let mut rest = gap;
+    while !rest.is_empty() {
+        let (kind, len) = if rest.starts_with("//") {
+            (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len()))
+        } else if rest.starts_with(char::is_whitespace) {
+            let len = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            (TokenKind::Whitespace, len)
+        } else if rest.starts_with("#[") {
+            (TokenKind::Punct, 2)
+        } else {
+            (TokenKind::Punct, rest.chars().next().unwrap().len_utf8())
+        };
+        out.push(SyntaxElement::Token(SyntaxToken {
+            kind,
+            text: rest[..len].to_string(),
+        }));
+        rest = &rest[len..];
+    }
+}
+
+impl SyntaxNode {
+    /// Direct child nodes, skipping tokens.
+    pub fn child_nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|c| match c {
+            SyntaxElement::Node(n) => Some(n),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// First direct child node of the given kind.
+    pub fn child(&self, kind: Rule) -> Option<&SyntaxNode> {
+        self.child_nodes().find(|n| n.kind == kind)
+    }
+
+    /// Text of the first direct leaf token of the given kind.
+    pub fn leaf_text(&self, kind: Rule) -> Option<&str> {
+        self.children.iter().find_map(|c| match c {
+            SyntaxElement::Token(t) if t.kind == TokenKind::Leaf(kind) => Some(t.text.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Top-level annotation nodes of a `bog_file` root.
+    pub fn annotations(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.child_nodes().filter(|n| n.kind == Rule::annotation)
+    }
+
+    /// Mutable top-level annotation nodes of a `bog_file` root.
+    pub fn annotations_mut(&mut self) -> impl Iterator<Item = &mut SyntaxNode> {
+        self.children.iter_mut().filter_map(|c| match c {
+            SyntaxElement::Node(n) if n.kind == Rule::annotation => Some(n),
+            _ => None,
+        })
+    }
+
+    /// Top-level annotation nodes of a `bog_file` root with the 1-based first and
+    /// last line each one spans.
+    pub fn annotation_lines(&self) -> Vec<(&SyntaxNode, usize, usize)> {
+        let mut out = Vec::new();
+        let mut line = 1;
+        for child in &self.children {
+            let text = match child {
+                SyntaxElement::Node(n) => n.to_string(),
+                SyntaxElement::Token(t) => t.text.clone(),
+            };
+            let newlines = text.matches('\n').count();
+            if let SyntaxElement::Node(n) = child
+                && n.kind == Rule::annotation
+            {
+                out.push((n, line, line + newlines));
+            }
+            line += newlines;
+        }
+        out
+    }
+
+    fn into_annotations(self) -> impl Iterator<Item = SyntaxNode> {
+        self.children.into_iter().filter_map(|c| match c {
+            SyntaxElement::Node(n) if n.kind == Rule::annotation => Some(n),
+            _ => None,
+        })
+    }
+
+    /// The ident after `#[` of an annotation node (e.g. "fn", "file").
+    pub fn annotation_name(&self) -> Option<&str> {
+        if self.kind != Rule::annotation {
+            return None;
+        }
+        self.leaf_text(Rule::ident)
+    }
+
+    /// The name inside an annotation's parens, e.g. `parse_bog` in `#[fn(parse_bog)]`
+    /// or `Type::new` in `#[fn(Type::new)]`.
+    pub fn annotation_arg(&self) -> Option<&str> {
+        let content = self.child(Rule::parens)?.child(Rule::parens_content)?;
+        content
+            .leaf_text(Rule::ident)
+            .or_else(|| content.leaf_text(Rule::fn_path))
+    }
+
+    /// Whether this is a `#[fn]` annotation naming `path`, however its name is
+    /// spaced: `#[fn(Foo :: m)]` names `Foo::m`.
+    pub fn is_fn_annotation(&self, path: &FnPath) -> bool {
+        self.annotation_name() == Some("fn")
+            && self
+                .annotation_arg()
+                .is_some_and(|arg| FnPath::parse(arg).is_ok_and(|p| &p == path))
+    }
+
+    /// All comment texts anywhere below this node, in source order.
+    pub fn comments(&self) -> Vec<&str> {
+        let mut out = Vec::new();
+        self.collect_comments(&mut out);
+        out
+    }
+
+    fn collect_comments<'a>(&'a self, out: &mut Vec<&'a str>) {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(n) => n.collect_comments(out),
+                SyntaxElement::Token(t) if t.kind == TokenKind::Comment => out.push(&t.text),
+                SyntaxElement::Token(_) => {}
+            }
+        }
+    }
+
+    /// Append an annotation to a `bog_file` root, separated by a blank line.
+    /// `leading_comment` is written on its own line directly above it.
+    pub fn append_annotation(&mut self, annotation: SyntaxNode, leading_comment: Option<&str>) {
+        while let Some(SyntaxElement::Token(t)) = self.children.last() {
+            if t.kind != TokenKind::Whitespace {
+                break;
+            }
+            self.children.pop();
+        }
+        let separator = if self.children.is_empty() { "" } else { "\n\n" };
+        self.push_token(TokenKind::Whitespace, separator);
+        if let Some(comment) = leading_comment {
+            self.push_token(TokenKind::Comment, comment);
+            self.push_token(TokenKind::Whitespace, "\n");
+        }
+        self.children.push(SyntaxElement::Node(annotation));
+        self.push_token(TokenKind::Whitespace, "\n");
+    }
+
+    /// Set a field in the body of an annotation node, or in its parens when it has
+    /// no body (`#[file(owner = ..)]`): an existing `kv_pair` with the same key is
+    /// replaced in place, otherwise `pair` is inserted after the field named `after`
+    /// (or after the last field), with the same indentation. Returns false if the
+    /// annotation has no `key = value` list.
+    pub fn set_field(&mut self, pair: SyntaxNode, after: &str) -> bool {
+        let Some(key) = pair.leaf_text(Rule::ident).map(str::to_string) else {
+            return false;
+        };
+        let (outer, content) = match self.child(Rule::body) {
+            Some(_) => (Rule::body, Rule::body_content),
+            None => (Rule::parens, Rule::parens_content),
+        };
+        // Whitespace before the first pair lives in the body, outside the kv_list
+        let Some(body) = self.child_mut(outer) else {
+            return false;
+        };
+        let leading = body.children.iter().find_map(|c| match c {
+            SyntaxElement::Token(t) if t.kind == TokenKind::Whitespace => Some(t.text.clone()),
+            _ => None,
+        });
+        let Some(list) = body
+            .child_mut(content)
+            .and_then(|c| c.child_mut(Rule::kv_list))
+        else {
+            return false;
+        };
+
+        let key_of = |c: &SyntaxElement, key: &str| {
+            matches!(c, SyntaxElement::Node(n) if n.leaf_text(Rule::ident) == Some(key))
+        };
+        if let Some(existing) = list.children.iter_mut().find(|c| key_of(c, &key)) {
+            *existing = SyntaxElement::Node(pair);
+            return true;
+        }
+
+        let anchor = list
+            .children
+            .iter()
+            .position(|c| key_of(c, after))
+            .or_else(|| {
+                list.children
+                    .iter()
+                    .rposition(|c| matches!(c, SyntaxElement::Node(_)))
+            })
+            .unwrap_or(0);
+        // Indent like the anchor: the whitespace token right before it, if any
+        let indent = match anchor.checked_sub(1).map(|i| &list.children[i]) {
+            Some(SyntaxElement::Token(t)) if t.kind == TokenKind::Whitespace => t.text.clone(),
+            _ => leading.unwrap_or_else(|| " ".to_string()),
+        };
+        let inserted = [
+            SyntaxElement::Token(SyntaxToken {
+                kind: TokenKind::Punct,
+                text: ",".to_string(),
+            }),
+            SyntaxElement::Token(SyntaxToken {
+                kind: TokenKind::Whitespace,
+                text: indent,
+            }),
+            SyntaxElement::Node(pair),
+        ];
+        list.children.splice(anchor + 1..anchor + 1, inserted);
+        true
+    }
+
+    /// Replace the name inside an annotation's parens, e.g. `#[fn(old)]` to
+    /// `#[fn(new)]`. Returns false if there are no parens or `arg` is not a name.
+    pub fn set_annotation_arg(&mut self, arg: &str) -> bool {
+        let Ok(parsed) = parse_annotation_cst(&format!("#[x({arg})]")) else {
+            return false;
+        };
+        let Some(parens) = parsed.child(Rule::parens).cloned() else {
+            return false;
+        };
+        if self.annotation_arg().is_none() {
+            return false;
+        }
+        match self.child_mut(Rule::parens) {
+            Some(existing) => {
+                *existing = parens;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn child_mut(&mut self, kind: Rule) -> Option<&mut SyntaxNode> {
+        self.children.iter_mut().find_map(|c| match c {
+            SyntaxElement::Node(n) if n.kind == kind => Some(n),
+            _ => None,
+        })
+    }
+
+    /// Remove the `marker` comment line of a `bog_file` root together with the run of
+    /// annotations directly after it that satisfy `in_section`. Other comments in the
+    /// run are kept. Returns false if the marker is not present.
+    pub fn remove_marked_section(
+        &mut self,
+        marker: &str,
+        in_section: impl Fn(&SyntaxNode) -> bool,
+    ) -> bool {
+        let Some(start) = self.children.iter().position(|c| {
+            matches!(c, SyntaxElement::Token(t) if t.kind == TokenKind::Comment && t.text.trim_end() == marker)
+        }) else {
+            return false;
+        };
+
+        // Walk forward over the section, remembering the last annotation that belongs to it
+        let mut end = start;
+        for (i, child) in self.children.iter().enumerate().skip(start + 1) {
+            match child {
+                SyntaxElement::Node(n) if in_section(n) => end = i,
+                SyntaxElement::Node(_) => break,
+                SyntaxElement::Token(_) => {}
+            }
+        }
+
+        let mut kept = Vec::new();
+        for child in self.children.drain(start..=end) {
+            if let SyntaxElement::Token(t) = &child
+                && t.kind == TokenKind::Comment
+                && t.text.trim_end() != marker
+            {
+                kept.push(child);
+                kept.push(SyntaxElement::Token(SyntaxToken {
+                    kind: TokenKind::Whitespace,
+                    text: "\n".to_string(),
+                }));
+            }
+        }
+        self.children.splice(start..start, kept);
+        true
+    }
+
+    fn push_token(&mut self, kind: TokenKind, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.children.push(SyntaxElement::Token(SyntaxToken {
+            kind,
+            text: text.to_string(),
+        }));
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(n) => write!(f, "{n}")?,
+                SyntaxElement::Token(t) => f.write_str(&t.text)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"// header comment
+#[file(
+  owner = "core-agent",   // trailing
+  subsystem = "core",
+  updated = "2026-02-24",
+  status = green
+)]
+
+#[description {
+  Some text // not a comment, just prose
+}]
+
+#[fn(parse_bog) {
+  status = green,
+  // contract pending
+  deps = [a::b, c],
+  contract = { in = [(input, str)], out = "String" }
+}]
+// trailing comment
+"#;
+
+    #[test]
+    fn test_cst_roundtrip_is_lossless() {
+        let cst = parse_cst(SAMPLE).unwrap();
+        assert_eq!(cst.to_string(), SAMPLE);
+    }
+
+    #[test]
+    fn test_cst_keeps_comments() {
+        let cst = parse_cst(SAMPLE).unwrap();
+        let comments = cst.comments();
+        assert_eq!(
+            comments,
+            vec![
+                "// header comment",
+                "// trailing",
+                "// contract pending",
+                "// trailing comment"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cst_annotation_accessors() {
+        let cst = parse_cst(SAMPLE).unwrap();
+        let names: Vec<&str> = cst.annotations().filter_map(|a| a.annotation_name()).collect();
+        assert_eq!(names, vec!["file", "description", "fn"]);
+        let fn_node = cst.annotations().nth(2).unwrap();
+        assert_eq!(fn_node.annotation_arg(), Some("parse_bog"));
+    }
+
+    #[test]
+    fn test_append_annotation_preserves_existing_text() {
+        let mut cst = parse_cst(SAMPLE).unwrap();
+        let node = parse_annotation_cst("#[fn(extra) {\n  status = yellow\n}]").unwrap();
+        cst.append_annotation(node, Some("// appended"));
+        let text = cst.to_string();
+        assert!(text.starts_with(SAMPLE.trim_end()));
+        assert!(text.ends_with("\n\n// appended\n#[fn(extra) {\n  status = yellow\n}]\n"));
+        assert!(crate::parser::parse_bog(&text).is_ok());
+    }
+
+    #[test]
+    fn test_remove_marked_section_keeps_following_annotations() {
+        let input = r#"#[file(owner = "a", subsystem = "s", updated = "2026-01-01", status = green)]
+
+// [integration:sk:clippy]
+#[skim(sk) {
+  status = yellow
+}]
+
+#[fn(kept) {
+  status = green
+}]
+"#;
+        let mut cst = parse_cst(input).unwrap();
+        let removed = cst.remove_marked_section("// [integration:sk:clippy]", |n| {
+            n.annotation_name() == Some("skim")
+        });
+        assert!(removed);
+        let text = cst.to_string();
+        assert!(!text.contains("integration:sk"));
+        assert!(!text.contains("#[skim"));
+        assert!(text.contains("#[fn(kept)"));
+        assert!(crate::parser::parse_bog(&text).is_ok());
+    }
+
+    #[test]
+    fn test_set_field_replaces_or_appends() {
+        let mut cst = parse_cst(SAMPLE).unwrap();
+        let fn_node = cst.annotations_mut().nth(2).unwrap();
+        assert!(fn_node.set_field(parse_kv_pair_cst("deps = [d]").unwrap(), "status"));
+        assert!(fn_node.set_field(parse_kv_pair_cst("refs = [e]").unwrap(), "deps"));
+        assert!(fn_node.set_field(parse_kv_pair_cst("stub = true").unwrap(), "missing"));
+        let text = cst.to_string();
+        assert!(text.contains("  // contract pending\n  deps = [d],\n  refs = [e],\n"));
+        assert!(text.contains("out = \"String\" },\n  stub = true\n}]"));
+
+        let mut single = parse_annotation_cst("#[fn(x) { status = green, }]").unwrap();
+        assert!(single.set_field(parse_kv_pair_cst("deps = []").unwrap(), "status"));
+        assert_eq!(single.to_string(), "#[fn(x) { status = green, deps = [], }]");
+    }
+
+    #[test]
+    fn test_set_file_field_and_annotation_arg() {
+        let mut cst = parse_cst(SAMPLE).unwrap();
+        let file = cst.annotations_mut().next().unwrap();
+        assert!(file.set_field(parse_kv_pair_cst("owner = \"auth-agent\"").unwrap(), "owner"));
+        assert!(!file.set_annotation_arg("renamed"));
+        let fn_node = cst.annotations_mut().nth(2).unwrap();
+        assert!(fn_node.set_annotation_arg("Parser::parse"));
+        assert!(!fn_node.set_annotation_arg("not a name"));
+        let text = cst.to_string();
+        assert!(text.contains("  owner = \"auth-agent\",   // trailing\n"));
+        assert!(text.contains("#[fn(Parser::parse) {\n  status = green,"));
+        assert_eq!(cst.annotations().nth(2).unwrap().annotation_arg(), Some("Parser::parse"));
+    }
+
+    #[test]
+    fn test_annotation_lines() {
+        let cst = parse_cst(SAMPLE).unwrap();
+        let lines: Vec<(&str, usize, usize)> = cst
+            .annotation_lines()
+            .into_iter()
+            .map(|(n, first, last)| (n.annotation_name().unwrap(), first, last))
+            .collect();
+        assert_eq!(lines, [("file", 2, 7), ("description", 9, 11), ("fn", 13, 18)]);
+    }
+
+    #[test]
+    fn test_parse_annotation_cst_rejects_multiple() {
+        assert!(parse_annotation_cst("#[description { a }]\n#[description { b }]").is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use std::path::Path;
+
+use crate::ast::{Annotation, FnAnnotation, FnPath};
+use crate::config;
+use crate::cst;
+use crate::lang::LanguageBackend;
+use crate::parser::{self, ParseError};
+use crate::treesitter::{self, Symbol};
+use crate::writer;
+
+/// Calls that never need to be listed as deps, as glob patterns over the call path.
+pub const DEFAULT_IGNORE: &[&str] = &[
+    "std::*",
+    "core::*",
+    "alloc::*",
+    "*::new",
+    "*::default",
+    "*::from",
+    "*::with_capacity",
+];
+
+/// Patterns for calls that deps are not expected to list.
+#[derive(Debug, Clone)]
+pub struct DepIgnore {
+    patterns: Vec<glob::Pattern>,
+}
+
+impl DepIgnore {
+    /// Build from glob patterns; invalid patterns are skipped.
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        DepIgnore {
+            patterns: patterns
+                .iter()
+                .filter_map(|p| glob::Pattern::new(p.as_ref()).ok())
+                .collect(),
+        }
+    }
+
+    pub fn matches(&self, call: &str) -> bool {
+        let call = strip_local_prefix(call);
+        self.patterns.iter().any(|p| p.matches(call))
+    }
+}
+
+impl Default for DepIgnore {
+    fn default() -> Self {
+        DepIgnore::new(DEFAULT_IGNORE)
+    }
+}
+
+/// The ignore list from `[deps]` in the project's bog.toml, or the defaults.
+pub fn load_ignore(root: &Path) -> DepIgnore {
+    config::load_config(&root.join("bog.toml"))
+        .map(|c| DepIgnore::new(&c.deps.ignore))
+        .unwrap_or_default()
+}
+
+/// Differences between a fn's declared deps and the calls in its body.
+#[derive(Debug, Default, PartialEq)]
+pub struct DepDrift {
+    /// Declared deps the function no longer calls
+    pub stale: Vec<String>,
+    /// Calls not covered by any declared dep or ignore pattern
+    pub missing: Vec<String>,
+}
+
+impl DepDrift {
+    pub fn is_empty(&self) -> bool {
+        self.stale.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Compare declared deps with the calls tree-sitter found in the function body.
+/// Enum variant and tuple struct constructors (`Value::Ident(..)`) are not calls.
+pub fn check(deps: &[String], symbol: &Symbol, ignore: &DepIgnore) -> DepDrift {
+    let calls: Vec<&String> = symbol
+        .calls
+        .iter()
+        .filter(|c| !ignore.matches(c) && !is_constructor(c))
+        .collect();
+    DepDrift {
+        stale: deps
+            .iter()
+            .filter(|d| !symbol.calls.iter().any(|c| same_target(d, c)))
+            .cloned()
+            .collect(),
+        missing: calls
+            .into_iter()
+            .filter(|c| !deps.iter().any(|d| same_target(d, c)))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Dep drift for a `#[fn]` annotation; empty when the reference does not resolve
+/// to exactly one function.
+pub fn check_fn(f: &FnAnnotation, symbols: &[Symbol], ignore: &DepIgnore) -> DepDrift {
+    let Ok(path) = FnPath::parse(&f.name) else {
+        return DepDrift::default();
+    };
+    match treesitter::resolve_fn(symbols, &path).as_slice() {
+        [symbol] => check(&f.deps, symbol, ignore),
+        _ => DepDrift::default(),
+    }
+}
+
+/// The deps list `--fix` writes: declared deps still called, in their original
+/// spelling, followed by the missing calls without any `crate::` prefix. A stale dep
+/// is only dropped if it names a free function in `symbols`; anything else may be
+/// reached by a method call, which is not extracted, so it is kept.
+pub fn fixed_deps(deps: &[String], drift: &DepDrift, symbols: &[Symbol]) -> Vec<String> {
+    deps.iter()
+        .filter(|d| !drift.stale.contains(d) || !is_free_fn(d, symbols))
+        .cloned()
+        .chain(drift.missing.iter().map(|c| strip_local_prefix(c).to_string()))
+        .collect()
+}
+
+/// What `fix_sidecar` did to one sidecar.
+#[derive(Debug, Default)]
+pub struct DepsFix {
+    /// The sidecar text with the rewritten deps lists
+    pub text: String,
+    /// Functions whose deps were rewritten
+    pub fixed: Vec<String>,
+    /// Drifted functions whose deps could not be rewritten, with the reason
+    pub unfixed: Vec<(String, String)>,
+}
+
+/// Rewrite the deps of every drifted `#[fn]` in a sidecar so they match the calls
+/// in `source`, as read by `backend`. Comments and layout outside the rewritten
+/// lists are kept. A drifted fn whose deps cannot be rewritten is left as it is
+/// and reported in `unfixed`.
+///
+/// # Errors
+///
+/// Returns the parse error if the sidecar is not valid .bog.
+pub fn fix_sidecar(
+    bog_text: &str,
+    source: &str,
+    backend: &dyn LanguageBackend,
+    ignore: &DepIgnore,
+) -> Result<DepsFix, ParseError> {
+    let mut tree = cst::parse_cst(bog_text)?;
+    let bog = parser::parse_bog(bog_text)?;
+    let Ok(symbols) = backend.extract_symbols(source) else {
+        return Ok(DepsFix {
+            text: bog_text.to_string(),
+            ..DepsFix::default()
+        });
+    };
+
+    let mut fix = DepsFix::default();
+    for ann in &bog.annotations {
+        let Annotation::Fn(f) = ann else {
+            continue;
+        };
+        let drift = check_fn(f, &symbols, ignore);
+        if drift.is_empty() {
+            continue;
+        }
+        let fixed = fixed_deps(&f.deps, &drift, &symbols);
+        // Only stale deps that may still be called: nothing to rewrite
+        if fixed == f.deps {
+            continue;
+        }
+        let deps: Vec<String> = fixed.iter().map(|d| writer::path_or_quoted(d)).collect();
+        let list = format!("deps = [{}]", deps.join(", "));
+        // A list that still does not parse leaves this fn alone, not the whole file
+        let Ok(pair) = cst::parse_kv_pair_cst(&list) else {
+            fix.unfixed.push((f.name.clone(), format!("`{list}` does not parse")));
+            continue;
+        };
+        // check_fn found drift, so the name parses
+        let Ok(path) = FnPath::parse(&f.name) else {
+            continue;
+        };
+        let Some(node) = tree.annotations_mut().find(|n| n.is_fn_annotation(&path)) else {
+            fix.unfixed.push((f.name.clone(), "no #[fn] node to rewrite".to_string()));
+            continue;
+        };
+        if node.set_field(pair, "status") {
+            fix.fixed.push(f.name.clone());
+        }
+    }
+
+    fix.text = tree.to_string();
+    Ok(fix)
+}
+
+/// Whether a dep and a call name the same function. Either may be written with
+/// more of the path than the other (`parser::parse_bog` vs an imported `parse_bog`).
+fn same_target(dep: &str, call: &str) -> bool {
+    let dep = strip_local_prefix(dep);
+    let call = strip_local_prefix(call);
+    dep == call
+        || dep.ends_with(&format!("::{call}"))
+        || call.ends_with(&format!("::{dep}"))
+}
+
+/// Whether a dep resolves to exactly one free function, which can only be called
+/// by the plain and path calls that are extracted.
+fn is_free_fn(dep: &str, symbols: &[Symbol]) -> bool {
+    let Ok(path) = FnPath::parse(strip_local_prefix(dep)) else {
+        return false;
+    };
+    matches!(
+        treesitter::resolve_fn(symbols, &path).as_slice(),
+        [s] if s.impl_type.is_none() && s.impl_trait.is_none()
+    )
+}
+
+fn is_constructor(call: &str) -> bool {
+    call.rsplit("::")
+        .next()
+        .is_some_and(|last| last.starts_with(|c: char| c.is_ascii_uppercase()))
+}
+
+fn strip_local_prefix(path: &str) -> &str {
+    let mut path = path;
+    for prefix in ["crate::", "self::", "super::"] {
+        while let Some(rest) = path.strip_prefix(prefix) {
+            path = rest;
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::rust::RUST;
+
+    const SOURCE: &str = r#"
+fn login(name: &str) -> bool {
+    let user = db::get_user(name);
+    let hash = crate::crypto::hash(name);
+    let _ = String::new();
+    let _ = Token::Bearer(name);
+    std::fs::read_to_string("x").ok();
+    audit(user, hash)
+}
+
+fn refresh() {}
+"#;
+
+    fn symbol() -> Symbol {
+        treesitter::extract_symbols(SOURCE).unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_stale_and_missing_deps() {
+        let deps: Vec<String> =
+            ["db::get_user", "cache::put", "hash", "refresh"].map(String::from).to_vec();
+        let symbols = treesitter::extract_symbols(SOURCE).unwrap();
+        let drift = check(&deps, &symbols[0], &DepIgnore::default());
+        assert_eq!(drift.stale, ["cache::put", "refresh"]);
+        assert_eq!(drift.missing, ["audit"]);
+        // cache::put is not in this file, so it may be a method the body calls
+        assert_eq!(
+            fixed_deps(&deps, &drift, &symbols),
+            ["db::get_user", "cache::put", "hash", "audit"]
+        );
+    }
+
+    #[test]
+    fn test_ignore_patterns_are_configurable() {
+        let ignore = DepIgnore::new(&["audit", "db::*"]);
+        let drift = check(&[], &symbol(), &ignore);
+        assert_eq!(drift.missing, ["crate::crypto::hash", "std::fs::read_to_string"]);
+    }
+
+    #[test]
+    fn test_fix_sidecar_rewrites_only_deps() {
+        let bog = r#"// auth sidecar
+#[fn(login) {
+  status = green,
+  // who we call
+  deps = [db::get_user, refresh],
+  description = "Logs in"
+}]
+
+#[fn(logout) { status = green }]
+"#;
+        let DepsFix { text, fixed, .. } =
+            fix_sidecar(bog, SOURCE, &RUST, &DepIgnore::default()).unwrap();
+        assert_eq!(fixed, ["login"]);
+        assert!(text.contains("// who we call\n  deps = [db::get_user, audit, crypto::hash],"));
+        assert!(text.starts_with("// auth sidecar\n"));
+        assert!(text.ends_with("#[fn(logout) { status = green }]\n"));
+    }
+
+    #[test]
+    fn test_fix_sidecar_quotes_qualified_trait_calls() {
+        let source = "fn run() {\n    <Foo as Bar>::baz();\n    helper();\n}\n";
+        let bog = "#[fn(run) { status = green, deps = [gone] }]\n";
+        let ignore = DepIgnore::default();
+        let DepsFix { text, fixed, .. } = fix_sidecar(bog, source, &RUST, &ignore).unwrap();
+        assert_eq!(fixed, ["run"]);
+        assert!(text.contains(r#"deps = [gone, "<Foo as Bar>::baz", helper]"#), "{text}");
+
+        // The quoted dep is read back as the call it names, so nothing is left to fix
+        let fix = fix_sidecar(&text, source, &RUST, &ignore).unwrap();
+        assert!(fix.fixed.is_empty());
+    }
+
+    #[test]
+    fn test_fix_sidecar_adds_missing_deps_field() {
+        let bog = "#[fn(login) {\n  status = green,\n  description = \"Logs in\"\n}]\n";
+        let text = fix_sidecar(bog, SOURCE, &RUST, &DepIgnore::default()).unwrap().text;
+        assert_eq!(
+            text,
+            "#[fn(login) {\n  status = green,\n  deps = [audit, crypto::hash, db::get_user],\n  description = \"Logs in\"\n}]\n"
+        );
+    }
+
+    #[test]
+    fn test_fix_sidecar_matches_non_canonical_fn_names() {
+        let source = "struct Foo;\nimpl Bar for Foo {\n    fn m(&self) {\n        helper();\n    }\n}\n";
+        let bog = "#[fn(< Foo  as Bar >::m) { status = green, deps = [gone] }]\n";
+        let fix = fix_sidecar(bog, source, &RUST, &DepIgnore::default()).unwrap();
+        assert_eq!(fix.fixed, ["<Foo as Bar>::m"]);
+        assert!(fix.unfixed.is_empty());
+        assert!(fix.text.contains("deps = [gone, helper]"), "{}", fix.text);
+    }
+
+    #[test]
+    fn test_fix_sidecar_keeps_deps_reached_by_method_calls() {
+        let source = "struct Store;\nimpl Store {\n    fn flush(&self) {}\n}\n\n\
+                      fn unused() {}\n\nfn sync(store: &Store) {\n    store.flush();\n}\n";
+        let bog = "#[fn(sync) { status = green, deps = [Store::flush, unused] }]\n";
+        let ignore = DepIgnore::default();
+        let fix = fix_sidecar(bog, source, &RUST, &ignore).unwrap();
+        assert_eq!(fix.fixed, ["sync"]);
+        assert!(fix.text.contains("deps = [Store::flush]"), "{}", fix.text);
+
+        // Store::flush is still stale, but there is nothing more --fix may remove
+        let again = fix_sidecar(&fix.text, source, &RUST, &ignore).unwrap();
+        assert!(again.fixed.is_empty());
+        assert_eq!(again.text, fix.text);
+    }
+}
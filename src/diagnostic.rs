@@ -0,0 +1,110 @@
+use std::fmt::Write;
+
+/// A byte range into the source text of a .bog file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// The same span moved `offset` bytes later.
+    pub fn shifted(self, offset: usize) -> Self {
+        Self::new(self.start + offset, self.end + offset)
+    }
+}
+
+impl From<pest::Span<'_>> for Span {
+    fn from(span: pest::Span<'_>) -> Self {
+        Self::new(span.start(), span.end())
+    }
+}
+
+/// 1-based (line, column) of a byte offset. Columns count characters, not bytes.
+pub fn line_col(source: &str, offset: usize) -> (usize, usize) {
+    let offset = floor_char_boundary(source, offset);
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let col = source[line_start..offset].chars().count() + 1;
+    (line, col)
+}
+
+/// Render a rustc-style snippet pointing at `span`:
+///
+/// ```text
+///   --> src/foo.rs.bog:3:12
+///    |
+///  3 |   status = gren,
+///    |            ^^^^
+/// ```
+///
+/// Spans covering several lines are underlined to the end of their first line.
+pub fn render_snippet(source: &str, path: &str, span: Span) -> String {
+    let (line, col) = line_col(source, span.start);
+    let line_text = source.lines().nth(line - 1).unwrap_or("");
+    let start = floor_char_boundary(source, span.start);
+    let end = floor_char_boundary(source, span.end.max(span.start));
+    let underline = source[start..end]
+        .lines()
+        .next()
+        .map_or(0, |s| s.trim_end().chars().count())
+        .max(1);
+
+    let gutter = " ".repeat(line.to_string().len());
+    let mut out = String::new();
+    let _ = writeln!(out, "{gutter}--> {path}:{line}:{col}");
+    let _ = writeln!(out, "{gutter} |");
+    let _ = writeln!(out, "{line} | {line_text}");
+    let _ = writeln!(
+        out,
+        "{gutter} | {}{}",
+        " ".repeat(col - 1),
+        "^".repeat(underline)
+    );
+    out
+}
+
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_col() {
+        let src = "ab\ncdé\nf";
+        assert_eq!(line_col(src, 0), (1, 1));
+        assert_eq!(line_col(src, 3), (2, 1));
+        assert_eq!(line_col(src, 8), (3, 1));
+        assert_eq!(line_col(src, 100), (3, 2));
+    }
+
+    #[test]
+    fn test_render_snippet_points_at_span() {
+        let src = "#[fn(a) {\n  status = gren\n}]\n";
+        let start = src.find("gren").unwrap();
+        let snippet = render_snippet(src, "a.rs.bog", Span::new(start, start + 4));
+        assert_eq!(
+            snippet,
+            " --> a.rs.bog:2:12\n  |\n2 |   status = gren\n  |            ^^^^\n"
+        );
+    }
+
+    #[test]
+    fn test_render_snippet_multiline_span_underlines_first_line() {
+        let src = "#[fn(a) {\n  status = green\n}]";
+        let snippet = render_snippet(src, "x", Span::new(0, src.len()));
+        assert!(snippet.ends_with("1 | #[fn(a) {\n  | ^^^^^^^^^\n"));
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::BogFile;
+use crate::parser;
+use crate::walk::Walker;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    #[error("Failed to read {0}: {1}")]
+    Read(String, String),
+
+    #[error("Parse error in {0}: {1}")]
+    Parse(String, String),
+
+    #[error("Unknown export format '{0}' (expected json, yaml or toml)")]
+    UnknownFormat(String),
+
+    #[error("Serialization failed: {0}")]
+    Serialize(String),
+
+    #[error("Invalid import document: {0}")]
+    InvalidImport(String),
+
+    #[error("Refusing to write '{0}': paths must be relative .bog files inside the project")]
+    UnsafePath(String),
+
+    #[error("Failed to write {0}: {1}")]
+    Write(String, String),
+}
+
+/// Every parsed .bog file of a project, keyed by path relative to the root.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProjectExport {
+    pub bog_version: String,
+    pub files: BTreeMap<String, BogFile>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = ExportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ExportFormat::Json),
+            "yaml" | "yml" => Ok(ExportFormat::Yaml),
+            "toml" => Ok(ExportFormat::Toml),
+            other => Err(ExportError::UnknownFormat(other.to_string())),
+        }
+    }
+}
+
+/// Parse repo.bog and every sidecar under `root`.
+///
+/// # Errors
+///
+/// Fails on the first file that cannot be read or parsed, naming that file.
+pub fn export_project(root: &Path) -> Result<ProjectExport, ExportError> {
+    let mut files = BTreeMap::new();
+
+    for path in Walker::new(root).files_with_suffix(".bog") {
+        let rel = path.strip_prefix(root).unwrap_or(&path);
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| ExportError::Read(rel.clone(), e.to_string()))?;
+        let bog = parser::parse_bog(&content)
+            .map_err(|e| ExportError::Parse(rel.clone(), e.message()))?;
+        files.insert(rel, bog);
+    }
+
+    Ok(ProjectExport {
+        bog_version: env!("CARGO_PKG_VERSION").to_string(),
+        files,
+    })
+}
+
+/// Serialize an export in the requested format.
+///
+/// # Errors
+///
+/// Returns `ExportError::Serialize` if the serializer rejects the document.
+pub fn render(export: &ProjectExport, format: ExportFormat) -> Result<String, ExportError> {
+    let result = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(export).map_err(|e| e.to_string()),
+        ExportFormat::Yaml => serde_yaml::to_string(export).map_err(|e| e.to_string()),
+        ExportFormat::Toml => toml::to_string_pretty(export).map_err(|e| e.to_string()),
+    };
+    result.map_err(ExportError::Serialize)
+}
+
+/// Write every file of a JSON export back to disk as .bog text. Existing files are
+/// replaced, so comments in them are not preserved. Returns the paths written.
+///
+/// # Errors
+///
+/// Rejects the whole document before writing anything if it does not deserialize or
+/// names a path outside the project.
+pub fn import_project(root: &Path, json: &str) -> Result<Vec<PathBuf>, ExportError> {
+    let export: ProjectExport =
+        serde_json::from_str(json).map_err(|e| ExportError::InvalidImport(e.to_string()))?;
+
+    for rel in export.files.keys() {
+        if !is_safe_relative(rel) {
+            return Err(ExportError::UnsafePath(rel.clone()));
+        }
+    }
+
+    let mut written = Vec::new();
+    for (rel, bog) in &export.files {
+        let path = root.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| ExportError::Write(rel.clone(), e.to_string()))?;
+        }
+        std::fs::write(&path, bog.to_string())
+            .map_err(|e| ExportError::Write(rel.clone(), e.to_string()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn is_safe_relative(rel: &str) -> bool {
+    let path = Path::new(rel);
+    rel.ends_with(".bog") && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ProjectExport {
+        let repo = parser::parse_bog(
+            r#"#[repo(name = "demo", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "a", files = ["src/*.rs"], status = green }]
+#[policies { max_fn_lines = 50, require_contracts = true }]"#,
+        )
+        .unwrap();
+        let sidecar = parser::parse_bog(
+            r#"#[file(owner = "a", subsystem = "core", updated = "2026-01-01", status = green)]
+#[health(test_coverage = green, staleness = yellow)]
+#[fn(login) {
+  status = green,
+  deps = [db::get],
+  contract = { in = [(name, "&str")], out = "bool" },
+  description = "Says \"hi\""
+}]
+#[skimsystem(q) {
+  owner = "q", targets = all, status = green,
+  integrations = { clippy = { command = "cargo clippy", format = cargo_diagnostic } }
+}]
+#[change_requests {
+  #[request(id = "c1", from = "q", target = fn(login), type = lint, status = pending, created = "2026-01-01", description = "d")]
+}]"#,
+        )
+        .unwrap();
+        ProjectExport {
+            bog_version: "0.1.0".to_string(),
+            files: BTreeMap::from([
+                ("repo.bog".to_string(), repo),
+                ("src/lib.rs.bog".to_string(), sidecar),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let export = sample();
+        let json = render(&export, ExportFormat::Json).unwrap();
+        let back: ProjectExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, export);
+        assert!(json.contains("\"kind\": \"fn\""));
+    }
+
+    #[test]
+    fn test_yaml_and_toml_roundtrip() {
+        let export = sample();
+        let yaml = render(&export, ExportFormat::Yaml).unwrap();
+        let back: ProjectExport = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(back, export);
+
+        let toml_text = render(&export, ExportFormat::Toml).unwrap();
+        let back: ProjectExport = toml::from_str(&toml_text).unwrap();
+        assert_eq!(back, export);
+    }
+
+    #[test]
+    fn test_render_is_deterministic() {
+        let a = render(&sample(), ExportFormat::Json).unwrap();
+        let b = render(&sample(), ExportFormat::Json).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_import_rejects_paths_outside_project() {
+        let root = std::env::temp_dir();
+        let json = r#"{"bog_version": "0.1.0", "files": {"../evil.bog": {"annotations": []}}}"#;
+        assert!(matches!(
+            import_project(&root, json),
+            Err(ExportError::UnsafePath(_))
+        ));
+        let json = r#"{"bog_version": "0.1.0", "files": {"src/main.rs": {"annotations": []}}}"#;
+        assert!(matches!(
+            import_project(&root, json),
+            Err(ExportError::UnsafePath(_))
+        ));
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("yml".parse::<ExportFormat>().unwrap(), ExportFormat::Yaml);
+        assert!("xml".parse::<ExportFormat>().is_err());
+    }
+}
@@ -0,0 +1,545 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::ast::{Annotation, BogFile, FnAnnotation, FnPath};
+use crate::changes::{ChangeSet, relative_to};
+use crate::contract;
+use crate::cst;
+use crate::deps::{self, DepIgnore};
+use crate::include;
+use crate::inline;
+use crate::lang::{self, LanguageBackend};
+use crate::parser::{self, ParseError};
+use crate::treesitter::{self, Symbol};
+use crate::writer;
+
+/// Renames scoring at least this are suggested by `bog validate`.
+pub const SUGGEST_SCORE: f64 = 0.5;
+
+/// Renames scoring at least this are applied by `--fix`, provided they rest on more
+/// than one signal and no other candidate comes within `CONFIDENT_MARGIN`.
+pub const CONFIDENT_SCORE: f64 = 0.8;
+
+const CONFIDENT_MARGIN: f64 = 0.2;
+
+/// A `#[fn]` reference that no longer resolves, and the unannotated function it
+/// most likely became.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rename {
+    /// The reference as written in `#[fn(...)]`
+    pub from: String,
+    /// The reference to write instead
+    pub to: String,
+    /// Weighted similarity, from 0 to 1
+    pub score: f64,
+    /// Safe for `--fix` to apply
+    pub confident: bool,
+}
+
+/// Propose renames for the `#[fn]` annotations of `bog` that match no function in
+/// `symbols`. Candidates are the functions no annotation refers to, scored on the
+/// contract's params and return type, the declared deps against the body's calls,
+/// and whether they sit between the functions annotated before and after in the
+/// sidecar. A candidate is proposed for at most one annotation.
+pub fn propose_renames(bog: &BogFile, symbols: &[Symbol], ignore: &DepIgnore) -> Vec<Rename> {
+    let fns: Vec<&FnAnnotation> = bog
+        .annotations
+        .iter()
+        .filter_map(|a| match a {
+            Annotation::Fn(f) => Some(f),
+            _ => None,
+        })
+        .collect();
+    let resolved: Vec<Vec<&Symbol>> = fns
+        .iter()
+        .map(|f| treesitter::resolve_fn(symbols, &fn_path(&f.name)))
+        .collect();
+    let candidates: Vec<&Symbol> = symbols
+        .iter()
+        .filter(|s| s.kind.is_fn() && !resolved.iter().flatten().any(|r| std::ptr::eq(*r, *s)))
+        .collect();
+
+    // Every candidate of every missing fn, best first
+    let mut ranked: Vec<(usize, Vec<Scored>)> = Vec::new();
+    for (i, f) in fns.iter().enumerate() {
+        if !resolved[i].is_empty() {
+            continue;
+        }
+        let path = fn_path(&f.name);
+        let after = resolved[..i].iter().rev().find_map(|r| only(r)).map(|s| s.end_line);
+        let before = resolved[i + 1..].iter().find_map(|r| only(r)).map(|s| s.start_line);
+        let mut scored: Vec<Scored> = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| fits_path(c, &path))
+            .map(|(candidate, c)| {
+                let (score, signals) = score(f, c, (after, before), ignore);
+                Scored {
+                    score,
+                    signals,
+                    candidate,
+                }
+            })
+            .collect();
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.candidate.cmp(&b.candidate)));
+        ranked.push((i, scored));
+    }
+    ranked.sort_by(|a, b| {
+        let best = |r: &[Scored]| r.first().map_or(0.0, |c| c.score);
+        best(&b.1).total_cmp(&best(&a.1)).then(a.0.cmp(&b.0))
+    });
+
+    let mut taken = vec![false; candidates.len()];
+    let mut renames = Vec::new();
+    for (i, scored) in ranked {
+        let mut free = scored.into_iter().filter(|c| !taken[c.candidate]);
+        let Some(best) = free.next() else {
+            continue;
+        };
+        if best.score < SUGGEST_SCORE {
+            continue;
+        }
+        let runner_up = free.next().map_or(0.0, |c| c.score);
+        taken[best.candidate] = true;
+        renames.push((
+            i,
+            Rename {
+                from: fns[i].name.clone(),
+                to: reference_to(&fn_path(&fns[i].name), candidates[best.candidate], symbols),
+                score: best.score,
+                confident: best.score >= CONFIDENT_SCORE
+                    && best.signals > 1
+                    && best.score - runner_up >= CONFIDENT_MARGIN,
+            },
+        ));
+    }
+    renames.sort_by_key(|(i, _)| *i);
+    renames.into_iter().map(|(_, r)| r).collect()
+}
+
+/// A rename candidate's score for one annotation.
+struct Scored {
+    score: f64,
+    /// How many signals the score rests on
+    signals: usize,
+    /// Index into the candidates
+    candidate: usize,
+}
+
+fn only<'a>(matches: &[&'a Symbol]) -> Option<&'a Symbol> {
+    match matches {
+        [s] => Some(s),
+        _ => None,
+    }
+}
+
+fn fn_path(reference: &str) -> FnPath {
+    FnPath::parse(reference).unwrap_or_else(|_| FnPath {
+        self_type: None,
+        trait_name: None,
+        name: reference.to_string(),
+    })
+}
+
+/// Whether a candidate is in the impl a qualified reference names; a renamed
+/// method stays on its type.
+fn fits_path(candidate: &Symbol, path: &FnPath) -> bool {
+    let renamed = FnPath {
+        name: candidate.name.clone(),
+        ..path.clone()
+    };
+    !treesitter::resolve_fn(std::slice::from_ref(candidate), &renamed).is_empty()
+}
+
+/// The old reference with the new name, qualified further if that is ambiguous.
+fn reference_to(path: &FnPath, symbol: &Symbol, symbols: &[Symbol]) -> String {
+    let renamed = FnPath {
+        name: symbol.name.clone(),
+        ..path.clone()
+    };
+    if treesitter::resolve_fn(symbols, &renamed).len() == 1 {
+        renamed.to_string()
+    } else {
+        symbol.qualified_name()
+    }
+}
+
+/// Weighted similarity of an annotation and a candidate over the signals the
+/// annotation has something to say about, and how many signals there were.
+fn score(
+    f: &FnAnnotation,
+    candidate: &Symbol,
+    (after, before): (Option<usize>, Option<usize>),
+    ignore: &DepIgnore,
+) -> (f64, usize) {
+    let mut signals: Vec<(f64, f64)> = Vec::new();
+    if let Some(contract) = &f.contract {
+        signals.push((3.0, param_similarity(&contract.inputs, &candidate.params)));
+        if let Some(out) = &contract.output {
+            let same = candidate
+                .return_type
+                .as_deref()
+                .is_some_and(|r| contract::normalize_type(r) == contract::normalize_type(out));
+            signals.push((1.0, if same { 1.0 } else { 0.0 }));
+        }
+    }
+    let drift = deps::check(&f.deps, candidate, ignore);
+    let union = f.deps.len() + drift.missing.len();
+    if union > 0 {
+        let shared = f.deps.len() - drift.stale.len();
+        signals.push((3.0, shared as f64 / union as f64));
+    }
+    if after.is_some() || before.is_some() {
+        let between = after.is_none_or(|end| candidate.start_line > end)
+            && before.is_none_or(|start| candidate.end_line < start);
+        signals.push((2.0, if between { 1.0 } else { 0.0 }));
+    }
+
+    let weight: f64 = signals.iter().map(|(w, _)| w).sum();
+    if weight == 0.0 {
+        return (0.0, 0);
+    }
+    let total: f64 = signals.iter().map(|(w, s)| w * s).sum();
+    (total / weight, signals.len())
+}
+
+/// Share of params matched by name and type (a type alone counts half), over the
+/// longer of the two lists. `self` is implied by the impl and not compared.
+fn param_similarity(declared: &[(String, String)], actual: &[(String, String)]) -> f64 {
+    let name = |n: &str| n.trim().trim_start_matches("mut ").to_string();
+    let declared: Vec<(String, String)> = declared
+        .iter()
+        .map(|(n, t)| (name(n), contract::normalize_type(t)))
+        .filter(|(n, _)| n != "self")
+        .collect();
+    let actual: Vec<(String, String)> = actual
+        .iter()
+        .map(|(n, t)| (name(n), contract::normalize_type(t)))
+        .collect();
+    let len = declared.len().max(actual.len());
+    if len == 0 {
+        return 1.0;
+    }
+    let matched: f64 = declared
+        .iter()
+        .map(|(n, t)| {
+            if actual.iter().any(|(an, at)| an == n && at == t) {
+                1.0
+            } else if actual.iter().any(|(_, at)| at == t) {
+                0.5
+            } else {
+                0.0
+            }
+        })
+        .sum();
+    matched / len as f64
+}
+
+/// One change made by `--fix`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fix {
+    /// `#[fn(from)]` rewritten to the function it was renamed to
+    Renamed { from: String, to: String },
+    /// `#[file]` owner replaced by the owner of its subsystem
+    Owner { from: String, to: String },
+    /// Deps of a function rewritten to match its calls
+    Deps { function: String },
+}
+
+impl fmt::Display for Fix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fix::Renamed { from, to } => write!(f, "renamed fn({from}) to fn({to})"),
+            Fix::Owner { from, to } => write!(f, "owner '{from}' -> '{to}'"),
+            Fix::Deps { function } => write!(f, "deps of {function}"),
+        }
+    }
+}
+
+/// What `fix_sidecar` did to one sidecar.
+#[derive(Debug, Default)]
+pub struct FixedSidecar {
+    /// The sidecar text with the fixes applied
+    pub text: String,
+    pub fixes: Vec<Fix>,
+    /// Fixes that were found but could not be written, with the reason
+    pub unapplied: Vec<(Fix, String)>,
+}
+
+/// Apply the mechanical fixes to a sidecar: the `#[file]` owner from `owners`
+/// (subsystem to owner, as declared in repo.bog), then, when the described
+/// `source` is given with the backend that reads it, confident renames and deps.
+/// Comments and layout outside the rewritten parts are kept.
+///
+/// # Errors
+///
+/// Returns the parse error if the sidecar is not valid .bog.
+pub fn fix_sidecar(
+    bog_text: &str,
+    source: Option<(&str, &dyn LanguageBackend)>,
+    owners: &HashMap<String, String>,
+    ignore: &DepIgnore,
+) -> Result<FixedSidecar, ParseError> {
+    let mut tree = cst::parse_cst(bog_text)?;
+    let mut bog = parser::parse_bog(bog_text)?;
+    let mut fixes = Vec::new();
+
+    let file = bog.annotations.iter().find_map(|a| match a {
+        Annotation::File(f) => Some(f),
+        _ => None,
+    });
+    if let Some(file) = file
+        && let Some(owner) = owners.get(&file.subsystem)
+        && &file.owner != owner
+    {
+        let pair = cst::parse_kv_pair_cst(&format!("owner = {}", writer::quote(owner)))?;
+        if let Some(node) = tree.annotations_mut().find(|n| n.annotation_name() == Some("file"))
+            && node.set_field(pair, "owner")
+        {
+            fixes.push(Fix::Owner {
+                from: file.owner.clone(),
+                to: owner.clone(),
+            });
+        }
+    }
+
+    let Some((source, backend)) = source else {
+        return Ok(FixedSidecar {
+            text: tree.to_string(),
+            fixes,
+            unapplied: Vec::new(),
+        });
+    };
+    let mut unapplied = Vec::new();
+    if let Ok(symbols) = backend.extract_symbols(source) {
+        // Inline annotations claim their functions, so they are not rename candidates
+        inline::merge(&mut bog, source, backend);
+        for rename in propose_renames(&bog, &symbols, ignore) {
+            if !rename.confident {
+                continue;
+            }
+            let path = FnPath::parse(&rename.from).ok();
+            let applied = path
+                .and_then(|path| tree.annotations_mut().find(|n| n.is_fn_annotation(&path)))
+                .map(|node| node.set_annotation_arg(&rename.to));
+            let fix = Fix::Renamed {
+                from: rename.from,
+                to: rename.to,
+            };
+            match applied {
+                Some(true) => fixes.push(fix),
+                Some(false) => unapplied.push((fix, "the new name does not parse".to_string())),
+                None => unapplied.push((fix, "no #[fn] node to rewrite".to_string())),
+            }
+        }
+    }
+    // Renamed functions resolve now, so their deps are fixed too
+    let deps = deps::fix_sidecar(&tree.to_string(), source, backend, ignore)?;
+    fixes.extend(deps.fixed.into_iter().map(|function| Fix::Deps { function }));
+    unapplied.extend(
+        deps.unfixed
+            .into_iter()
+            .map(|(function, reason)| (Fix::Deps { function }, reason)),
+    );
+    Ok(FixedSidecar {
+        text: deps.text,
+        fixes,
+        unapplied,
+    })
+}
+
+/// `fix_sidecar` on a sidecar on disk and, when it describes a source file in a
+/// known language, that source, writing the sidecar back only if something changed.
+///
+/// # Errors
+///
+/// Fails if a file cannot be read, the sidecar cannot be parsed or written.
+pub fn fix_file(
+    bog_path: &Path,
+    owners: &HashMap<String, String>,
+    ignore: &DepIgnore,
+) -> Result<FixedSidecar, Box<dyn std::error::Error>> {
+    let bog_text = std::fs::read_to_string(bog_path)?;
+    let source = match lang::source_for(bog_path) {
+        Some((source_path, backend)) if source_path.exists() => {
+            Some((std::fs::read_to_string(&source_path)?, backend))
+        }
+        _ => None,
+    };
+    let source = source.as_ref().map(|(text, backend)| (text.as_str(), *backend));
+    let fixed = fix_sidecar(&bog_text, source, owners, ignore)?;
+    if !fixed.fixes.is_empty() {
+        std::fs::write(bog_path, &fixed.text)?;
+    }
+    Ok(fixed)
+}
+
+#[derive(Debug, Default)]
+pub struct FixReport {
+    /// Sidecars rewritten, with the fixes made to each
+    pub fixed: Vec<(PathBuf, Vec<Fix>)>,
+    /// Fixes found but not written, with the reason
+    pub unapplied: Vec<(PathBuf, Fix, String)>,
+    /// Sidecars that could not be fixed, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// `fix_file` on every sidecar under `root`, with owners from its repo.bog. With
+/// `changes`, only sidecars the diff touches are rewritten.
+pub fn fix_project(root: &Path, ignore: &DepIgnore, changes: Option<&ChangeSet>) -> FixReport {
+    let owners: HashMap<String, String> = include::load_repo_bog(root)
+        .map(|repo| {
+            repo.bog
+                .annotations
+                .iter()
+                .filter_map(|a| match a {
+                    Annotation::Subsystem(s) => Some((s.name.clone(), s.owner.clone())),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut report = FixReport::default();
+    for bog_path in include::sidecar_files(root) {
+        if changes.is_some_and(|c| !c.touches(&relative_to(root, &bog_path))) {
+            continue;
+        }
+        match fix_file(&bog_path, &owners, ignore) {
+            Ok(fixed) => {
+                for (fix, reason) in fixed.unapplied {
+                    report.unapplied.push((bog_path.clone(), fix, reason));
+                }
+                if !fixed.fixes.is_empty() {
+                    report.fixed.push((bog_path, fixed.fixes));
+                }
+            }
+            Err(e) => report.failed.push((bog_path, e.to_string())),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::rust::RUST;
+
+    const SOURCE: &str = r#"
+fn open(path: &str) -> File {
+    fs::open(path)
+}
+
+fn load_config(path: &Path, strict: bool) -> Config {
+    let text = fs::read(path);
+    parse_toml(text, strict)
+}
+
+fn close(file: File) {
+    fs::close(file);
+}
+
+fn helper() {}
+"#;
+
+    const SIDECAR: &str = r#"#[file(owner = "old-agent", subsystem = "io", updated = "2026-01-01", status = green)]
+
+#[fn(open) { status = green }]
+
+// renamed in source
+#[fn(read_config) {
+  status = green,
+  deps = [fs::read, parse_toml],
+  contract = { in = [(path, Path), (strict, bool)], out = "Config" }
+}]
+
+#[fn(close) { status = green, deps = [fs::close] }]
+
+#[fn(gone) { status = green }]
+"#;
+
+    fn sidecar() -> BogFile {
+        parser::parse_bog(SIDECAR).unwrap()
+    }
+
+    #[test]
+    fn test_renames_are_proposed_from_contract_deps_and_position() {
+        let symbols = treesitter::extract_symbols(SOURCE).unwrap();
+        let renames = propose_renames(&sidecar(), &symbols, &DepIgnore::default());
+        assert_eq!(renames.len(), 2);
+        assert_eq!(renames[0].from, "read_config");
+        assert_eq!(renames[0].to, "load_config");
+        assert!(renames[0].confident);
+        // Only its position points at `helper`, which is not enough to rewrite it
+        assert_eq!(renames[1].from, "gone");
+        assert_eq!(renames[1].to, "helper");
+        assert!(!renames[1].confident);
+    }
+
+    #[test]
+    fn test_fix_sidecar_renames_and_sets_owner() {
+        let owners = HashMap::from([("io".to_string(), "io-agent".to_string())]);
+        let FixedSidecar { text, fixes, .. } =
+            fix_sidecar(SIDECAR, Some((SOURCE, &RUST)), &owners, &DepIgnore::default()).unwrap();
+        assert_eq!(
+            fixes,
+            [
+                Fix::Owner {
+                    from: "old-agent".to_string(),
+                    to: "io-agent".to_string()
+                },
+                Fix::Renamed {
+                    from: "read_config".to_string(),
+                    to: "load_config".to_string()
+                },
+                Fix::Deps {
+                    function: "open".to_string()
+                },
+            ]
+        );
+        assert!(text.starts_with("#[file(owner = \"io-agent\", subsystem = \"io\""));
+        assert!(text.contains("// renamed in source\n#[fn(load_config) {\n  status = green,"));
+        assert!(text.contains("#[fn(gone) { status = green }]"));
+
+        let again =
+            fix_sidecar(&text, Some((SOURCE, &RUST)), &owners, &DepIgnore::default()).unwrap();
+        assert!(again.fixes.is_empty() && again.unapplied.is_empty());
+    }
+
+    #[test]
+    fn test_fix_sidecar_renames_qualified_fns_however_spaced() {
+        let source = "struct Store;\nimpl Store {\n    fn open(&self) {}\n    \
+                      fn save_all(&self, items: &[Item], force: bool) -> usize {\n        \
+                      disk::write(items, force)\n    }\n}\n";
+        let bog = r#"#[fn(Store::open) { status = green }]
+#[fn(< Store >::persist) {
+  status = green,
+  deps = [disk::write],
+  contract = { in = [(items, "&[Item]"), (force, bool)], out = "usize" }
+}]
+"#;
+        let fixed =
+            fix_sidecar(bog, Some((source, &RUST)), &HashMap::new(), &DepIgnore::default()).unwrap();
+        assert_eq!(
+            fixed.fixes,
+            [Fix::Renamed {
+                from: "Store::persist".to_string(),
+                to: "Store::save_all".to_string()
+            }]
+        );
+        assert!(fixed.unapplied.is_empty());
+        assert!(fixed.text.contains("#[fn(Store::save_all) {"), "{}", fixed.text);
+    }
+
+    #[test]
+    fn test_owner_is_quoted() {
+        let owner = r#"io "core" \ agent"#;
+        let owners = HashMap::from([("io".to_string(), owner.to_string())]);
+        let FixedSidecar { text, fixes, .. } =
+            fix_sidecar(SIDECAR, None, &owners, &DepIgnore::default()).unwrap();
+        assert_eq!(fixes.len(), 1);
+        let file = parser::parse_bog(&text).unwrap().annotations.remove(0);
+        assert!(matches!(file, Annotation::File(f) if f.owner == owner));
+    }
+}
@@ -0,0 +1,633 @@
+use std::path::{Path, PathBuf};
+
+use crate::ast::FnPath;
+use crate::cst::{self, SyntaxElement, SyntaxNode, SyntaxToken, TokenKind};
+use crate::parser::{ParseError, Rule};
+use crate::walk::Walker;
+
+const INDENT: &str = "  ";
+const MAX_WIDTH: usize = 100;
+
+/// One element of a formatted container: an item node or a standalone comment line.
+/// `blank_lines` counts the empty lines the author left above it.
+enum Entry<'a> {
+    Item {
+        node: &'a SyntaxNode,
+        blank_lines: usize,
+        trailing: Option<&'a str>,
+    },
+    Comment {
+        text: &'a str,
+        blank_lines: usize,
+    },
+}
+
+impl Entry<'_> {
+    fn blank_lines(&self) -> usize {
+        match self {
+            Entry::Item { blank_lines, .. } | Entry::Comment { blank_lines, .. } => *blank_lines,
+        }
+    }
+
+    fn blank_before(&self) -> bool {
+        self.blank_lines() > 0
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FormatReport {
+    pub files_checked: usize,
+    /// Files whose contents differ from the canonical layout
+    pub changed: Vec<PathBuf>,
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+/// Format a single .bog file, or every .bog file below a directory.
+/// Changed files are rewritten unless `check` is set.
+pub fn format_path(path: &Path, check: bool) -> FormatReport {
+    let mut report = FormatReport::default();
+
+    let files: Vec<PathBuf> = if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        Walker::new(path).files_with_suffix(".bog")
+    };
+
+    for file in files {
+        report.files_checked += 1;
+        let content = match std::fs::read_to_string(&file) {
+            Ok(c) => c,
+            Err(e) => {
+                report.errors.push((file, e.to_string()));
+                continue;
+            }
+        };
+        let formatted = match format_bog(&content) {
+            Ok(f) => f,
+            Err(e) => {
+                report.errors.push((file, e.to_string()));
+                continue;
+            }
+        };
+        if formatted == content {
+            continue;
+        }
+        if !check && let Err(e) = std::fs::write(&file, &formatted) {
+            report.errors.push((file, e.to_string()));
+            continue;
+        }
+        report.changed.push(file);
+    }
+
+    report
+}
+
+/// Format .bog text into the canonical layout, keeping every comment.
+pub fn format_bog(input: &str) -> Result<String, ParseError> {
+    let cst = cst::parse_cst(input)?;
+    Ok(format_cst(&cst))
+}
+
+/// Pretty-print a `bog_file` syntax tree in the canonical layout.
+/// Blank lines between top-level entries are kept as written, with at least one after an annotation.
+pub fn format_cst(root: &SyntaxNode) -> String {
+    let mut out = String::new();
+    let entries = collect_entries(root, Rule::annotation);
+    let mut prev_was_item = false;
+
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            let min = usize::from(prev_was_item);
+            for _ in 0..entry.blank_lines().max(min) {
+                out.push('\n');
+            }
+        }
+        match entry {
+            Entry::Comment { text, .. } => {
+                out.push_str(text.trim_end());
+                out.push('\n');
+                prev_was_item = false;
+            }
+            Entry::Item { node, trailing, .. } => {
+                write_loose_comments(node, "", &mut out);
+                format_annotation(node, "", &mut out);
+                if let Some(c) = trailing {
+                    out.push(' ');
+                    out.push_str(c.trim_end());
+                }
+                out.push('\n');
+                prev_was_item = true;
+            }
+        }
+    }
+
+    out
+}
+
+/// Walk a container's subtree down to nodes of `item_kind`, turning the trivia in
+/// between into comment entries, trailing comments and blank-line markers.
+fn collect_entries(container: &SyntaxNode, item_kind: Rule) -> Vec<Entry<'_>> {
+    let mut entries = Vec::new();
+    let mut newlines = 0;
+    walk_entries(container, item_kind, &mut entries, &mut newlines);
+    entries
+}
+
+fn walk_entries<'a>(
+    node: &'a SyntaxNode,
+    item_kind: Rule,
+    entries: &mut Vec<Entry<'a>>,
+    newlines: &mut usize,
+) {
+    for child in &node.children {
+        match child {
+            SyntaxElement::Node(n) if n.kind == item_kind => {
+                entries.push(Entry::Item {
+                    node: n,
+                    blank_lines: newlines.saturating_sub(1),
+                    trailing: None,
+                });
+                *newlines = 0;
+            }
+            SyntaxElement::Node(n) => walk_entries(n, item_kind, entries, newlines),
+            SyntaxElement::Token(t) => match t.kind {
+                TokenKind::Whitespace => *newlines += t.text.matches('\n').count(),
+                TokenKind::Comment => {
+                    if *newlines == 0
+                        && let Some(Entry::Item { trailing, .. }) = entries.last_mut()
+                        && trailing.is_none()
+                    {
+                        *trailing = Some(&t.text);
+                    } else {
+                        entries.push(Entry::Comment {
+                            text: &t.text,
+                            blank_lines: newlines.saturating_sub(1),
+                        });
+                    }
+                    *newlines = 0;
+                }
+                TokenKind::Punct | TokenKind::Leaf(_) => {}
+            },
+        }
+    }
+}
+
+/// Nodes that lay out their own comments; comments elsewhere are hoisted above the item.
+fn is_container(node: &SyntaxNode) -> bool {
+    match node.kind {
+        Rule::body | Rule::list | Rule::nested_block => true,
+        Rule::parens => node
+            .child(Rule::parens_content)
+            .is_some_and(|c| c.child(Rule::kv_list).is_some()),
+        _ => false,
+    }
+}
+
+fn loose_comments<'a>(node: &'a SyntaxNode, out: &mut Vec<&'a str>) {
+    for child in &node.children {
+        match child {
+            SyntaxElement::Node(n) if is_container(n) => {}
+            SyntaxElement::Node(n) => loose_comments(n, out),
+            SyntaxElement::Token(t) if t.kind == TokenKind::Comment => out.push(&t.text),
+            SyntaxElement::Token(_) => {}
+        }
+    }
+}
+
+fn write_loose_comments(node: &SyntaxNode, indent: &str, out: &mut String) {
+    let mut comments = Vec::new();
+    loose_comments(node, &mut comments);
+    for c in comments {
+        out.push_str(c.trim_end());
+        out.push('\n');
+        out.push_str(indent);
+    }
+}
+
+/// Write the entries of a multi-line container, one item per line at `indent`.
+fn write_entries(
+    entries: &[Entry<'_>],
+    indent: &str,
+    separator: &str,
+    out: &mut String,
+    render: impl Fn(&SyntaxNode, &str) -> String,
+) {
+    let last_item = entries
+        .iter()
+        .rposition(|e| matches!(e, Entry::Item { .. }));
+
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 && entry.blank_before() {
+            out.push('\n');
+        }
+        out.push_str(indent);
+        match entry {
+            Entry::Comment { text, .. } => out.push_str(text.trim_end()),
+            Entry::Item { node, trailing, .. } => {
+                write_loose_comments(node, indent, out);
+                out.push_str(&render(node, indent));
+                if Some(i) != last_item {
+                    out.push_str(separator);
+                }
+                if let Some(c) = trailing {
+                    out.push(' ');
+                    out.push_str(c.trim_end());
+                }
+            }
+        }
+        out.push('\n');
+    }
+}
+
+fn format_annotation(node: &SyntaxNode, indent: &str, out: &mut String) {
+    let name = node.annotation_name().unwrap_or_default();
+    out.push_str("#[");
+    out.push_str(name);
+
+    let body = node.child(Rule::body);
+    if let Some(parens) = node.child(Rule::parens) {
+        let prefix_width = indent.len() + 2 + name.len();
+        format_parens(parens, indent, body.is_some(), prefix_width, out);
+    }
+    if let Some(body) = body {
+        out.push(' ');
+        format_body(body, indent, out);
+    }
+    out.push(']');
+}
+
+fn format_parens(
+    parens: &SyntaxNode,
+    indent: &str,
+    prefer_inline: bool,
+    prefix_width: usize,
+    out: &mut String,
+) {
+    let Some(content) = parens.child(Rule::parens_content) else {
+        out.push_str("()");
+        return;
+    };
+    if let Some(path) = content.leaf_text(Rule::fn_path) {
+        out.push_str(&format!("({})", format_fn_path(path)));
+        return;
+    }
+    if let Some(arg) = content
+        .leaf_text(Rule::ident)
+        .or_else(|| content.leaf_text(Rule::string_literal))
+    {
+        out.push('(');
+        out.push_str(arg);
+        out.push(')');
+        return;
+    }
+
+    let entries = collect_entries(parens, Rule::kv_pair);
+    if prefer_inline && parens.comments().is_empty() {
+        let items: Vec<String> = entries
+            .iter()
+            .filter_map(|e| match e {
+                Entry::Item { node, .. } => Some(format_kv(node, indent)),
+                Entry::Comment { .. } => None,
+            })
+            .collect();
+        let inline = format!("({})", items.join(", "));
+        if !inline.contains('\n') && prefix_width + inline.len() + 2 <= MAX_WIDTH {
+            out.push_str(&inline);
+            return;
+        }
+    }
+
+    let inner = format!("{indent}{INDENT}");
+    out.push_str("(\n");
+    write_entries(&entries, &inner, ",", out, format_kv);
+    out.push_str(indent);
+    out.push(')');
+}
+
+fn format_body(body: &SyntaxNode, indent: &str, out: &mut String) {
+    let inner = format!("{indent}{INDENT}");
+    let content = body.child(Rule::body_content);
+
+    if content.is_some_and(|c| c.leaf_text(Rule::freeform_text).is_some()) {
+        out.push_str("{\n");
+        let mut parts = Vec::new();
+        freeform_parts(body, &mut parts, &mut 0);
+        for (i, (blank_lines, token)) in parts.iter().enumerate() {
+            if i > 0 && *blank_lines > 0 {
+                out.push('\n');
+            }
+            if token.kind == TokenKind::Comment {
+                out.push_str(&inner);
+                out.push_str(token.text.trim_end());
+                out.push('\n');
+            } else {
+                write_freeform(&token.text, &inner, out);
+            }
+        }
+        out.push_str(indent);
+        out.push('}');
+        return;
+    }
+
+    let is_annotations = content.is_some_and(|c| c.child(Rule::annotation).is_some());
+    let entries = if is_annotations {
+        collect_entries(body, Rule::annotation)
+    } else {
+        collect_entries(body, Rule::kv_pair)
+    };
+
+    if entries.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+
+    out.push_str("{\n");
+    if is_annotations {
+        write_entries(&entries, &inner, "", out, |node, ind| {
+            let mut s = String::new();
+            format_annotation(node, ind, &mut s);
+            s
+        });
+    } else {
+        write_entries(&entries, &inner, ",", out, format_kv);
+    }
+    out.push_str(indent);
+    out.push('}');
+}
+
+/// The comments and text of a freeform body in source order, each with the number
+/// of blank lines before it. Comments after the first line of text are part of it.
+fn freeform_parts<'a>(
+    node: &'a SyntaxNode,
+    parts: &mut Vec<(usize, &'a SyntaxToken)>,
+    newlines: &mut usize,
+) {
+    for child in &node.children {
+        match child {
+            SyntaxElement::Node(n) => freeform_parts(n, parts, newlines),
+            SyntaxElement::Token(t) => match t.kind {
+                TokenKind::Whitespace => *newlines += t.text.matches('\n').count(),
+                TokenKind::Comment | TokenKind::Leaf(Rule::freeform_text) => {
+                    parts.push((newlines.saturating_sub(1), t));
+                    *newlines = 0;
+                }
+                _ => {}
+            },
+        }
+    }
+}
+
+/// Re-indent freeform text to `indent`, keeping relative indentation of continuation lines.
+fn write_freeform(text: &str, indent: &str, out: &mut String) {
+    let text = text.trim_end();
+    let lines: Vec<&str> = text.lines().collect();
+    let common = lines
+        .iter()
+        .skip(1)
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    for (i, line) in lines.iter().enumerate() {
+        let line = if i == 0 {
+            line.trim()
+        } else if line.trim().is_empty() {
+            ""
+        } else {
+            line[common..].trim_end()
+        };
+        if !line.is_empty() {
+            out.push_str(indent);
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+}
+
+fn format_kv(pair: &SyntaxNode, indent: &str) -> String {
+    let key = pair.leaf_text(Rule::ident).unwrap_or_default();
+    let used_width = indent.len() + key.len() + 4;
+    let value = pair
+        .child(Rule::value)
+        .map(|v| format_value(v, indent, used_width))
+        .unwrap_or_default();
+    format!("{key} = {value}")
+}
+
+/// A qualified fn name with whitespace normalized, e.g. `< T  as Tr >::f` to `<T as Tr>::f`.
+fn format_fn_path(path: &str) -> String {
+    FnPath::parse(path).map_or_else(|_| path.to_string(), |p| p.to_string())
+}
+
+/// The single meaningful child of a wrapper rule (`value`, `list_item`, `tuple_item`).
+fn inner_element(node: &SyntaxNode) -> Option<&SyntaxElement> {
+    node.children.iter().find(|c| match c {
+        SyntaxElement::Node(_) => true,
+        SyntaxElement::Token(t) => matches!(t.kind, TokenKind::Leaf(_)),
+    })
+}
+
+fn format_value(node: &SyntaxNode, indent: &str, used_width: usize) -> String {
+    match inner_element(node) {
+        Some(SyntaxElement::Token(t)) => t.text.clone(),
+        Some(SyntaxElement::Node(n)) => match n.kind {
+            Rule::fn_ref => match n.leaf_text(Rule::fn_path) {
+                Some(path) => format!("fn({})", format_fn_path(path)),
+                None => format!("fn({})", n.leaf_text(Rule::ident).unwrap_or_default()),
+            },
+            Rule::tuple => {
+                let items: Vec<String> = n
+                    .child_nodes()
+                    .filter(|c| c.kind == Rule::tuple_item)
+                    .map(|c| format_value(c, indent, used_width))
+                    .collect();
+                format!("({})", items.join(", "))
+            }
+            Rule::list => format_list(n, indent, used_width),
+            Rule::nested_block => {
+                let inner = format!("{indent}{INDENT}");
+                let mut out = String::from("{\n");
+                write_entries(&collect_entries(n, Rule::kv_pair), &inner, ",", &mut out, format_kv);
+                out.push_str(indent);
+                out.push('}');
+                out
+            }
+            _ => n.to_string().trim().to_string(),
+        },
+        None => String::new(),
+    }
+}
+
+fn format_list(list: &SyntaxNode, indent: &str, used_width: usize) -> String {
+    let entries = collect_entries(list, Rule::list_item);
+    let render = |node: &SyntaxNode, ind: &str| format_value(node, ind, used_width);
+
+    if list.comments().is_empty() {
+        let items: Vec<String> = entries
+            .iter()
+            .filter_map(|e| match e {
+                Entry::Item { node, .. } => Some(render(node, indent)),
+                Entry::Comment { .. } => None,
+            })
+            .collect();
+        let inline = format!("[{}]", items.join(", "));
+        if used_width + inline.len() <= MAX_WIDTH {
+            return inline;
+        }
+    }
+
+    let inner = format!("{indent}{INDENT}");
+    let mut out = String::from("[\n");
+    write_entries(&entries, &inner, ",", &mut out, render);
+    out.push_str(indent);
+    out.push(']');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_normalizes_layout() {
+        let input = r#"#[file(owner = "a",subsystem="core", updated = "2026-01-01",status=green,)]
+#[fn(login){status=green,deps=[db::get_user,   crypto::verify],
+contract={in=[(username,String)],out="Token"}}]"#;
+        let expected = r#"#[file(
+  owner = "a",
+  subsystem = "core",
+  updated = "2026-01-01",
+  status = green
+)]
+
+#[fn(login) {
+  status = green,
+  deps = [db::get_user, crypto::verify],
+  contract = {
+    in = [(username, String)],
+    out = "Token"
+  }
+}]
+"#;
+        assert_eq!(format_bog(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_normalizes_qualified_fn_names() {
+        let input = "#[fn(< Auth  as Default >::default) { status = green }]\n\
+#[skim(q) { target = fn(<Vec< u8 >>::push), status = red }]";
+        let expected = "#[fn(<Auth as Default>::default) {\n  status = green\n}]\n\n\
+#[skim(q) {\n  target = fn(<Vec<u8>>::push),\n  status = red\n}]\n";
+        assert_eq!(format_bog(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_keeps_comments() {
+        let input = r#"// top of file
+#[health(
+  test_coverage = green, // measured by CI
+  // staleness is manual
+
+  staleness = yellow
+)]
+// end
+"#;
+        let expected = r#"// top of file
+#[health(
+  test_coverage = green, // measured by CI
+  // staleness is manual
+
+  staleness = yellow
+)]
+
+// end
+"#;
+        assert_eq!(format_bog(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_keeps_blank_lines_between_annotations() {
+        let input = "#[fn(a) {}]\n\n\n\n// later\n#[fn(a) {}]\n#[fn(a) {}]\n";
+        let expected = "#[fn(a) {}]\n\n\n\n// later\n#[fn(a) {}]\n\n#[fn(a) {}]\n";
+        assert_eq!(format_bog(input).unwrap(), expected);
+        assert_eq!(format_bog(expected).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_pickled_parens_inline_and_description_reindented() {
+        let input = r#"#[pickled(
+      agent = "core-agent",
+      updated = "2026-02-26"
+) {
+  id = "p-1", kind = decision, tags = [architecture], content = "x"
+}]
+#[description {
+        Line one.
+          Indented continuation.
+        Line three.
+}]"#;
+        let expected = r#"#[pickled(agent = "core-agent", updated = "2026-02-26") {
+  id = "p-1",
+  kind = decision,
+  tags = [architecture],
+  content = "x"
+}]
+
+#[description {
+  Line one.
+    Indented continuation.
+  Line three.
+}]
+"#;
+        assert_eq!(format_bog(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_keeps_comments_in_place_in_freeform_bodies() {
+        let input = "#[description {\n    // why this file exists\n\n    First line.\n    \
+                     // about the first line\n    Second line.\n    // trailing note\n}]\n";
+        let expected = "#[description {\n  // why this file exists\n\n  First line.\n  \
+                        // about the first line\n  Second line.\n  // trailing note\n}]\n";
+        let formatted = format_bog(input).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(format_bog(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_breaks_long_lists() {
+        let input = r#"#[subsystem(core) { owner = "a", status = green, files = ["src/a/very/long/path/one.rs", "src/a/very/long/path/two.rs", "src/a/very/long/path/three.rs"] }]"#;
+        let formatted = format_bog(input).unwrap();
+        assert!(formatted.contains("  files = [\n    \"src/a/very/long/path/one.rs\",\n"));
+        assert!(formatted.contains("    \"src/a/very/long/path/three.rs\"\n  ]\n"));
+    }
+
+    #[test]
+    fn test_format_nested_change_requests() {
+        let input = r#"#[change_requests { #[request(id = "cr-1", from = "a", target = fn(login), type = lint, status = pending, created = "2026-01-01", description = "d")] }]"#;
+        let formatted = format_bog(input).unwrap();
+        assert!(formatted.starts_with("#[change_requests {\n  #[request(\n    id = \"cr-1\",\n"));
+        assert!(formatted.contains("    target = fn(login),\n"));
+        assert!(formatted.ends_with("    description = \"d\"\n  )]\n}]\n"));
+    }
+
+    #[test]
+    fn test_format_is_idempotent_and_reparses() {
+        let input = r#"
+// header
+#[skimsystem(code-quality) {
+  owner = "q", targets = all, status = green,
+  integrations = { clippy = { command = "cargo clippy", format = cargo_diagnostic } },
+  principles = ["one", "two"] // inline note
+}]
+"#;
+        let once = format_bog(input).unwrap();
+        let twice = format_bog(&once).unwrap();
+        assert_eq!(once, twice);
+        let original = crate::parser::parse_bog(input).unwrap();
+        let reparsed = crate::parser::parse_bog(&once).unwrap();
+        assert_eq!(original.annotations.len(), reparsed.annotations.len());
+        assert!(once.contains("// header"));
+        assert!(once.contains("// inline note"));
+    }
+}
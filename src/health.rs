@@ -1,10 +1,17 @@
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use colored::Colorize;
+use rayon::prelude::*;
 
 use crate::ast::*;
-use crate::parser;
+use crate::cache::Cache;
+use crate::contract;
+use crate::include;
+use crate::inline;
+use crate::policy::Policies;
+use crate::staleness::{self, FileStaleness};
+use crate::walk::Walker;
 
 #[derive(Debug)]
 pub struct SubsystemHealth {
@@ -62,6 +69,9 @@ pub struct RepoHealth {
     pub name: String,
     pub subsystems: Vec<SubsystemHealth>,
     pub skimsystems: Vec<SkimsystemHealth>,
+    /// Sidecars behind their source according to git, with their staleness, most
+    /// out of date first
+    pub stale_files: Vec<(Status, FileStaleness)>,
 }
 
 impl RepoHealth {
@@ -85,27 +95,75 @@ impl RepoHealth {
     }
 }
 
+/// One sidecar as seen by `compute_health`.
+struct ScannedFile {
+    bog: BogFile,
+    /// Dimensions computed from source and git rather than read from #[health]
+    computed: Vec<(&'static str, Status)>,
+    /// Set when staleness is not green, with the sidecar path relative to the root
+    stale: Option<(Status, FileStaleness)>,
+}
+
+/// Parse the sidecar of `source_path` and compute its contract compliance and
+/// staleness. `None` if there is no readable sidecar.
+fn scan_file(
+    root: &Path,
+    source_path: &Path,
+    cache: &Cache,
+    stale_after_days: i64,
+) -> Option<ScannedFile> {
+    let bog_path_str = format!("{}.bog", source_path.display());
+    let bog_path = Path::new(&bog_path_str);
+    let content = std::fs::read_to_string(bog_path).ok()?;
+    // Keep whatever parsed: one bad annotation should not hide the file
+    let (mut bog, _) = cache.parse_bog_recovering(&content);
+    inline::merge_from_source(&mut bog, source_path);
+    let mut computed = Vec::new();
+    if let Some(status) = cache
+        .symbols(source_path)
+        .and_then(|symbols| contract::compliance(&bog, &symbols))
+    {
+        computed.push(("contract_compliance", status));
+    }
+    let mut stale = None;
+    if let Some(mut file) = staleness::file_staleness(bog_path, &content, &bog, source_path) {
+        let status = file.status(stale_after_days);
+        computed.push(("staleness", status));
+        if status != Status::Green {
+            file.bog_path = bog_path.strip_prefix(root).unwrap_or(bog_path).to_path_buf();
+            stale = Some((status, file));
+        }
+    }
+    Some(ScannedFile {
+        bog,
+        computed,
+        stale,
+    })
+}
+
 /// Compute health report for the entire project
 pub fn compute_health(root: &Path) -> RepoHealth {
-    let repo_bog_path = root.join("repo.bog");
     let mut repo_name = "unknown".to_string();
     let mut subsystem_decls: Vec<SubsystemDecl> = Vec::new();
     let mut skimsystem_decls: Vec<SkimsystemDecl> = Vec::new();
 
-    // Parse repo.bog for subsystem and skimsystem declarations
-    if let Ok(content) = std::fs::read_to_string(&repo_bog_path) {
-        if let Ok(bog) = parser::parse_bog(&content) {
-            for ann in &bog.annotations {
-                match ann {
-                    Annotation::Repo(r) => repo_name = r.name.clone(),
-                    Annotation::Subsystem(s) => subsystem_decls.push(s.clone()),
-                    Annotation::Skimsystem(sk) => skimsystem_decls.push(sk.clone()),
-                    _ => {}
-                }
-            }
+    // Parse repo.bog (and its includes) for subsystem and skimsystem declarations
+    let (repo, _) = include::load_repo_bog_recovering(root);
+    for ann in &repo.bog.annotations {
+        match ann {
+            Annotation::Repo(r) => repo_name = r.name.clone(),
+            Annotation::Subsystem(s) => subsystem_decls.push(s.clone()),
+            Annotation::Skimsystem(sk) => skimsystem_decls.push(sk.clone()),
+            _ => {}
         }
     }
 
+    let stale_after_days = Policies::from_repo(&repo.bog)
+        .ok()
+        .and_then(|p| p.stale_after_days)
+        .map_or(staleness::DEFAULT_STALE_AFTER_DAYS, |(days, _)| days);
+    let mut stale_files = Vec::new();
+
     // Map subsystem names to their file globs for skim observation matching
     let subsystem_map: HashMap<String, &SubsystemDecl> = subsystem_decls
         .iter()
@@ -116,6 +174,8 @@ pub fn compute_health(root: &Path) -> RepoHealth {
     let mut subsystems = Vec::new();
     let mut all_file_bogs: Vec<(String, BogFile)> = Vec::new(); // (subsystem_name, bog)
 
+    let walker = Walker::new(root);
+    let cache = Cache::open(root);
     for decl in &subsystem_decls {
         let mut sub_health = SubsystemHealth {
             name: decl.name.clone(),
@@ -126,24 +186,18 @@ pub fn compute_health(root: &Path) -> RepoHealth {
             fn_statuses: StatusCount::default(),
         };
 
-        // Find all .bog files matching this subsystem's globs
-        for pattern in &decl.files {
-            let full_pattern = root.join(pattern).to_string_lossy().to_string();
-            if let Ok(paths) = glob::glob(&full_pattern) {
-                for source_path in paths.flatten() {
-                    let bog_path_str = format!("{}.bog", source_path.display());
-                    let bog_path = Path::new(&bog_path_str);
-                    if bog_path.exists() {
-                        if let Ok(content) = std::fs::read_to_string(bog_path) {
-                            if let Ok(bog) = parser::parse_bog(&content) {
-                                sub_health.file_count += 1;
-                                aggregate_file_health(&bog, &mut sub_health);
-                                all_file_bogs.push((decl.name.clone(), bog));
-                            }
-                        }
-                    }
-                }
-            }
+        // Scan the sidecars matching this subsystem's globs in parallel, then
+        // aggregate them in glob order so the report does not depend on scheduling
+        let sources: Vec<PathBuf> = decl.files.iter().flat_map(|p| walker.glob(p)).collect();
+        let scanned: Vec<ScannedFile> = sources
+            .par_iter()
+            .filter_map(|source_path| scan_file(root, source_path, &cache, stale_after_days))
+            .collect();
+        for file in scanned {
+            sub_health.file_count += 1;
+            aggregate_file_health(&file.bog, &file.computed, &mut sub_health);
+            stale_files.extend(file.stale);
+            all_file_bogs.push((decl.name.clone(), file.bog));
         }
 
         subsystems.push(sub_health);
@@ -178,13 +232,13 @@ pub fn compute_health(root: &Path) -> RepoHealth {
                 continue;
             }
             for ann in &bog.annotations {
-                if let Annotation::Skim(obs) = ann {
-                    if obs.skimsystem == sk_decl.name {
-                        sk_health.observation_count += 1;
-                        sk_health.observation_statuses.add(obs.status);
-                        if let Some(counts) = per_subsystem.get_mut(subsystem_name) {
-                            counts.add(obs.status);
-                        }
+                if let Annotation::Skim(obs) = ann
+                    && obs.skimsystem == sk_decl.name
+                {
+                    sk_health.observation_count += 1;
+                    sk_health.observation_statuses.add(obs.status);
+                    if let Some(counts) = per_subsystem.get_mut(subsystem_name) {
+                        counts.add(obs.status);
                     }
                 }
             }
@@ -196,18 +250,43 @@ pub fn compute_health(root: &Path) -> RepoHealth {
         skimsystems.push(sk_health);
     }
 
+    stale_files.sort_by(|(_, a), (_, b)| {
+        b.lag_days()
+            .cmp(&a.lag_days())
+            .then(b.stale_fns.len().cmp(&a.stale_fns.len()))
+            .then(a.bog_path.cmp(&b.bog_path))
+    });
+
     RepoHealth {
         name: repo_name,
         subsystems,
         skimsystems,
+        stale_files,
     }
 }
 
-fn aggregate_file_health(bog: &BogFile, health: &mut SubsystemHealth) {
+/// `computed` holds the dimensions derived from source and history; a hand-written
+/// value for one of them in `#[health]` is ignored. contract_compliance is never
+/// taken from `#[health]`, while staleness is when the files have no git history.
+fn aggregate_file_health(
+    bog: &BogFile,
+    computed: &[(&str, Status)],
+    health: &mut SubsystemHealth,
+) {
+    for (dim, status) in computed {
+        health
+            .dimensions
+            .entry(dim.to_string())
+            .or_default()
+            .add(*status);
+    }
     for ann in &bog.annotations {
         match ann {
             Annotation::Health(h) => {
                 for (dim, status) in &h.dimensions {
+                    if dim == "contract_compliance" || computed.iter().any(|(d, _)| d == dim) {
+                        continue;
+                    }
                     health
                         .dimensions
                         .entry(dim.clone())
@@ -223,6 +302,9 @@ fn aggregate_file_health(bog: &BogFile, health: &mut SubsystemHealth) {
     }
 }
 
+/// How many of the most stale sidecars the health report lists.
+const STALE_FILES_SHOWN: usize = 5;
+
 /// Format the health report for terminal display
 pub fn format_health_report(health: &RepoHealth) -> String {
     let mut out = String::new();
@@ -311,6 +393,39 @@ pub fn format_health_report(health: &RepoHealth) -> String {
         }
     }
 
+    if !health.stale_files.is_empty() {
+        out.push_str(&format!("  {}\n\n", "Most stale sidecars:".bold()));
+        for (status, stale) in health.stale_files.iter().take(STALE_FILES_SHOWN) {
+            out.push_str(&format!(
+                "  {} {}\n",
+                format_status(*status),
+                stale.bog_path.display()
+            ));
+            if stale.lag_days() > 0 {
+                out.push_str(&format!(
+                    "    Source changed {}, {} days after updated = {}\n",
+                    stale.source_changed,
+                    stale.lag_days(),
+                    stale.updated
+                ));
+            }
+            if !stale.stale_fns.is_empty() {
+                let names: Vec<&str> = stale.stale_fns.iter().map(|f| f.name.as_str()).collect();
+                out.push_str(&format!(
+                    "    Changed since annotated: {}\n",
+                    names.join(", ")
+                ));
+            }
+        }
+        if health.stale_files.len() > STALE_FILES_SHOWN {
+            out.push_str(&format!(
+                "  ... and {} more\n",
+                health.stale_files.len() - STALE_FILES_SHOWN
+            ));
+        }
+        out.push('\n');
+    }
+
     out
 }
 
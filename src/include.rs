@@ -0,0 +1,324 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::ast::{Annotation, BogFile};
+use crate::parser::{self, ParseError};
+use crate::walk::Walker;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IncludeError {
+    #[error("Failed to read {}: {message}", path.display())]
+    Read { path: PathBuf, message: String },
+
+    #[error("Parse error in {}: {}", path.display(), error.message())]
+    Parse {
+        path: PathBuf,
+        error: Box<ParseError>,
+    },
+
+    #[error("In {}: invalid include pattern '{pattern}': {message}", path.display())]
+    Pattern {
+        path: PathBuf,
+        pattern: String,
+        message: String,
+    },
+
+    #[error("In {}: included file '{pattern}' not found", path.display())]
+    NotFound { path: PathBuf, pattern: String },
+
+    #[error("In {}: include cycle {}", path.display(), format_chain(chain))]
+    Cycle { path: PathBuf, chain: Vec<PathBuf> },
+}
+
+fn format_chain(chain: &[PathBuf]) -> String {
+    chain
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// repo.bog with every `#[include]` replaced by the annotations of the files it names.
+#[derive(Debug, Default)]
+pub struct RepoBog {
+    pub bog: BogFile,
+    /// repo.bog and each included file with its source text, in load order
+    pub sources: Vec<(PathBuf, String)>,
+}
+
+/// Load repo.bog under `root`, resolving includes. Fails on the first unreadable,
+/// unparseable, missing or cyclic include.
+///
+/// # Errors
+///
+/// Returns the first `IncludeError`, naming the file it occurred in.
+pub fn load_repo_bog(root: &Path) -> Result<RepoBog, IncludeError> {
+    let mut loader = Loader {
+        recover: false,
+        ..Loader::default()
+    };
+    let mut repo = RepoBog::default();
+    loader.load(&root.join("repo.bog"), &mut repo);
+    match loader.errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(repo),
+    }
+}
+
+/// Like `load_repo_bog`, but keeps every annotation that parsed and returns the
+/// errors alongside instead of failing.
+pub fn load_repo_bog_recovering(root: &Path) -> (RepoBog, Vec<IncludeError>) {
+    let mut loader = Loader {
+        recover: true,
+        ..Loader::default()
+    };
+    let mut repo = RepoBog::default();
+    loader.load(&root.join("repo.bog"), &mut repo);
+    (repo, loader.errors)
+}
+
+impl RepoBog {
+    /// Whether `path` is repo.bog or one of the files it includes.
+    pub fn is_source(&self, path: &Path) -> bool {
+        let path = canonical(path);
+        self.sources.iter().any(|(p, _)| canonical(p) == path)
+    }
+}
+
+/// Every .bog file under `root` that is a sidecar: not repo.bog and not a file
+/// repo.bog includes.
+pub fn sidecar_files(root: &Path) -> Vec<PathBuf> {
+    let (repo, _) = load_repo_bog_recovering(root);
+    let included: HashSet<PathBuf> = repo.sources.iter().map(|(p, _)| canonical(p)).collect();
+    Walker::new(root)
+        .files_with_suffix(".bog")
+        .into_iter()
+        .filter(|p| {
+            p.file_name().is_none_or(|n| n != "repo.bog") && !included.contains(&canonical(p))
+        })
+        .collect()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[derive(Default)]
+struct Loader {
+    recover: bool,
+    /// Canonical paths of the files currently being loaded, outermost first
+    stack: Vec<PathBuf>,
+    /// Display paths matching `stack`, for cycle messages
+    chain: Vec<PathBuf>,
+    /// Canonical paths already loaded; a file included twice is only merged once
+    seen: Vec<PathBuf>,
+    errors: Vec<IncludeError>,
+}
+
+impl Loader {
+    fn load(&mut self, path: &Path, repo: &mut RepoBog) {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let content = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                self.errors.push(IncludeError::Read {
+                    path: path.to_path_buf(),
+                    message: e.to_string(),
+                });
+                return;
+            }
+        };
+
+        let bog = if self.recover {
+            let (bog, errors) = parser::parse_bog_recovering(&content);
+            self.errors
+                .extend(errors.into_iter().map(|error| IncludeError::Parse {
+                    path: path.to_path_buf(),
+                    error: Box::new(error),
+                }));
+            bog
+        } else {
+            match parser::parse_bog(&content) {
+                Ok(bog) => bog,
+                Err(error) => {
+                    self.errors.push(IncludeError::Parse {
+                        path: path.to_path_buf(),
+                        error: Box::new(error),
+                    });
+                    return;
+                }
+            }
+        };
+
+        repo.sources.push((path.to_path_buf(), content));
+        self.seen.push(canonical.clone());
+        self.stack.push(canonical);
+        self.chain.push(path.to_path_buf());
+
+        let dir = path.parent().unwrap_or(Path::new("."));
+        for ann in bog.annotations {
+            match ann {
+                Annotation::Include(pattern) => self.include(path, dir, &pattern, repo),
+                other => repo.bog.annotations.push(other),
+            }
+        }
+
+        self.stack.pop();
+        self.chain.pop();
+    }
+
+    fn include(&mut self, from: &Path, dir: &Path, pattern: &str, repo: &mut RepoBog) {
+        let full = dir.join(pattern);
+        let mut matches: Vec<PathBuf> = match glob::glob(&full.to_string_lossy()) {
+            Ok(paths) => paths.flatten().collect(),
+            Err(e) => {
+                self.errors.push(IncludeError::Pattern {
+                    path: from.to_path_buf(),
+                    pattern: pattern.to_string(),
+                    message: e.to_string(),
+                });
+                return;
+            }
+        };
+        matches.sort();
+
+        // A glob may legitimately match nothing yet; a plain path must exist
+        let is_glob = pattern.contains(['*', '?', '[']);
+        if matches.is_empty() && !is_glob {
+            self.errors.push(IncludeError::NotFound {
+                path: from.to_path_buf(),
+                pattern: pattern.to_string(),
+            });
+            return;
+        }
+
+        for path in matches {
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if self.stack.contains(&canonical) {
+                let mut chain = self.chain.clone();
+                chain.push(path);
+                self.errors.push(IncludeError::Cycle {
+                    path: from.to_path_buf(),
+                    chain,
+                });
+                continue;
+            }
+            if self.seen.contains(&canonical) {
+                continue;
+            }
+            self.load(&path, repo);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("bog-include-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("subsystems")).unwrap();
+        root
+    }
+
+    fn subsystem(name: &str) -> String {
+        format!(
+            "#[subsystem({name}) {{\n  owner = \"{name}-agent\",\n  files = [\"src/{name}.rs\"],\n  status = green\n}}]\n"
+        )
+    }
+
+    fn subsystem_names(bog: &BogFile) -> Vec<&str> {
+        bog.annotations
+            .iter()
+            .filter_map(|a| match a {
+                Annotation::Subsystem(s) => Some(s.name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_includes_are_merged_in_place_and_in_order() {
+        let root = temp_root();
+        std::fs::write(
+            root.join("repo.bog"),
+            format!(
+                "{}#[include(\"subsystems/*.bog\")]\n{}",
+                subsystem("core"),
+                subsystem("zeta")
+            ),
+        )
+        .unwrap();
+        std::fs::write(root.join("subsystems/b.bog"), subsystem("beta")).unwrap();
+        std::fs::write(
+            root.join("subsystems/a.bog"),
+            format!("{}#[include(\"b.bog\")]\n", subsystem("alpha")),
+        )
+        .unwrap();
+
+        let repo = load_repo_bog(&root).unwrap();
+        let b_is_source = repo.is_source(&root.join("subsystems/./b.bog"));
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(subsystem_names(&repo.bog), ["core", "alpha", "beta", "zeta"]);
+        assert_eq!(repo.sources.len(), 3);
+        assert!(b_is_source);
+        assert!(!repo.bog.annotations.iter().any(|a| matches!(a, Annotation::Include(_))));
+    }
+
+    #[test]
+    fn test_sidecar_files_skip_repo_bog_and_includes() {
+        let root = temp_root();
+        std::fs::write(root.join("repo.bog"), "#[include(\"subsystems/a.bog\")]\n").unwrap();
+        std::fs::write(root.join("subsystems/a.bog"), subsystem("alpha")).unwrap();
+        std::fs::write(root.join("subsystems/lib.rs.bog"), "").unwrap();
+
+        let sidecars = sidecar_files(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(sidecars.len(), 1);
+        assert!(sidecars[0].ends_with("subsystems/lib.rs.bog"));
+    }
+
+    #[test]
+    fn test_include_cycle_is_reported() {
+        let root = temp_root();
+        std::fs::write(root.join("repo.bog"), "#[include(\"subsystems/a.bog\")]\n").unwrap();
+        std::fs::write(root.join("subsystems/a.bog"), "#[include(\"b.bog\")]\n").unwrap();
+        std::fs::write(root.join("subsystems/b.bog"), "#[include(\"a.bog\")]\n").unwrap();
+
+        let err = load_repo_bog(&root).unwrap_err();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let IncludeError::Cycle { path, chain } = &err else {
+            panic!("expected cycle, got {err}");
+        };
+        assert!(path.ends_with("subsystems/b.bog"));
+        assert_eq!(chain.len(), 4);
+        assert!(err.to_string().contains("repo.bog -> "));
+    }
+
+    #[test]
+    fn test_errors_name_the_included_file() {
+        let root = temp_root();
+        std::fs::write(
+            root.join("repo.bog"),
+            "#[include(\"subsystems/bad.bog\")]\n#[include(\"subsystems/missing.bog\")]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("subsystems/bad.bog"),
+            format!("{}#[subsystem(x) {{ owner = \"a\", status = gren }}]\n", subsystem("ok")),
+        )
+        .unwrap();
+
+        let strict = load_repo_bog(&root).unwrap_err();
+        let (repo, errors) = load_repo_bog_recovering(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!(matches!(&strict, IncludeError::Parse { path, .. } if path.ends_with("subsystems/bad.bog")));
+        assert_eq!(subsystem_names(&repo.bog), ["ok"]);
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(&errors[1], IncludeError::NotFound { path, .. } if path.ends_with("repo.bog")));
+    }
+}
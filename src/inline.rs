@@ -0,0 +1,242 @@
+use std::path::Path;
+
+use crate::ast::{Annotation, BogFile, FnPath};
+use crate::diagnostic;
+use crate::lang::{self, LanguageBackend};
+use crate::parser;
+use crate::treesitter::{self, Symbol};
+
+/// Doc comment lines starting with this marker hold the body of an inline annotation.
+const MARKER: &str = "bog:";
+
+#[derive(Debug, thiserror::Error)]
+pub enum InlineError {
+    #[error("line {line}: inline annotation on '{item}': {message}")]
+    Parse {
+        item: String,
+        line: usize,
+        message: String,
+    },
+
+    #[error("line {line}: '{item}' is annotated both inline and in the sidecar")]
+    Conflict { item: String, line: usize },
+}
+
+/// An annotation written as `/// bog: key = value` lines in the doc comment of a fn,
+/// type, trait or const.
+#[derive(Debug, Clone)]
+pub struct InlineAnnotation {
+    pub annotation: Annotation,
+    /// Source line of the first `bog:` line
+    pub line: usize,
+}
+
+/// Collect the inline annotations in Rust source, in source order. Source that
+/// tree-sitter cannot parse yields nothing.
+pub fn harvest(source: &str) -> (Vec<InlineAnnotation>, Vec<InlineError>) {
+    if !has_marker_line(source) {
+        return (Vec::new(), Vec::new());
+    }
+    let Ok(symbols) = treesitter::extract_symbols(source) else {
+        return (Vec::new(), Vec::new());
+    };
+    let (found, errors) = collect(&symbols);
+    (found.into_iter().map(|(_, a)| a).collect(), errors)
+}
+
+/// Append the inline annotations of `source` to a sidecar's annotations. An item
+/// annotated in both places is a conflict: the sidecar annotation is kept and the
+/// inline one dropped. Only backends that record `///` docs (Rust) yield any.
+///
+/// bog: status = green,
+/// bog: deps = [has_marker_line, collect, annotates, item_name],
+/// bog: refs = [validator::validate_project, context::load_context, health::compute_health],
+/// bog: contract = {
+/// bog:   in = [(bog, "&mut BogFile"), (source, "&str"), (backend, "&dyn LanguageBackend")],
+/// bog:   out = "Vec<InlineError>"
+/// bog: }
+pub fn merge(
+    bog: &mut BogFile,
+    source: &str,
+    backend: &dyn LanguageBackend,
+) -> Vec<InlineError> {
+    // Most files have no inline annotations; skip the tree-sitter parse for them
+    if !has_marker_line(source) {
+        return Vec::new();
+    }
+    let Ok(symbols) = backend.extract_symbols(source) else {
+        return Vec::new();
+    };
+    let (found, mut errors) = collect(&symbols);
+    for (symbol, inline) in found {
+        if bog.annotations.iter().any(|a| annotates(a, &symbols, symbol)) {
+            errors.push(InlineError::Conflict {
+                item: item_name(symbol),
+                line: inline.line,
+            });
+            continue;
+        }
+        bog.annotations.push(inline.annotation);
+    }
+    errors
+}
+
+/// `merge` with the source read from disk by the backend for its extension; an
+/// unreadable source or one no backend reads merges nothing.
+pub fn merge_from_source(bog: &mut BogFile, source_path: &Path) -> Vec<InlineError> {
+    let Some(backend) = lang::backend_for(source_path) else {
+        return Vec::new();
+    };
+    match std::fs::read_to_string(source_path) {
+        Ok(source) => merge(bog, &source, backend),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn has_marker_line(source: &str) -> bool {
+    source.lines().any(|line| {
+        line.trim_start()
+            .strip_prefix("///")
+            .is_some_and(|doc| doc.trim_start().starts_with(MARKER))
+    })
+}
+
+fn collect(symbols: &[Symbol]) -> (Vec<(&Symbol, InlineAnnotation)>, Vec<InlineError>) {
+    let mut found = Vec::new();
+    let mut errors = Vec::new();
+
+    for symbol in symbols {
+        let bog_lines: Vec<(usize, &str)> = symbol
+            .doc
+            .iter()
+            .filter_map(|(line, text)| Some((*line, text.trim_start().strip_prefix(MARKER)?)))
+            .collect();
+        let Some(&(first_line, _)) = bog_lines.first() else {
+            continue;
+        };
+        let kind = match symbol.kind.item_kind() {
+            Some(kind) => kind.to_string(),
+            None => "fn".to_string(),
+        };
+        let name = item_name(symbol);
+        let body: Vec<&str> = bog_lines.iter().map(|(_, text)| *text).collect();
+        let text = format!("#[{kind}({name}) {{\n{}\n}}]", body.join("\n"));
+
+        match parser::parse_bog(&text) {
+            Ok(mut bog) => found.push((
+                symbol,
+                InlineAnnotation {
+                    annotation: bog.annotations.remove(0),
+                    line: first_line,
+                },
+            )),
+            Err(e) => {
+                // Line 1 of the generated text is the header; line n+1 is bog line n
+                let (line, _) = diagnostic::line_col(&text, e.span().start);
+                let line = bog_lines
+                    .get(line.saturating_sub(2))
+                    .map_or(first_line, |(l, _)| *l);
+                errors.push(InlineError::Parse {
+                    item: name,
+                    line,
+                    message: e.message(),
+                });
+            }
+        }
+    }
+
+    (found, errors)
+}
+
+/// Methods are named by their impl so the generated reference is unambiguous.
+fn item_name(symbol: &Symbol) -> String {
+    if symbol.kind.is_fn() {
+        symbol.qualified_name()
+    } else {
+        symbol.name.clone()
+    }
+}
+
+/// Whether a sidecar annotation describes `target`.
+fn annotates(ann: &Annotation, symbols: &[Symbol], target: &Symbol) -> bool {
+    match ann {
+        Annotation::Fn(f) if target.kind.is_fn() => FnPath::parse(&f.name).is_ok_and(|path| {
+            treesitter::resolve_fn(symbols, &path)
+                .iter()
+                .any(|s| std::ptr::eq(*s, target))
+        }),
+        Annotation::Item(item) => {
+            item.name == target.name && target.kind.item_kind() == Some(item.kind)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{ItemKind, Status};
+    use crate::lang::rust::RUST;
+
+    const SOURCE: &str = r#"
+/// Logs a user in.
+/// bog: status = green,
+/// bog: contract = { in = [(name, "&str")], out = "bool" }
+pub fn login(name: &str) -> bool { true }
+
+pub struct Auth;
+
+impl Auth {
+    /// bog: status = yellow, description = "Builds one"
+    pub fn new() -> Self { Auth }
+}
+
+/// bog: status = red
+pub trait Verify {}
+
+/// bog: status = gren
+pub fn broken() {}
+"#;
+
+    #[test]
+    fn test_harvest_builds_annotations_from_doc_comments() {
+        let (found, errors) = harvest(SOURCE);
+        assert_eq!(found.len(), 3);
+        let Annotation::Fn(login) = &found[0].annotation else {
+            panic!("expected fn");
+        };
+        assert_eq!(login.name, "login");
+        assert_eq!(login.contract.as_ref().unwrap().output.as_deref(), Some("bool"));
+        assert_eq!(found[0].line, 3);
+        assert!(matches!(&found[1].annotation, Annotation::Fn(f) if f.name == "Auth::new" && f.status == Status::Yellow));
+        assert!(matches!(&found[2].annotation, Annotation::Item(i) if i.kind == ItemKind::Trait));
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(&errors[0], InlineError::Parse { item, line: 17, .. } if item == "broken"));
+    }
+
+    #[test]
+    fn test_merge_reports_conflicts_and_keeps_sidecar() {
+        let mut bog = parser::parse_bog(
+            r#"
+#[fn(new) { status = green }]
+#[trait(Verify) { status = green }]
+"#,
+        )
+        .unwrap();
+        let errors = merge(&mut bog, SOURCE, &RUST);
+
+        let conflicts: Vec<String> = errors
+            .iter()
+            .filter_map(|e| match e {
+                InlineError::Conflict { item, .. } => Some(item.clone()),
+                InlineError::Parse { .. } => None,
+            })
+            .collect();
+        assert_eq!(conflicts, ["Auth::new", "Verify"]);
+        // Sidecar entries kept, only the non-conflicting inline fn appended
+        assert_eq!(bog.annotations.len(), 3);
+        assert!(matches!(&bog.annotations[0], Annotation::Fn(f) if f.status == Status::Green));
+        assert!(matches!(&bog.annotations[2], Annotation::Fn(f) if f.name == "login"));
+    }
+}
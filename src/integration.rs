@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::fmt::Write;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
 use std::process::Command;
@@ -7,7 +6,10 @@ use std::process::Command;
 use colored::Colorize;
 use serde::Deserialize;
 
-use crate::ast::{IntegrationFormat, IntegrationSpec};
+use crate::ast::{
+    Annotation, ChangeRequest, IntegrationFormat, IntegrationSpec, SkimObservation, Status, Value,
+};
+use crate::cst::{self, SyntaxNode};
 use crate::stub;
 use crate::treesitter;
 
@@ -211,7 +213,8 @@ fn map_findings_to_subsystems(
     (by_subsystem, unowned)
 }
 
-/// Find which function encloses a given line number using tree-sitter.
+/// Find which function encloses a given line number using tree-sitter, qualified by its
+/// impl so the reference stays unambiguous.
 fn find_enclosing_function(file_path: &str, line: usize, root: &Path) -> String {
     let source_path = root.join(file_path);
     let Ok(source) = std::fs::read_to_string(&source_path) else {
@@ -220,9 +223,9 @@ fn find_enclosing_function(file_path: &str, line: usize, root: &Path) -> String
     let Ok(symbols) = treesitter::extract_symbols(&source) else {
         return "unknown".to_string();
     };
-    for sym in &symbols {
+    for sym in symbols.iter().filter(|s| s.kind.is_fn()) {
         if line >= sym.start_line && line <= sym.end_line {
-            return sym.name.clone();
+            return sym.qualified_name();
         }
     }
     "file".to_string()
@@ -260,75 +263,88 @@ pub fn write_integration_results(
             by_file.entry(f.file_path.clone()).or_default().push(f);
         }
     }
+    // Tools do not promise an output order; sorting keeps the numbering of repeated
+    // findings, and so their request ids, the same from run to run
+    for findings in by_file.values_mut() {
+        findings.sort_by(|a, b| {
+            (a.line_start, a.line_end, &a.code, &a.message, &a.rendered)
+                .cmp(&(b.line_start, b.line_end, &b.code, &b.message, &b.rendered))
+        });
+    }
 
     for (file_path, findings) in &by_file {
         let bog_path = root.join(format!("{file_path}.bog"));
-        let mut content = if bog_path.exists() {
+        let content = if bog_path.exists() {
             std::fs::read_to_string(&bog_path).unwrap_or_default()
         } else {
             let source_path = root.join(file_path);
             stub::generate_file_header(&source_path, root)
         };
+        let mut tree = cst::parse_cst(&content).map_err(|e| {
+            IntegrationError::WriteFailed(bog_path.display().to_string(), e.to_string())
+        })?;
 
-        // Remove previous integration section (from marker to next marker or EOF)
-        if let Some(marker_pos) = content.find(&marker) {
-            // Look for the next marker after this one
-            let after_marker = marker_pos + marker.len();
-            if let Some(next_marker) = content[after_marker..].find("\n// [integration:") {
-                // Truncate just this section, keep the rest
-                let keep_after = after_marker + next_marker;
-                let before = &content[..marker_pos];
-                let after = &content[keep_after..];
-                content = format!("{before}{after}");
-            } else {
-                content.truncate(marker_pos);
-            }
-        }
-
-        // Ensure trailing newline
-        if !content.ends_with('\n') {
-            content.push('\n');
-        }
-
-        // Write marker
-        let _ = write!(content, "\n{marker}\n");
+        // Remove the previous section for this integration, leaving everything else untouched
+        tree.remove_marked_section(&marker, |node| match node.annotation_name() {
+            Some("skim") => node.annotation_arg() == Some(skimsystem),
+            Some("change_requests") => true,
+            _ => false,
+        });
 
         // Write skim observation
         let skim_status = if findings.len() > 5 {
-            "red"
+            Status::Red
         } else if findings.is_empty() {
-            "green"
+            Status::Green
         } else {
-            "yellow"
+            Status::Yellow
         };
-        let _ = write!(
-            content,
-            "#[skim({skimsystem}) {{\n  status = {skim_status},\n  notes = \"{integration_name}: {} warning(s)\"\n}}]\n",
-            findings.len()
-        );
+        let skim = SkimObservation {
+            skimsystem: skimsystem.to_string(),
+            status: skim_status,
+            notes: Some(format!("{integration_name}: {} warning(s)", findings.len())),
+            target: None,
+        };
+        append_generated(&mut tree, &skim.to_string(), Some(&marker), &bog_path)?;
 
         // Write change_requests block
         if !findings.is_empty() {
-            content.push_str("\n#[change_requests {\n");
+            let mut requests = Vec::new();
+            // The same lint can fire more than once on a line; number the repeats
+            let mut seen: HashMap<String, usize> = HashMap::new();
             for finding in findings {
-                let id = generate_finding_id(skimsystem, integration_name, finding);
                 let target_fn = find_enclosing_function(&finding.file_path, finding.line_start, root);
-                let target_str = if target_fn == "file" {
-                    "file".to_string()
+                let target = if target_fn == "file" {
+                    Value::Ident("file".to_string())
                 } else {
-                    format!("fn({target_fn})")
+                    Value::FnRef(target_fn)
                 };
-                let desc = finding.message.replace('"', "\\\"");
-                let _ = write!(
-                    content,
-                    "  #[request(\n    id = \"{id}\",\n    from = \"{owner}\",\n    target = {target_str},\n    type = lint_warning,\n    status = pending,\n    created = \"{today}\",\n    description = \"{} (line {}): {desc}\"\n  )]\n",
-                    finding.code, finding.line_start
-                );
+                let mut id = generate_finding_id(skimsystem, integration_name, finding);
+                let count = seen.entry(id.clone()).or_default();
+                *count += 1;
+                if *count > 1 {
+                    id = format!("{id}-{count}");
+                }
+                requests.push(ChangeRequest {
+                    id,
+                    from: owner.to_string(),
+                    target,
+                    change_type: "lint_warning".to_string(),
+                    status: "pending".to_string(),
+                    priority: None,
+                    created: today.clone(),
+                    description: format!(
+                        "{} (line {}): {}",
+                        finding.code, finding.line_start, finding.message
+                    ),
+                });
                 report.change_requests_generated += 1;
             }
-            content.push_str("}]\n");
+            let requests = Annotation::ChangeRequests(requests).to_string();
+            append_generated(&mut tree, &requests, None, &bog_path)?;
         }
 
+        let content = tree.to_string();
         std::fs::write(&bog_path, &content).map_err(|e| {
             IntegrationError::WriteFailed(bog_path.display().to_string(), e.to_string())
         })?;
@@ -338,6 +354,20 @@ pub fn write_integration_results(
     Ok(())
 }
 
+/// Parse a generated annotation and append it to a sidecar's syntax tree.
+fn append_generated(
+    tree: &mut SyntaxNode,
+    text: &str,
+    leading_comment: Option<&str>,
+    bog_path: &Path,
+) -> Result<(), IntegrationError> {
+    let node = cst::parse_annotation_cst(text).map_err(|e| {
+        IntegrationError::WriteFailed(bog_path.display().to_string(), e.to_string())
+    })?;
+    tree.append_annotation(node, leading_comment);
+    Ok(())
+}
+
 /// Print a summary of findings grouped by subsystem.
 pub fn print_report(report: &IntegrationReport) {
     if let Some(err) = &report.build_error {
@@ -446,4 +476,105 @@ mod tests {
             generate_finding_id("sk", "int", &f2)
         );
     }
+
+    #[test]
+    fn test_write_integration_results_keeps_user_content() {
+        let root = std::env::temp_dir().join(format!("bog-integration-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let bog_path = root.join("src/foo.rs.bog");
+        std::fs::write(
+            &bog_path,
+            r#"// hand-written note
+#[description { Foo }]
+
+// [integration:sk:clippy]
+#[skim(sk) {
+  status = red,
+  notes = "clippy: 9 warning(s)"
+}]
+
+// kept after the section
+#[fn(kept) {
+  status = green
+}]
+"#,
+        )
+        .unwrap();
+
+        let finding = IntegrationFinding {
+            file_path: "src/foo.rs".to_string(),
+            line_start: 3,
+            line_end: 3,
+            code: "clippy::a".to_string(),
+            level: FindingLevel::Warning,
+            message: "uses \"quotes\"".to_string(),
+            rendered: String::new(),
+        };
+        let mut report = IntegrationReport {
+            skimsystem: "sk".to_string(),
+            integration_name: "clippy".to_string(),
+            total_findings: 2,
+            // Reported twice on the same line, e.g. at two columns
+            findings_by_subsystem: HashMap::from([(
+                "core".to_string(),
+                vec![finding.clone(), finding],
+            )]),
+            unowned_findings: Vec::new(),
+            files_written: 0,
+            change_requests_generated: 0,
+            build_error: None,
+        };
+        write_integration_results("sk", "clippy", "owner", &mut report, &root).unwrap();
+
+        let content = std::fs::read_to_string(&bog_path).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+        assert!(content.starts_with("// hand-written note\n"));
+        assert!(content.contains("// kept after the section\n"));
+        assert!(content.contains("#[fn(kept)"));
+        assert!(!content.contains("9 warning(s)"));
+        assert_eq!(content.matches("// [integration:sk:clippy]").count(), 1);
+        assert!(content.contains("notes = \"clippy: 2 warning(s)\""));
+        assert_eq!(report.change_requests_generated, 2);
+        let id = generate_finding_id("sk", "clippy", &report.findings_by_subsystem["core"][0]);
+        assert!(content.contains(&format!("id = \"{id}\"")));
+        assert!(content.contains(&format!("id = \"{id}-2\"")));
+        assert!(crate::parser::parse_bog(&content).is_ok());
+    }
+
+    #[test]
+    fn test_repeated_findings_are_numbered_in_a_stable_order() {
+        let root = std::env::temp_dir().join(format!("bog-integration-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let finding = |message: &str| IntegrationFinding {
+            file_path: "src/foo.rs".to_string(),
+            line_start: 3,
+            line_end: 3,
+            code: "clippy::a".to_string(),
+            level: FindingLevel::Warning,
+            message: message.to_string(),
+            rendered: String::new(),
+        };
+        let write = |findings: Vec<IntegrationFinding>| {
+            let mut report = IntegrationReport {
+                skimsystem: "sk".to_string(),
+                integration_name: "clippy".to_string(),
+                total_findings: findings.len(),
+                findings_by_subsystem: HashMap::from([("core".to_string(), findings)]),
+                unowned_findings: Vec::new(),
+                files_written: 0,
+                change_requests_generated: 0,
+                build_error: None,
+            };
+            write_integration_results("sk", "clippy", "owner", &mut report, &root).unwrap();
+            std::fs::read_to_string(root.join("src/foo.rs.bog")).unwrap()
+        };
+
+        let first = write(vec![finding("first"), finding("second")]);
+        let reversed = write(vec![finding("second"), finding("first")]);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(first, reversed);
+        let id = generate_finding_id("sk", "clippy", &finding("first"));
+        let numbered = first.find(&format!("id = \"{id}-2\"")).unwrap();
+        assert!(first[numbered..].contains("second"));
+    }
 }
@@ -0,0 +1,133 @@
+pub mod rust;
+
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "typescript")]
+pub mod typescript;
+
+use std::path::{Path, PathBuf};
+
+use tree_sitter::{Node, Parser};
+
+use crate::treesitter::{Symbol, TreeSitterError};
+use crate::walk::Walker;
+
+/// A source language bog can check sidecars against: its tree-sitter grammar,
+/// how symbols (with the calls in their bodies) are read from a syntax tree, and
+/// where sidecars live.
+pub trait LanguageBackend: Sync {
+    /// Name of the language, as in bog.toml's `[tree_sitter] language`
+    fn name(&self) -> &'static str;
+
+    /// File extensions of its sources, without the dot
+    fn extensions(&self) -> &'static [&'static str];
+
+    fn grammar(&self) -> tree_sitter::Language;
+
+    /// Functions, methods and named items declared in a parsed file, in source order
+    fn symbols(&self, root: Node, source: &[u8]) -> Vec<Symbol>;
+
+    /// The sidecar describing a source file: `src/a.rs` is described by `src/a.rs.bog`.
+    fn sidecar_path(&self, source: &Path) -> PathBuf {
+        PathBuf::from(format!("{}.bog", source.display()))
+    }
+
+    /// The source file a sidecar describes, if it is one of this language's.
+    fn source_path(&self, sidecar: &Path) -> Option<PathBuf> {
+        let source = PathBuf::from(sidecar.to_str()?.strip_suffix(".bog")?);
+        let ext = source.extension()?.to_str()?;
+        self.extensions().contains(&ext).then_some(source)
+    }
+
+    fn extract_symbols(&self, source: &str) -> Result<Vec<Symbol>, TreeSitterError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&self.grammar())
+            .map_err(|e| TreeSitterError::Init(e.to_string()))?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or(TreeSitterError::ParseFailed)?;
+        Ok(self.symbols(tree.root_node(), source.as_bytes()))
+    }
+}
+
+/// The backends compiled in: Rust always, others behind their cargo feature.
+static BACKENDS: &[&dyn LanguageBackend] = &[
+    &rust::RUST,
+    #[cfg(feature = "python")]
+    &python::PYTHON,
+    #[cfg(feature = "typescript")]
+    &typescript::TYPESCRIPT,
+    #[cfg(feature = "typescript")]
+    &typescript::TSX,
+];
+
+pub fn backends() -> &'static [&'static dyn LanguageBackend] {
+    BACKENDS
+}
+
+/// The backend for a source file, chosen by its extension.
+pub fn backend_for(source: &Path) -> Option<&'static dyn LanguageBackend> {
+    let ext = source.extension()?.to_str()?;
+    backends()
+        .iter()
+        .copied()
+        .find(|b| b.extensions().contains(&ext))
+}
+
+/// The backend named `name`, if it is compiled in.
+pub fn backend_named(name: &str) -> Option<&'static dyn LanguageBackend> {
+    backends().iter().copied().find(|b| b.name() == name)
+}
+
+/// The source file a sidecar describes and the backend that reads it; `None` for
+/// sidecars of no known language, such as repo.bog.
+pub fn source_for(sidecar: &Path) -> Option<(PathBuf, &'static dyn LanguageBackend)> {
+    backends()
+        .iter()
+        .find_map(|b| Some((b.source_path(sidecar)?, *b)))
+}
+
+/// Every source file some backend reads, in walk order.
+pub fn source_files(walker: &Walker) -> Vec<PathBuf> {
+    walker.files_where(|name| backend_for(Path::new(name)).is_some())
+}
+
+/// Every sidecar that describes a source file, in walk order.
+pub fn sidecar_files(walker: &Walker) -> Vec<PathBuf> {
+    walker.files_where(|name| source_for(Path::new(name)).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_is_chosen_by_extension() {
+        assert_eq!(backend_for(Path::new("src/a.rs")).unwrap().name(), "rust");
+        assert!(backend_for(Path::new("src/a.rs.bog")).is_none());
+        assert!(backend_for(Path::new("README")).is_none());
+        assert_eq!(backend_named("rust").unwrap().extensions(), ["rs"]);
+
+        let (source, backend) = source_for(Path::new("src/a.rs.bog")).unwrap();
+        assert_eq!(source, Path::new("src/a.rs"));
+        assert_eq!(backend.sidecar_path(&source), Path::new("src/a.rs.bog"));
+        assert!(source_for(Path::new("repo.bog")).is_none());
+        assert!(source_for(Path::new("src/a.rs")).is_none());
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_sources_are_recognized() {
+        let (source, backend) = source_for(Path::new("pkg/auth.py.bog")).unwrap();
+        assert_eq!(source, Path::new("pkg/auth.py"));
+        assert_eq!(backend.name(), "python");
+    }
+
+    #[cfg(feature = "typescript")]
+    #[test]
+    fn test_typescript_and_tsx_use_their_own_grammars() {
+        assert_eq!(backend_for(Path::new("web/app.ts")).unwrap().name(), "typescript");
+        assert_eq!(backend_for(Path::new("web/app.tsx")).unwrap().name(), "tsx");
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::BTreeSet;
+
+use tree_sitter::Node;
+
+use super::LanguageBackend;
+use crate::treesitter::{Symbol, SymbolKind};
+
+/// Python sources, read with tree-sitter-python.
+#[derive(Debug, Clone, Copy)]
+pub struct Python;
+
+pub static PYTHON: Python = Python;
+
+impl LanguageBackend for Python {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["py", "pyi"]
+    }
+
+    fn grammar(&self) -> tree_sitter::Language {
+        tree_sitter_python::LANGUAGE.into()
+    }
+
+    fn symbols(&self, root: Node, source: &[u8]) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        collect_symbols(root, source, None, &mut symbols);
+        symbols
+    }
+}
+
+fn children(node: Node) -> impl Iterator<Item = Node> {
+    (0..node.child_count()).filter_map(move |i| node.child(i))
+}
+
+fn text(node: Node, source: &[u8]) -> Option<String> {
+    node.utf8_text(source).ok().map(str::to_string)
+}
+
+/// Module-level and class-level definitions; function bodies are not entered.
+fn collect_symbols(node: Node, source: &[u8], class: Option<&str>, symbols: &mut Vec<Symbol>) {
+    for child in children(node) {
+        let child = match child.kind() {
+            "decorated_definition" => match child.child_by_field_name("definition") {
+                Some(def) => def,
+                None => continue,
+            },
+            _ => child,
+        };
+        match child.kind() {
+            "function_definition" => {
+                if let Some(mut sym) = extract_function(child, source) {
+                    if let Some(class) = class {
+                        sym.kind = SymbolKind::Method;
+                        sym.impl_type = Some(class.to_string());
+                        sym.params.retain(|(p, _)| p != "self" && p != "cls");
+                    }
+                    symbols.push(sym);
+                }
+            }
+            "class_definition" => {
+                let Some(name_node) = child.child_by_field_name("name") else {
+                    continue;
+                };
+                let Some(name) = text(name_node, source) else {
+                    continue;
+                };
+                symbols.push(item(&name, SymbolKind::Class, name_node, child));
+                if let Some(body) = child.child_by_field_name("body") {
+                    collect_symbols(body, source, Some(&name), symbols);
+                }
+            }
+            "expression_statement" if class.is_none() => {
+                // `MAX_USERS = 10`: upper-case module globals are constants by convention
+                if let Some(assign) = child.child(0)
+                    && assign.kind() == "assignment"
+                    && let Some(left) = assign.child_by_field_name("left")
+                    && left.kind() == "identifier"
+                    && let Some(name) = text(left, source)
+                    && name.chars().any(|c| c.is_ascii_uppercase())
+                    && !name.chars().any(|c| c.is_ascii_lowercase())
+                {
+                    symbols.push(item(&name, SymbolKind::Const, left, child));
+                }
+            }
+            "if_statement" | "try_statement" | "block" => {
+                collect_symbols(child, source, class, symbols);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn item(name: &str, kind: SymbolKind, name_node: Node, node: Node) -> Symbol {
+    Symbol {
+        name: name.to_string(),
+        kind,
+        params: Vec::new(),
+        return_type: None,
+        start_line: name_node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        calls: Vec::new(),
+        impl_type: None,
+        impl_trait: None,
+        doc: Vec::new(),
+        visibility: visibility(name),
+        is_async: false,
+        is_unsafe: false,
+        is_const: false,
+        generics: None,
+        where_clause: None,
+        module_path: Vec::new(),
+        module_public: true,
+    }
+}
+
+/// Python has no modifiers: a leading underscore marks a name private.
+fn visibility(name: &str) -> Option<String> {
+    (!name.starts_with('_')).then(|| "pub".to_string())
+}
+
+fn extract_function(node: Node, source: &[u8]) -> Option<Symbol> {
+    let name_node = node.child_by_field_name("name")?;
+    let name = text(name_node, source)?;
+
+    let mut params = Vec::new();
+    if let Some(list) = node.child_by_field_name("parameters") {
+        for param in children(list) {
+            let (name, ty) = match param.kind() {
+                "identifier" => (Some(param), None),
+                "typed_parameter" => (param.child(0), param.child_by_field_name("type")),
+                "default_parameter" => (param.child_by_field_name("name"), None),
+                "typed_default_parameter" => (
+                    param.child_by_field_name("name"),
+                    param.child_by_field_name("type"),
+                ),
+                "list_splat_pattern" | "dictionary_splat_pattern" => (Some(param), None),
+                _ => continue,
+            };
+            let name = name.and_then(|n| text(n, source)).unwrap_or_else(|| "_".to_string());
+            let ty = ty.and_then(|n| text(n, source)).unwrap_or_else(|| "?".to_string());
+            params.push((name, ty));
+        }
+    }
+
+    let return_type = node
+        .child_by_field_name("return_type")
+        .and_then(|n| text(n, source));
+
+    let mut calls = BTreeSet::new();
+    let mut locals: BTreeSet<String> = params
+        .iter()
+        .map(|(p, _)| p.trim_start_matches('*').to_string())
+        .chain(["self".to_string(), "cls".to_string()])
+        .collect();
+    if let Some(body) = node.child_by_field_name("body") {
+        extract_calls(body, source, &mut calls);
+        extract_assigned_names(body, source, &mut locals);
+    }
+    calls.remove(&name);
+    calls.retain(|c| !locals.contains(c.split("::").next().unwrap_or(c)));
+
+    Some(Symbol {
+        calls: calls.into_iter().collect(),
+        params,
+        return_type,
+        is_async: children(node).any(|c| c.kind() == "async"),
+        ..item(&name, SymbolKind::Function, name_node, node)
+    })
+}
+
+/// `f()` is recorded as `f` and `module.f()` as `module::f`; longer attribute
+/// chains are calls on objects and are skipped.
+fn extract_calls(node: Node, source: &[u8], calls: &mut BTreeSet<String>) {
+    if node.kind() == "call"
+        && let Some(func) = node.child_by_field_name("function")
+    {
+        match func.kind() {
+            "identifier" => {
+                if let Some(name) = text(func, source)
+                    && !is_builtin(&name)
+                {
+                    calls.insert(name);
+                }
+            }
+            // Only `module.f()`: deeper chains are attribute access on objects
+            "attribute" => {
+                if let Some(object) = func.child_by_field_name("object")
+                    && object.kind() == "identifier"
+                    && let Some(object) = text(object, source)
+                    && let Some(attr) = func.child_by_field_name("attribute")
+                    && let Some(attr) = text(attr, source)
+                {
+                    calls.insert(format!("{object}::{attr}"));
+                }
+            }
+            _ => {}
+        }
+    }
+    for child in children(node) {
+        extract_calls(child, source, calls);
+    }
+}
+
+/// Names bound by `name = ...`, `for name in` and `with ... as name` in a body.
+fn extract_assigned_names(node: Node, source: &[u8], names: &mut BTreeSet<String>) {
+    let target = match node.kind() {
+        "assignment" | "augmented_assignment" => node.child_by_field_name("left"),
+        "for_statement" => node.child_by_field_name("left"),
+        "as_pattern" => node.child_by_field_name("alias"),
+        _ => None,
+    };
+    if let Some(target) = target {
+        let target = match target.kind() {
+            "as_pattern_target" => target.child(0).unwrap_or(target),
+            _ => target,
+        };
+        if target.kind() == "identifier"
+            && let Some(name) = text(target, source)
+        {
+            names.insert(name);
+        }
+    }
+    for child in children(node) {
+        extract_assigned_names(child, source, names);
+    }
+}
+
+fn is_builtin(name: &str) -> bool {
+    matches!(
+        name,
+        "print" | "len" | "range" | "isinstance" | "issubclass" | "str" | "int" | "float"
+            | "bool" | "bytes" | "list" | "dict" | "set" | "frozenset" | "tuple" | "super"
+            | "open" | "enumerate" | "zip" | "map" | "filter" | "sorted" | "reversed" | "min"
+            | "max" | "sum" | "any" | "all" | "abs" | "round" | "getattr" | "setattr"
+            | "hasattr" | "delattr" | "type" | "iter" | "next" | "repr" | "hash" | "id"
+            | "format" | "object" | "callable" | "vars" | "dir"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_python_symbols_and_calls() {
+        let source = r#"
+import db
+
+MAX_USERS = 10
+
+class Auth(Base):
+    def __init__(self, secret: str):
+        self.secret = secret
+
+    @staticmethod
+    def verify(token: str, strict=False) -> bool:
+        parsed = parse(token)
+        parsed.check()
+        return db.lookup(parsed) is not None
+
+def _helper(*args, **kwargs):
+    print(len(args))
+"#;
+        let symbols = PYTHON.extract_symbols(source).unwrap();
+        let kinds: Vec<(&str, SymbolKind)> =
+            symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+        assert_eq!(
+            kinds,
+            [
+                ("MAX_USERS", SymbolKind::Const),
+                ("Auth", SymbolKind::Class),
+                ("__init__", SymbolKind::Method),
+                ("verify", SymbolKind::Method),
+                ("_helper", SymbolKind::Function),
+            ]
+        );
+        let verify = &symbols[3];
+        assert_eq!(verify.qualified_name(), "Auth::verify");
+        assert_eq!(
+            verify.params,
+            [("token".to_string(), "str".to_string()), ("strict".to_string(), "?".to_string())]
+        );
+        assert_eq!(verify.return_type.as_deref(), Some("bool"));
+        assert_eq!(verify.calls, ["db::lookup", "parse"]);
+        assert_eq!(symbols[2].params, [("secret".to_string(), "str".to_string())]);
+        assert!(symbols[1].is_public());
+        assert!(!symbols[4].is_public());
+        assert!(symbols[4].calls.is_empty());
+    }
+}
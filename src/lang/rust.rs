@@ -0,0 +1,380 @@
+use std::collections::BTreeSet;
+
+use tree_sitter::Node;
+
+use super::LanguageBackend;
+use crate::treesitter::{Symbol, SymbolKind};
+
+/// Rust sources, read with tree-sitter-rust.
+#[derive(Debug, Clone, Copy)]
+pub struct Rust;
+
+pub static RUST: Rust = Rust;
+
+impl LanguageBackend for Rust {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rs"]
+    }
+
+    fn grammar(&self) -> tree_sitter::Language {
+        tree_sitter_rust::LANGUAGE.into()
+    }
+
+    fn symbols(&self, root: Node, source: &[u8]) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        collect_symbols(root, source, &Scope::root(), &mut symbols);
+        symbols
+    }
+}
+
+/// The modules an item is declared in, as recorded on its symbol.
+#[derive(Debug, Clone)]
+struct Scope {
+    module_path: Vec<String>,
+    module_public: bool,
+}
+
+impl Scope {
+    fn root() -> Scope {
+        Scope {
+            module_path: Vec::new(),
+            module_public: true,
+        }
+    }
+
+    fn module(&self, name: String, public: bool) -> Scope {
+        let mut module_path = self.module_path.clone();
+        module_path.push(name);
+        Scope {
+            module_path,
+            module_public: self.module_public && public,
+        }
+    }
+
+    /// Items in a function body: same path, never reachable from outside.
+    fn local(&self) -> Scope {
+        Scope {
+            module_public: false,
+            ..self.clone()
+        }
+    }
+}
+
+fn collect_symbols(node: Node, source: &[u8], scope: &Scope, symbols: &mut Vec<Symbol>) {
+    match node.kind() {
+        "function_item" => collect_function(node, source, scope, symbols, |_| {}),
+        "impl_item" => {
+            let text = |field| {
+                node.child_by_field_name(field)
+                    .and_then(|n| n.utf8_text(source).ok())
+                    .map(str::to_string)
+            };
+            let impl_type = text("type");
+            let impl_trait = text("trait");
+            for item in body_functions(node) {
+                collect_function(item, source, scope, symbols, |sym| {
+                    sym.kind = SymbolKind::Method;
+                    sym.impl_type.clone_from(&impl_type);
+                    sym.impl_trait.clone_from(&impl_trait);
+                });
+            }
+        }
+        "trait_item" => {
+            let Some(sym) = extract_item(node, source, SymbolKind::Trait, scope) else {
+                return;
+            };
+            let (name, visibility) = (sym.name.clone(), sym.visibility.clone());
+            symbols.push(sym);
+            // Default methods; signatures without a body have nothing to check
+            for item in body_functions(node) {
+                collect_function(item, source, scope, symbols, |sym| {
+                    sym.kind = SymbolKind::Method;
+                    sym.impl_trait = Some(name.clone());
+                    sym.visibility.clone_from(&visibility);
+                });
+            }
+        }
+        "mod_item" => {
+            let (Some(name), Some(body)) = (
+                node.child_by_field_name("name").and_then(|n| n.utf8_text(source).ok()),
+                node.child_by_field_name("body"),
+            ) else {
+                return;
+            };
+            let public = visibility(node, source).as_deref() == Some("pub");
+            collect_symbols(body, source, &scope.module(name.to_string(), public), symbols);
+        }
+        kind => {
+            if let Some(item_kind) = item_symbol_kind(kind) {
+                if let Some(sym) = extract_item(node, source, item_kind, scope) {
+                    symbols.push(sym);
+                }
+                return;
+            }
+            for i in 0..node.child_count() {
+                collect_symbols(node.child(i).unwrap(), source, scope, symbols);
+            }
+        }
+    }
+}
+
+/// The `fn` items of an impl or trait body.
+fn body_functions(node: Node) -> Vec<Node> {
+    let Some(body) = node.child_by_field_name("body") else {
+        return Vec::new();
+    };
+    (0..body.child_count())
+        .filter_map(|i| body.child(i))
+        .filter(|item| item.kind() == "function_item")
+        .collect()
+}
+
+/// Push a function, adjusted by `edit`, then the items declared in its body.
+fn collect_function(
+    node: Node,
+    source: &[u8],
+    scope: &Scope,
+    symbols: &mut Vec<Symbol>,
+    edit: impl FnOnce(&mut Symbol),
+) {
+    if let Some(mut sym) = extract_function(node, source, scope) {
+        edit(&mut sym);
+        symbols.push(sym);
+    }
+    if let Some(body) = node.child_by_field_name("body") {
+        collect_symbols(body, source, &scope.local(), symbols);
+    }
+}
+
+fn item_symbol_kind(node_kind: &str) -> Option<SymbolKind> {
+    match node_kind {
+        "struct_item" => Some(SymbolKind::Struct),
+        "enum_item" => Some(SymbolKind::Enum),
+        "union_item" => Some(SymbolKind::Union),
+        "type_item" => Some(SymbolKind::TypeAlias),
+        "trait_item" => Some(SymbolKind::Trait),
+        "const_item" => Some(SymbolKind::Const),
+        "static_item" => Some(SymbolKind::Static),
+        _ => None,
+    }
+}
+
+/// A named non-function item: its name, extent, generics and visibility are recorded.
+fn extract_item(node: Node, source: &[u8], kind: SymbolKind, scope: &Scope) -> Option<Symbol> {
+    let name_node = node.child_by_field_name("name")?;
+    Some(Symbol {
+        name: name_node.utf8_text(source).ok()?.to_string(),
+        kind,
+        params: Vec::new(),
+        return_type: None,
+        start_line: name_node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        calls: Vec::new(),
+        impl_type: None,
+        impl_trait: None,
+        doc: doc_lines(node, source),
+        visibility: visibility(node, source),
+        is_async: false,
+        is_unsafe: false,
+        is_const: false,
+        generics: generics(node, source),
+        where_clause: where_clause(node, source),
+        module_path: scope.module_path.clone(),
+        module_public: scope.module_public,
+    })
+}
+
+fn child_text(node: Node, source: &[u8], kind: &str) -> Option<String> {
+    (0..node.child_count())
+        .filter_map(|i| node.child(i))
+        .find(|c| c.kind() == kind)
+        .and_then(|c| c.utf8_text(source).ok())
+        .map(str::to_string)
+}
+
+fn visibility(node: Node, source: &[u8]) -> Option<String> {
+    child_text(node, source, "visibility_modifier")
+}
+
+fn generics(node: Node, source: &[u8]) -> Option<String> {
+    node.child_by_field_name("type_parameters")
+        .and_then(|n| n.utf8_text(source).ok())
+        .map(str::to_string)
+}
+
+fn where_clause(node: Node, source: &[u8]) -> Option<String> {
+    child_text(node, source, "where_clause")
+}
+
+/// The `async`, `unsafe` and `const` qualifiers of a function.
+fn qualifiers(node: Node) -> (bool, bool, bool) {
+    let Some(modifiers) = (0..node.child_count())
+        .filter_map(|i| node.child(i))
+        .find(|c| c.kind() == "function_modifiers")
+    else {
+        return (false, false, false);
+    };
+    let has = |kind| {
+        (0..modifiers.child_count()).any(|i| modifiers.child(i).is_some_and(|c| c.kind() == kind))
+    };
+    (has("async"), has("unsafe"), has("const"))
+}
+
+/// Outer doc comments directly above an item, skipping attributes between them.
+fn doc_lines(node: Node, source: &[u8]) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut prev = node.prev_sibling();
+    while let Some(sibling) = prev {
+        match sibling.kind() {
+            "attribute_item" => {}
+            "line_comment" => {
+                let text = sibling.utf8_text(source).unwrap_or("");
+                // `////` and longer are ordinary comments, not docs
+                match text.strip_prefix("///") {
+                    Some(doc) if !doc.starts_with('/') => {
+                        let doc = doc.strip_prefix(' ').unwrap_or(doc).trim_end();
+                        lines.push((sibling.start_position().row + 1, doc.to_string()));
+                    }
+                    _ => break,
+                }
+            }
+            _ => break,
+        }
+        prev = sibling.prev_sibling();
+    }
+    lines.reverse();
+    lines
+}
+
+fn extract_function(node: Node, source: &[u8], scope: &Scope) -> Option<Symbol> {
+    let name_node = node.child_by_field_name("name")?;
+    let name = name_node.utf8_text(source).ok()?.to_string();
+
+    let mut params = Vec::new();
+    if let Some(param_list) = node.child_by_field_name("parameters") {
+        for i in 0..param_list.child_count() {
+            let param = param_list.child(i).unwrap();
+            if param.kind() == "parameter" {
+                let param_name = param
+                    .child_by_field_name("pattern")
+                    .map(|n| n.utf8_text(source).unwrap_or("_").to_string())
+                    .unwrap_or_else(|| "_".to_string());
+                let param_type = param
+                    .child_by_field_name("type")
+                    .map(|n| n.utf8_text(source).unwrap_or("?").to_string())
+                    .unwrap_or_else(|| "?".to_string());
+                params.push((param_name, param_type));
+            }
+        }
+    }
+
+    let return_type = node
+        .child_by_field_name("return_type")
+        .map(|n| n.utf8_text(source).unwrap_or("").to_string());
+
+    // Extract function calls from the body
+    let mut calls = BTreeSet::new();
+    let mut locals: BTreeSet<String> = params
+        .iter()
+        .map(|(p, _)| p.trim_start_matches("mut ").to_string())
+        .collect();
+    if let Some(body) = node.child_by_field_name("body") {
+        extract_calls(body, source, &mut calls);
+        extract_let_names(body, source, &mut locals);
+    }
+    // Remove self-references and calls of closures held in params or locals
+    calls.remove(&name);
+    calls.retain(|c| !locals.contains(c));
+    let (is_async, is_unsafe, is_const) = qualifiers(node);
+
+    Some(Symbol {
+        name,
+        kind: SymbolKind::Function,
+        params,
+        return_type,
+        start_line: name_node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        calls: calls.into_iter().collect(),
+        impl_type: None,
+        impl_trait: None,
+        doc: doc_lines(node, source),
+        visibility: visibility(node, source),
+        is_async,
+        is_unsafe,
+        is_const,
+        generics: generics(node, source),
+        where_clause: where_clause(node, source),
+        module_path: scope.module_path.clone(),
+        module_public: scope.module_public,
+    })
+}
+
+fn extract_calls(node: Node, source: &[u8], calls: &mut BTreeSet<String>) {
+    if node.kind() == "call_expression"
+        && let Some(func) = node.child_by_field_name("function")
+    {
+        match func.kind() {
+            "identifier" => {
+                if let Ok(name) = func.utf8_text(source) {
+                    // Skip common non-function identifiers
+                    if !is_builtin(name) {
+                        calls.insert(name.to_string());
+                    }
+                }
+            }
+            "scoped_identifier" => {
+                if let Ok(path) = func.utf8_text(source) {
+                    // Skip Self::, self::, and stdlib type constructors. Only types are
+                    // checked here: a module may share a macro's name (`include::load`).
+                    let root_segment = path.split("::").next().unwrap_or("");
+                    if !path.starts_with("Self::")
+                        && !path.starts_with("self::")
+                        && !is_builtin_type(root_segment)
+                    {
+                        calls.insert(path.to_string());
+                    }
+                }
+            }
+            // Skip field_expression (method calls on objects like obj.method())
+            _ => {}
+        }
+    }
+
+    for i in 0..node.child_count() {
+        extract_calls(node.child(i).unwrap(), source, calls);
+    }
+}
+
+/// Names bound by `let name = ...` anywhere in a function body.
+fn extract_let_names(node: Node, source: &[u8], names: &mut BTreeSet<String>) {
+    if node.kind() == "let_declaration"
+        && let Some(pattern) = node.child_by_field_name("pattern")
+        && pattern.kind() == "identifier"
+        && let Ok(name) = pattern.utf8_text(source)
+    {
+        names.insert(name.to_string());
+    }
+    for i in 0..node.child_count() {
+        extract_let_names(node.child(i).unwrap(), source, names);
+    }
+}
+
+fn is_builtin_type(name: &str) -> bool {
+    matches!(name, "Some" | "None" | "Ok" | "Err" | "Box" | "Vec" | "String")
+}
+
+fn is_builtin(name: &str) -> bool {
+    is_builtin_type(name)
+        || matches!(
+            name,
+            "format" | "println" | "eprintln" | "print" | "eprint" | "write" | "writeln"
+                | "panic" | "todo" | "unimplemented" | "unreachable" | "assert" | "assert_eq"
+                | "assert_ne" | "debug_assert" | "debug_assert_eq" | "debug_assert_ne" | "cfg"
+                | "include" | "include_str" | "include_bytes" | "env" | "option_env" | "concat"
+                | "line" | "column" | "file" | "stringify" | "module_path"
+        )
+}
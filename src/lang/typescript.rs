@@ -0,0 +1,359 @@
+use std::collections::BTreeSet;
+
+use tree_sitter::Node;
+
+use super::LanguageBackend;
+use crate::treesitter::{Symbol, SymbolKind};
+
+/// TypeScript sources, read with tree-sitter-typescript. `.tsx` files need the
+/// TSX grammar, so they get a backend of their own.
+#[derive(Debug, Clone, Copy)]
+pub struct TypeScript {
+    tsx: bool,
+}
+
+pub static TYPESCRIPT: TypeScript = TypeScript { tsx: false };
+pub static TSX: TypeScript = TypeScript { tsx: true };
+
+impl LanguageBackend for TypeScript {
+    fn name(&self) -> &'static str {
+        if self.tsx { "tsx" } else { "typescript" }
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        if self.tsx { &["tsx"] } else { &["ts", "mts", "cts"] }
+    }
+
+    fn grammar(&self) -> tree_sitter::Language {
+        if self.tsx {
+            tree_sitter_typescript::LANGUAGE_TSX.into()
+        } else {
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()
+        }
+    }
+
+    fn symbols(&self, root: Node, source: &[u8]) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        collect_symbols(root, source, false, &mut symbols);
+        symbols
+    }
+}
+
+fn children(node: Node) -> impl Iterator<Item = Node> {
+    (0..node.child_count()).filter_map(move |i| node.child(i))
+}
+
+fn text(node: Node, source: &[u8]) -> Option<String> {
+    node.utf8_text(source).ok().map(str::to_string)
+}
+
+/// Top-level declarations; `exported` is set inside an `export` statement.
+fn collect_symbols(node: Node, source: &[u8], exported: bool, symbols: &mut Vec<Symbol>) {
+    for child in children(node) {
+        match child.kind() {
+            "export_statement" => collect_symbols(child, source, true, symbols),
+            "function_declaration" | "generator_function_declaration" => {
+                if let Some(sym) = extract_function(child, child, source, exported) {
+                    symbols.push(sym);
+                }
+            }
+            "class_declaration" | "abstract_class_declaration" => {
+                let Some(class) = named_item(child, source, SymbolKind::Class, exported) else {
+                    continue;
+                };
+                let methods = child
+                    .child_by_field_name("body")
+                    .map(|body| extract_methods(body, source, &class))
+                    .unwrap_or_default();
+                symbols.push(class);
+                symbols.extend(methods);
+            }
+            "interface_declaration" => {
+                symbols.extend(named_item(child, source, SymbolKind::Interface, exported));
+            }
+            "type_alias_declaration" => {
+                symbols.extend(named_item(child, source, SymbolKind::TypeAlias, exported));
+            }
+            "enum_declaration" => {
+                symbols.extend(named_item(child, source, SymbolKind::Enum, exported));
+            }
+            "lexical_declaration" => {
+                let is_const = child.child(0).is_some_and(|k| k.kind() == "const");
+                for declarator in children(child).filter(|d| d.kind() == "variable_declarator") {
+                    symbols.extend(extract_variable(declarator, source, is_const, exported));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn visibility(exported: bool) -> Option<String> {
+    exported.then(|| "pub".to_string())
+}
+
+fn named_item(node: Node, source: &[u8], kind: SymbolKind, exported: bool) -> Option<Symbol> {
+    let name_node = node.child_by_field_name("name")?;
+    Some(Symbol {
+        name: text(name_node, source)?,
+        kind,
+        params: Vec::new(),
+        return_type: None,
+        start_line: name_node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        calls: Vec::new(),
+        impl_type: None,
+        impl_trait: None,
+        doc: Vec::new(),
+        visibility: visibility(exported),
+        is_async: false,
+        is_unsafe: false,
+        is_const: false,
+        generics: node.child_by_field_name("type_parameters").and_then(|n| text(n, source)),
+        where_clause: None,
+        module_path: Vec::new(),
+        module_public: true,
+    })
+}
+
+/// `const handler = (req: Request) => ...` is a function; any other `const`
+/// is a constant. `let` bindings are not recorded.
+fn extract_variable(
+    declarator: Node,
+    source: &[u8],
+    is_const: bool,
+    exported: bool,
+) -> Option<Symbol> {
+    let value = declarator.child_by_field_name("value");
+    match value.map(|v| v.kind()) {
+        Some("arrow_function" | "function_expression" | "function") => {
+            extract_function(declarator, value?, source, exported)
+        }
+        _ if is_const => named_item(declarator, source, SymbolKind::Const, exported),
+        _ => None,
+    }
+}
+
+/// Methods of a class body. `private` and `protected` members keep their modifier;
+/// the rest are as visible as the class.
+fn extract_methods(body: Node, source: &[u8], class: &Symbol) -> Vec<Symbol> {
+    children(body)
+        .filter(|m| m.kind() == "method_definition")
+        .filter_map(|m| {
+            let mut sym = extract_function(m, m, source, false)?;
+            sym.kind = SymbolKind::Method;
+            sym.impl_type = Some(class.name.clone());
+            let modifier = children(m)
+                .find(|c| c.kind() == "accessibility_modifier")
+                .and_then(|c| text(c, source));
+            sym.visibility = match modifier.as_deref() {
+                Some("private" | "protected") => modifier,
+                _ => class.visibility.clone(),
+            };
+            Some(sym)
+        })
+        .collect()
+}
+
+/// A function whose name is on `named` and whose signature and body are on
+/// `func`; they differ for arrow functions bound to a `const`.
+fn extract_function(named: Node, func: Node, source: &[u8], exported: bool) -> Option<Symbol> {
+    let name_node = named.child_by_field_name("name")?;
+    let name = text(name_node, source)?;
+    // Type annotations include their colon: `: string`
+    let annotation =
+        |n: Node| text(n, source).map(|t| t.trim_start_matches(':').trim().to_string());
+
+    let mut params = Vec::new();
+    if let Some(list) = func.child_by_field_name("parameters") {
+        for param in children(list) {
+            if !matches!(param.kind(), "required_parameter" | "optional_parameter") {
+                continue;
+            }
+            let pattern = param.child_by_field_name("pattern").and_then(|n| text(n, source));
+            if pattern.as_deref() == Some("this") {
+                continue;
+            }
+            let ty = param.child_by_field_name("type").and_then(annotation);
+            params.push((
+                pattern.unwrap_or_else(|| "_".to_string()),
+                ty.unwrap_or_else(|| "?".to_string()),
+            ));
+        }
+    }
+    let return_type = func.child_by_field_name("return_type").and_then(annotation);
+
+    let mut calls = BTreeSet::new();
+    let mut locals: BTreeSet<String> = params.iter().map(|(p, _)| p.clone()).collect();
+    if let Some(body) = func.child_by_field_name("body") {
+        extract_calls(body, source, &mut calls);
+        extract_declared_names(body, source, &mut locals);
+    }
+    calls.remove(&name);
+    calls.retain(|c| !locals.contains(c.split("::").next().unwrap_or(c)));
+
+    Some(Symbol {
+        name,
+        kind: SymbolKind::Function,
+        params,
+        return_type,
+        start_line: name_node.start_position().row + 1,
+        end_line: named.end_position().row + 1,
+        calls: calls.into_iter().collect(),
+        impl_type: None,
+        impl_trait: None,
+        doc: Vec::new(),
+        visibility: visibility(exported),
+        is_async: children(func).any(|c| c.kind() == "async"),
+        is_unsafe: false,
+        is_const: false,
+        generics: func.child_by_field_name("type_parameters").and_then(|n| text(n, source)),
+        where_clause: None,
+        module_path: Vec::new(),
+        module_public: true,
+    })
+}
+
+/// `f()` is recorded as `f` and `module.f()` as `module::f`; longer member
+/// chains and calls on `this` are calls on objects and are skipped.
+fn extract_calls(node: Node, source: &[u8], calls: &mut BTreeSet<String>) {
+    if node.kind() == "call_expression"
+        && let Some(func) = node.child_by_field_name("function")
+    {
+        match func.kind() {
+            "identifier" => {
+                if let Some(name) = text(func, source)
+                    && !is_builtin(&name)
+                {
+                    calls.insert(name);
+                }
+            }
+            "member_expression" => {
+                if let Some(object) = func.child_by_field_name("object")
+                    && object.kind() == "identifier"
+                    && let Some(object) = text(object, source)
+                    && !is_builtin(&object)
+                    && let Some(property) = func.child_by_field_name("property")
+                    && let Some(property) = text(property, source)
+                {
+                    calls.insert(format!("{object}::{property}"));
+                }
+            }
+            _ => {}
+        }
+    }
+    for child in children(node) {
+        extract_calls(child, source, calls);
+    }
+}
+
+/// Names declared by `const`/`let`/`var` anywhere in a body.
+fn extract_declared_names(node: Node, source: &[u8], names: &mut BTreeSet<String>) {
+    if node.kind() == "variable_declarator"
+        && let Some(name) = node.child_by_field_name("name")
+        && name.kind() == "identifier"
+        && let Some(name) = text(name, source)
+    {
+        names.insert(name);
+    }
+    for child in children(node) {
+        extract_declared_names(child, source, names);
+    }
+}
+
+fn is_builtin(name: &str) -> bool {
+    matches!(
+        name,
+        "console" | "Math" | "JSON" | "Object" | "Array" | "Promise" | "Number" | "String"
+            | "Boolean" | "Symbol" | "Date" | "Reflect" | "parseInt" | "parseFloat" | "isNaN"
+            | "isFinite" | "require" | "setTimeout" | "setInterval" | "clearTimeout"
+            | "clearInterval"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_typescript_symbols_and_calls() {
+        let source = r#"
+import * as db from "./db";
+
+export const MAX_USERS = 10;
+
+export interface Verifier {
+  verify(token: string): boolean;
+}
+
+export class Auth {
+  private secret: string;
+
+  constructor(secret: string) {
+    this.secret = secret;
+  }
+
+  verify(token: string, strict?: boolean): boolean {
+    const parsed = parse(token);
+    parsed.check();
+    console.log(token);
+    return db.lookup(parsed) !== undefined;
+  }
+
+  private rotate(): void {
+    this.verify("");
+  }
+}
+
+const helper = (x: number): number => Math.abs(x);
+
+function main() {
+  helper(1);
+}
+"#;
+        let symbols = TYPESCRIPT.extract_symbols(source).unwrap();
+        let kinds: Vec<(&str, SymbolKind)> =
+            symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+        assert_eq!(
+            kinds,
+            [
+                ("MAX_USERS", SymbolKind::Const),
+                ("Verifier", SymbolKind::Interface),
+                ("Auth", SymbolKind::Class),
+                ("constructor", SymbolKind::Method),
+                ("verify", SymbolKind::Method),
+                ("rotate", SymbolKind::Method),
+                ("helper", SymbolKind::Function),
+                ("main", SymbolKind::Function),
+            ]
+        );
+        let verify = &symbols[4];
+        assert_eq!(verify.qualified_name(), "Auth::verify");
+        assert_eq!(
+            verify.params,
+            [
+                ("token".to_string(), "string".to_string()),
+                ("strict".to_string(), "boolean".to_string())
+            ]
+        );
+        assert_eq!(verify.return_type.as_deref(), Some("boolean"));
+        assert_eq!(verify.calls, ["db::lookup", "parse"]);
+        assert!(verify.is_public());
+        assert_eq!(symbols[5].visibility.as_deref(), Some("private"));
+        assert_eq!(symbols[6].params, [("x".to_string(), "number".to_string())]);
+        assert!(!symbols[6].is_public());
+        assert_eq!(symbols[7].calls, ["helper"]);
+    }
+
+    #[test]
+    fn test_tsx_parses_jsx() {
+        let source = r#"
+export function App(props: Props) {
+  return <div>{render(props)}</div>;
+}
+"#;
+        let symbols = TSX.extract_symbols(source).unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].calls, ["render"]);
+    }
+}
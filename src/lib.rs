@@ -1,11 +1,28 @@
 pub mod ast;
+pub mod cache;
+pub mod changes;
 pub mod cli;
 pub mod config;
 pub mod context;
+pub mod contract;
+pub mod cst;
+pub mod deps;
+pub mod diagnostic;
+pub mod export;
+pub mod fix;
+pub mod formatter;
 pub mod health;
+pub mod include;
+pub mod inline;
 pub mod integration;
+pub mod lang;
 pub mod orchestrate;
 pub mod parser;
+pub mod policy;
+pub mod report;
+pub mod staleness;
 pub mod stub;
 pub mod treesitter;
 pub mod validator;
+pub mod walk;
+pub mod writer;
@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 use crate::ast::{
     self, Annotation, BogFile, DerivedAgents, SkimTargets, SkimsystemDecl, SubsystemDecl,
 };
+use crate::cache::Cache;
 use crate::config::{AgentRole, BogConfig};
 
 use super::error::OrchestrateError;
@@ -33,11 +34,18 @@ impl RepoContext {
         let config = crate::config::load_config(&config_path)
             .map_err(|e| OrchestrateError::ContextLoad(format!("bog.toml: {e}")))?;
 
-        let repo_bog_path = root.join("repo.bog");
-        let repo_bog_raw = std::fs::read_to_string(&repo_bog_path)
-            .map_err(|e| OrchestrateError::ContextLoad(format!("repo.bog: {e}")))?;
-        let repo_bog = crate::parser::parse_bog(&repo_bog_raw)
-            .map_err(|e| OrchestrateError::ContextLoad(format!("repo.bog parse: {e}")))?;
+        let loaded = crate::include::load_repo_bog(root)
+            .map_err(|e| OrchestrateError::ContextLoad(e.to_string()))?;
+        // Included files follow repo.bog, each under a comment naming it
+        let mut repo_bog_raw = String::new();
+        for (i, (path, content)) in loaded.sources.iter().enumerate() {
+            if i > 0 {
+                let rel = path.strip_prefix(root).unwrap_or(path);
+                repo_bog_raw.push_str(&format!("\n// included: {}\n", rel.display()));
+            }
+            repo_bog_raw.push_str(content);
+        }
+        let repo_bog = loaded.bog;
 
         let mut subsystems = HashMap::new();
         let mut skimsystems = HashMap::new();
@@ -216,15 +224,15 @@ fn load_all_sidecars(
     subsystems: &HashMap<String, SubsystemDecl>,
 ) -> HashMap<String, BogFile> {
     let mut sidecars = HashMap::new();
+    let cache = Cache::open(root);
     for sub in subsystems.values() {
         for file_path in &sub.files {
             let bog_path = root.join(format!("{file_path}.bog"));
             let Ok(content) = std::fs::read_to_string(&bog_path) else {
                 continue;
             };
-            let Ok(bog) = crate::parser::parse_bog(&content) else {
-                continue;
-            };
+            let (mut bog, _) = cache.parse_bog_recovering(&content);
+            crate::inline::merge_from_source(&mut bog, &root.join(file_path));
             sidecars.insert(file_path.clone(), bog);
         }
     }
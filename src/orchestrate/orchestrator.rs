@@ -95,10 +95,10 @@ pub fn orchestrate(
                         result.agent,
                         result.files_modified.len()
                     );
-                    if config.merge_strategy == MergeStrategy::Incremental {
-                        if let Some(wt) = worktree_mgr.find_worktree(&result.agent, &run_id) {
-                            worktree_mgr.merge_changes(wt)?;
-                        }
+                    if config.merge_strategy == MergeStrategy::Incremental
+                        && let Some(wt) = worktree_mgr.find_worktree(&result.agent, &run_id)
+                    {
+                        worktree_mgr.merge_changes(wt)?;
                     }
                 }
                 AgentResultStatus::Failed(msg) => {
@@ -128,10 +128,10 @@ pub fn orchestrate(
             // All agents succeeded
             if config.merge_strategy == MergeStrategy::AllOrNothing {
                 for result in &agent_results {
-                    if matches!(result.status, AgentResultStatus::Success) {
-                        if let Some(wt) = worktree_mgr.find_worktree(&result.agent, &run_id) {
-                            worktree_mgr.merge_changes(wt)?;
-                        }
+                    if matches!(result.status, AgentResultStatus::Success)
+                        && let Some(wt) = worktree_mgr.find_worktree(&result.agent, &run_id)
+                    {
+                        worktree_mgr.merge_changes(wt)?;
                     }
                 }
             }
@@ -529,21 +529,20 @@ fn render_skim_observations(ctx: &RepoContext, agent_name: &str) -> Option<Strin
 
     for (path, bog) in &all_sidecars {
         for ann in &bog.annotations {
-            if let Annotation::Skim(obs) = ann {
-                if skimsystem_names.contains(&obs.skimsystem)
-                    && obs.status != Status::Green
-                {
-                    has_any = true;
-                    let notes_str = obs
-                        .notes
-                        .as_deref()
-                        .map(|n| format!(": {n}"))
-                        .unwrap_or_default();
-                    out.push_str(&format!(
-                        "\n### {path} — {}{notes_str}",
-                        obs.status
-                    ));
-                }
+            if let Annotation::Skim(obs) = ann
+                && skimsystem_names.contains(&obs.skimsystem)
+                && obs.status != Status::Green
+            {
+                has_any = true;
+                let notes_str = obs
+                    .notes
+                    .as_deref()
+                    .map(|n| format!(": {n}"))
+                    .unwrap_or_default();
+                out.push_str(&format!(
+                    "\n### {path} — {}{notes_str}",
+                    obs.status
+                ));
             }
         }
     }
@@ -657,15 +656,15 @@ fn extract_health_line(bog: &BogFile) -> Option<String> {
 fn extract_notable_functions(bog: &BogFile) -> Vec<(String, Status, Option<String>, bool)> {
     let mut fns = Vec::new();
     for ann in &bog.annotations {
-        if let Annotation::Fn(f) = ann {
-            if f.status != Status::Green || f.stub {
-                fns.push((
-                    f.name.clone(),
-                    f.status,
-                    f.description.clone(),
-                    f.stub,
-                ));
-            }
+        if let Annotation::Fn(f) = ann
+            && (f.status != Status::Green || f.stub)
+        {
+            fns.push((
+                f.name.clone(),
+                f.status,
+                f.description.clone(),
+                f.stub,
+            ));
         }
     }
     fns
@@ -708,10 +707,12 @@ fn worse(a: Status, b: Status) -> Status {
 
 fn format_value(v: &Value) -> String {
     match v {
-        Value::String(s) => s.clone(),
+        Value::String(s) => crate::parser::unquote(s),
         Value::Ident(s) => s.clone(),
         Value::Bool(b) => b.to_string(),
         Value::Number(n) => n.to_string(),
+        Value::Float(x) => x.to_string(),
+        Value::Date(d) => d.to_string(),
         Value::Status(s) => s.to_string(),
         Value::FnRef(name) => format!("fn({name})"),
         Value::Path(parts) => parts.join("::"),
@@ -110,7 +110,7 @@ impl Provider for ClaudeCliProvider {
             let mut collected = String::new();
             if let Some(pipe) = stderr_pipe {
                 let reader = BufReader::new(pipe);
-                for line in reader.lines().flatten() {
+                for line in reader.lines().map_while(Result::ok) {
                     collected.push_str(&line);
                     collected.push('\n');
                 }
@@ -216,7 +216,7 @@ fn parse_event_stream(pipe: Option<ChildStdout>, label: &str) -> StreamProgress
     };
 
     let reader = BufReader::new(pipe);
-    for line in reader.lines().flatten() {
+    for line in reader.lines().map_while(Result::ok) {
         if line.trim().is_empty() {
             continue;
         }
@@ -536,42 +536,40 @@ fn parse_codex_event_stream(pipe: Option<ChildStdout>, label: &str) -> StreamPro
                     progress.total_output_tokens = Some(total);
                 }
             }
-            Some("item.started") => {
-                // Emit progress for commands starting
-                if !label.is_empty() {
-                    let item = &event["item"];
-                    let item_type = item["type"].as_str().unwrap_or("");
-                    match item_type {
-                        "command_execution" => {
-                            let cmd = item["command"].as_str().unwrap_or("");
-                            let short = summarize_shell_cmd(cmd);
-                            let turn = progress.turn_count;
-                            eprintln!("  [{label}] turn {turn} ▸ Bash {short}");
-                        }
-                        "file_edit" => {
-                            let path = item["file_path"].as_str().unwrap_or("?");
-                            let short = strip_worktree_prefix(path);
-                            let turn = progress.turn_count;
-                            eprintln!("  [{label}] turn {turn} ▸ Edit {short}");
-                        }
-                        "file_read" => {
-                            let path = item["file_path"].as_str().unwrap_or("?");
-                            let short = strip_worktree_prefix(path);
-                            let turn = progress.turn_count;
-                            eprintln!("  [{label}] turn {turn} ▸ Read {short}");
-                        }
-                        _ => {}
+            // Emit progress for commands starting
+            Some("item.started") if !label.is_empty() => {
+                let item = &event["item"];
+                let item_type = item["type"].as_str().unwrap_or("");
+                match item_type {
+                    "command_execution" => {
+                        let cmd = item["command"].as_str().unwrap_or("");
+                        let short = summarize_shell_cmd(cmd);
+                        let turn = progress.turn_count;
+                        eprintln!("  [{label}] turn {turn} ▸ Bash {short}");
+                    }
+                    "file_edit" => {
+                        let path = item["file_path"].as_str().unwrap_or("?");
+                        let short = strip_worktree_prefix(path);
+                        let turn = progress.turn_count;
+                        eprintln!("  [{label}] turn {turn} ▸ Edit {short}");
+                    }
+                    "file_read" => {
+                        let path = item["file_path"].as_str().unwrap_or("?");
+                        let short = strip_worktree_prefix(path);
+                        let turn = progress.turn_count;
+                        eprintln!("  [{label}] turn {turn} ▸ Read {short}");
                     }
+                    _ => {}
                 }
             }
             Some("item.completed") => {
                 let item = &event["item"];
                 let item_type = item["type"].as_str().unwrap_or("");
 
-                if item_type == "agent_message" {
-                    if let Some(text) = item["text"].as_str() {
-                        progress.last_assistant_text = Some(text.to_string());
-                    }
+                if item_type == "agent_message"
+                    && let Some(text) = item["text"].as_str()
+                {
+                    progress.last_assistant_text = Some(text.to_string());
                 }
             }
             _ => {} // thread.started, etc. — skip
@@ -692,8 +690,10 @@ mod tests {
     #[test]
     fn test_registry_routes_codex_model() {
         // When model is a Codex model, should route to codex
-        let mut options = ProviderOptions::default();
-        options.model = Some("o4-mini".to_string());
+        let options = ProviderOptions {
+            model: Some("o4-mini".to_string()),
+            ..Default::default()
+        };
         assert!(is_codex_model(options.model.as_deref().unwrap()));
     }
 
@@ -2,7 +2,9 @@ use std::collections::HashMap;
 use std::process::Command;
 
 use crate::ast::{Annotation, ChangeRequest};
+use crate::lang;
 use crate::parser;
+use crate::walk::Walker;
 
 use super::agent;
 use super::context::RepoContext;
@@ -192,16 +194,7 @@ fn collect_pending_requests(
     let mut by_subsystem: HashMap<String, Vec<(String, String, Vec<ChangeRequest>)>> =
         HashMap::new();
 
-    // Glob all .bog files under the repo root
-    let pattern = ctx.root.join("**/*.rs.bog");
-    let pattern_str = pattern.to_string_lossy();
-
-    let paths: Vec<_> = glob::glob(&pattern_str)
-        .map_err(|e| OrchestrateError::ContextLoad(format!("glob: {e}")))?
-        .filter_map(|p| p.ok())
-        .collect();
-
-    for bog_path in paths {
+    for bog_path in lang::sidecar_files(&Walker::new(&ctx.root)) {
         let content = match std::fs::read_to_string(&bog_path) {
             Ok(c) => c,
             Err(_) => continue,
@@ -250,9 +243,11 @@ fn collect_pending_requests(
             continue;
         }
 
-        // Derive the source file path from the .bog path (strip .bog suffix)
         let bog_str = bog_path.to_string_lossy().to_string();
-        let source_path = bog_str.strip_suffix(".bog").unwrap_or(&bog_str).to_string();
+        let Some((source_path, _)) = lang::source_for(&bog_path) else {
+            continue;
+        };
+        let source_path = source_path.to_string_lossy().to_string();
 
         // Make paths relative to repo root
         let rel_bog = pathdiff(&bog_str, &ctx.root.to_string_lossy());
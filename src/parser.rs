@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 
+use pest::error::InputLocation;
 use pest::iterators::{Pair, Pairs};
-use pest::Parser;
+use pest::{Parser, Position};
 use pest_derive::Parser;
 
 use crate::ast::*;
+use crate::diagnostic::Span;
 
 #[derive(Parser)]
 #[grammar = "parser.pest"]
@@ -15,14 +17,68 @@ pub enum ParseError {
     #[error("Parse error: {0}")]
     Pest(#[from] pest::error::Error<Rule>),
 
-    #[error("Unknown annotation type: {0}")]
-    UnknownAnnotation(String),
-
     #[error("Missing required field '{field}' in {context}")]
-    MissingField { context: String, field: String },
+    MissingField {
+        context: String,
+        field: String,
+        span: Span,
+    },
 
     #[error("Invalid value for field '{field}': {message}")]
-    InvalidValue { field: String, message: String },
+    InvalidValue {
+        field: String,
+        message: String,
+        span: Span,
+    },
+}
+
+impl ParseError {
+    /// Byte span of the offending source text.
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::Pest(e) => match e.location {
+                InputLocation::Pos(pos) => Span::new(pos, pos),
+                InputLocation::Span((start, end)) => Span::new(start, end),
+            },
+            ParseError::MissingField { span, .. }
+            | ParseError::InvalidValue { span, .. } => *span,
+        }
+    }
+
+    /// One-line message, without pest's own multi-line rendering.
+    pub fn message(&self) -> String {
+        match self {
+            ParseError::Pest(e) => e.variant.message().to_string(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// Key/value pairs of a parens or body block, plus the span of every value.
+struct KvMap {
+    values: HashMap<String, Value>,
+    /// Value spans keyed by path: `key`, `key.nested`, `key[0]`
+    spans: HashMap<String, Span>,
+    /// Span of the enclosing annotation, reported for missing keys
+    span: Span,
+}
+
+impl KvMap {
+    fn empty(span: Span) -> Self {
+        Self {
+            values: HashMap::new(),
+            spans: HashMap::new(),
+            span,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    fn value_span(&self, path: &str) -> Span {
+        self.spans.get(path).copied().unwrap_or(self.span)
+    }
 }
 
 pub fn parse_bog(input: &str) -> Result<BogFile, ParseError> {
@@ -39,131 +95,387 @@ pub fn parse_bog(input: &str) -> Result<BogFile, ParseError> {
     Ok(BogFile { annotations })
 }
 
+/// Parse as much of a .bog file as possible. An annotation that fails to parse is
+/// skipped and reported; after a syntax error, parsing resumes at the next top-level
+/// `#[`. Returns every annotation that did parse, plus the errors.
+pub fn parse_bog_recovering(input: &str) -> (BogFile, Vec<ParseError>) {
+    let mut annotations = Vec::new();
+    let mut errors = Vec::new();
+    let mut cursor = skip_trivia(input, 0);
+
+    while cursor < input.len() {
+        let rest = &input[cursor..];
+        match BogParser::parse(Rule::annotation, rest) {
+            Ok(mut pairs) => {
+                let pair = pairs.next().unwrap();
+                let end = pair.as_span().end();
+                match parse_annotation(pair) {
+                    Ok(ann) => annotations.push(ann),
+                    Err(e) => errors.push(offset_error(e, input, cursor)),
+                }
+                cursor += end;
+            }
+            Err(e) => {
+                errors.push(offset_error(ParseError::Pest(e), input, cursor));
+                let Some(next) = next_annotation(rest) else {
+                    break;
+                };
+                cursor += next;
+            }
+        }
+        cursor = skip_trivia(input, cursor);
+    }
+
+    (BogFile { annotations }, errors)
+}
+
+/// Where the next top-level annotation starts in text beginning with a broken one:
+/// the first `#[` outside strings and comments once its brackets are closed. If they
+/// never are, the first `#[` that starts a line, at any indentation.
+fn next_annotation(rest: &str) -> Option<usize> {
+    let bytes = rest.as_bytes();
+    let (mut i, mut depth) = if rest.starts_with("#[") { (2, 1usize) } else { (0, 0) };
+    while i < bytes.len() {
+        let tail = &rest[i..];
+        match bytes[i] {
+            b'#' if depth == 0 && tail.starts_with("#[") => return Some(i),
+            b'[' | b'{' | b'(' => depth += 1,
+            b']' | b'}' | b')' => depth = depth.saturating_sub(1),
+            b'/' if tail.starts_with("//") => {
+                i += tail.find('\n').unwrap_or(tail.len());
+                continue;
+            }
+            b'"' | b'r' => {
+                if let Some(len) = string_len(tail) {
+                    i += len;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let mut offset = rest.find('\n')? + 1;
+    for line in rest[offset..].split_inclusive('\n') {
+        let indent = line.len() - line.trim_start().len();
+        if line[indent..].starts_with("#[") {
+            return Some(offset + indent);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// The length of the string literal `s` starts with, if it starts with a complete one.
+fn string_len(s: &str) -> Option<usize> {
+    if let Some(body) = s.strip_prefix("\"\"\"") {
+        return body.find("\"\"\"").map(|end| end + 6);
+    }
+    if let Some(raw) = s.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw[hashes..].strip_prefix('"')?;
+        let close = format!("\"{}", "#".repeat(hashes));
+        return body.find(&close).map(|end| 1 + hashes + 1 + end + close.len());
+    }
+    let body = s.strip_prefix('"')?;
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return Some(i + 2),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+/// Advance past whitespace and `//` comments.
+fn skip_trivia(input: &str, mut cursor: usize) -> usize {
+    loop {
+        let rest = &input[cursor..];
+        let trimmed = rest.trim_start();
+        cursor += rest.len() - trimmed.len();
+        if !trimmed.starts_with("//") {
+            return cursor;
+        }
+        cursor += trimmed.find('\n').unwrap_or(trimmed.len());
+    }
+}
+
+/// Re-anchor an error from parsing `input[offset..]` onto the full input.
+fn offset_error(err: ParseError, input: &str, offset: usize) -> ParseError {
+    match err {
+        ParseError::Pest(e) => {
+            let shifted = match e.location {
+                InputLocation::Pos(pos) => Position::new(input, offset + pos)
+                    .map(|p| pest::error::Error::new_from_pos(e.variant.clone(), p)),
+                InputLocation::Span((start, end)) => {
+                    pest::Span::new(input, offset + start, offset + end)
+                        .map(|s| pest::error::Error::new_from_span(e.variant.clone(), s))
+                }
+            };
+            ParseError::Pest(shifted.unwrap_or(e))
+        }
+        ParseError::MissingField {
+            context,
+            field,
+            span,
+        } => ParseError::MissingField {
+            context,
+            field,
+            span: span.shifted(offset),
+        },
+        ParseError::InvalidValue {
+            field,
+            message,
+            span,
+        } => ParseError::InvalidValue {
+            field,
+            message,
+            span: span.shifted(offset),
+        },
+    }
+}
+
+/// Annotation kinds the parser understands natively. Any other kind, a typo such
+/// as `#[fnn]` included, parses as `Annotation::Custom`: the parser has no schemas,
+/// so undeclared kinds are reported by `bog validate`, not at parse time.
+pub const BUILTIN_ANNOTATIONS: &[&str] = &[
+    "repo",
+    "file",
+    "description",
+    "health",
+    "fn",
+    "type",
+    "trait",
+    "const",
+    "subsystem",
+    "skimsystem",
+    "skim",
+    "policies",
+    "change_requests",
+    "pickled",
+    "annotation",
+    "include",
+];
+
 fn parse_annotation(pair: Pair<Rule>) -> Result<Annotation, ParseError> {
+    let span = Span::from(pair.as_span());
     let mut inner = pair.into_inner();
-    let ident_pair = inner.next().unwrap();
-    let name = ident_pair.as_str();
+    let name = inner.next().unwrap().as_str();
 
     match name {
-        "repo" => parse_repo(inner),
-        "file" => parse_file(inner),
+        "repo" => parse_repo(inner, span),
+        "file" => parse_file(inner, span),
         "description" => parse_description(inner),
-        "health" => parse_health(inner),
-        "fn" => parse_fn(inner),
-        "subsystem" => parse_subsystem(inner),
-        "skimsystem" => parse_skimsystem(inner),
-        "skim" => parse_skim(inner),
-        "policies" => parse_policies(inner),
+        "health" => parse_health(inner, span),
+        "fn" => parse_fn(inner, span),
+        "type" | "trait" | "const" => parse_item(name, inner, span),
+        "subsystem" => parse_subsystem(inner, span),
+        "skimsystem" => parse_skimsystem(inner, span),
+        "skim" => parse_skim(inner, span),
+        "policies" => parse_policies(inner, span),
         "change_requests" => parse_change_requests(inner),
-        "pickled" => parse_pickled(inner),
-        other => Err(ParseError::UnknownAnnotation(other.to_string())),
+        "pickled" => parse_pickled(inner, span),
+        "annotation" => parse_schema(inner, span),
+        "include" => parse_include(inner, span),
+        other => parse_custom(other, inner, span),
     }
 }
 
 // --- Helper functions ---
 
-fn extract_kv_map(pairs: Pairs<Rule>) -> Result<HashMap<String, Value>, ParseError> {
-    let mut map = HashMap::new();
+fn extract_kv_map(pairs: Pairs<Rule>, span: Span) -> Result<KvMap, ParseError> {
+    let mut map = KvMap::empty(span);
     for pair in pairs {
         if pair.as_rule() == Rule::kv_pair {
             let mut kv_inner = pair.into_inner();
             let key = kv_inner.next().unwrap().as_str().to_string();
-            let val = parse_value(kv_inner.next().unwrap())?;
-            map.insert(key, val);
+            let value = kv_inner.next().unwrap();
+            collect_value_spans(&key, &value, &mut map.spans);
+            let val = parse_value(value)?;
+            map.values.insert(key, val);
         }
     }
     Ok(map)
 }
 
-fn get_kv_list_from_parens(pairs: &mut Pairs<Rule>) -> Result<HashMap<String, Value>, ParseError> {
-    if let Some(parens) = pairs.next() {
-        if parens.as_rule() == Rule::parens {
-            let content = parens.into_inner().next().unwrap();
-            if content.as_rule() == Rule::parens_content {
-                let inner = content.into_inner().next().unwrap();
-                if inner.as_rule() == Rule::kv_list {
-                    return extract_kv_map(inner.into_inner());
-                }
+/// Record the span of a value and, recursively, of its list items and nested keys.
+fn collect_value_spans(path: &str, pair: &Pair<Rule>, spans: &mut HashMap<String, Span>) {
+    spans.insert(path.to_string(), pair.as_span().into());
+    let Some(inner) = pair.clone().into_inner().next() else {
+        return;
+    };
+    match inner.as_rule() {
+        Rule::list => {
+            for (i, item) in inner.into_inner().enumerate() {
+                collect_value_spans(&format!("{path}[{i}]"), &item, spans);
+            }
+        }
+        Rule::nested_block => {
+            let kv_list = inner.into_inner().next().unwrap();
+            for kv in kv_list.into_inner() {
+                let mut kv_inner = kv.into_inner();
+                let key = kv_inner.next().unwrap().as_str();
+                let value = kv_inner.next().unwrap();
+                collect_value_spans(&format!("{path}.{key}"), &value, spans);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn get_kv_list_from_parens(pairs: &mut Pairs<Rule>, span: Span) -> Result<KvMap, ParseError> {
+    if let Some(parens) = pairs.next()
+        && parens.as_rule() == Rule::parens
+    {
+        let content = parens.into_inner().next().unwrap();
+        if content.as_rule() == Rule::parens_content {
+            let inner = content.into_inner().next().unwrap();
+            if inner.as_rule() == Rule::kv_list {
+                return extract_kv_map(inner.into_inner(), span);
             }
         }
     }
-    Ok(HashMap::new())
+    Ok(KvMap::empty(span))
 }
 
 fn get_ident_from_parens(pairs: &mut Pairs<Rule>) -> Option<String> {
-    if let Some(parens) = pairs.next() {
-        if parens.as_rule() == Rule::parens {
-            let content = parens.into_inner().next().unwrap();
-            if content.as_rule() == Rule::parens_content {
-                let inner = content.into_inner().next().unwrap();
-                if inner.as_rule() == Rule::ident {
-                    return Some(inner.as_str().to_string());
-                }
+    if let Some(parens) = pairs.next()
+        && parens.as_rule() == Rule::parens
+    {
+        let content = parens.into_inner().next().unwrap();
+        if content.as_rule() == Rule::parens_content {
+            let inner = content.into_inner().next().unwrap();
+            if inner.as_rule() == Rule::ident {
+                return Some(inner.as_str().to_string());
             }
         }
     }
     None
 }
 
-fn get_body_kv_map(pairs: &mut Pairs<Rule>) -> Result<HashMap<String, Value>, ParseError> {
-    if let Some(body) = pairs.next() {
-        if body.as_rule() == Rule::body {
-            if let Some(content) = body.into_inner().next() {
-                if content.as_rule() == Rule::body_content {
-                    let inner = content.into_inner().next().unwrap();
-                    if inner.as_rule() == Rule::kv_list {
-                        return extract_kv_map(inner.into_inner());
-                    }
-                }
+/// The function name in `#[fn(name)]`, which may be qualified as `Type::name` or
+/// `<Type as Trait>::name`.
+fn get_fn_name_from_parens(pairs: &mut Pairs<Rule>) -> Result<Option<String>, ParseError> {
+    if let Some(parens) = pairs.next()
+        && parens.as_rule() == Rule::parens
+    {
+        let content = parens.into_inner().next().unwrap();
+        if content.as_rule() == Rule::parens_content {
+            let inner = content.into_inner().next().unwrap();
+            if matches!(inner.as_rule(), Rule::ident | Rule::fn_path) {
+                return fn_name(&inner).map(Some);
             }
         }
     }
-    Ok(HashMap::new())
+    Ok(None)
 }
 
+fn get_body_kv_map(pairs: &mut Pairs<Rule>, span: Span) -> Result<KvMap, ParseError> {
+    if let Some(body) = pairs.next()
+        && body.as_rule() == Rule::body
+        && let Some(content) = body.into_inner().next()
+        && content.as_rule() == Rule::body_content
+    {
+        let inner = content.into_inner().next().unwrap();
+        if inner.as_rule() == Rule::kv_list {
+            return extract_kv_map(inner.into_inner(), span);
+        }
+    }
+    Ok(KvMap::empty(span))
+}
 
 fn get_body_text(pairs: &mut Pairs<Rule>) -> String {
-    if let Some(body) = pairs.next() {
-        if body.as_rule() == Rule::body {
-            if let Some(content) = body.into_inner().next() {
-                // Use raw text regardless of how pest parsed it
-                return content.as_str().trim().to_string();
-            }
-        }
+    if let Some(body) = pairs.next()
+        && body.as_rule() == Rule::body
+        && let Some(content) = body.into_inner().next()
+    {
+        // Use raw text regardless of how pest parsed it
+        return dedent(content.as_str().trim());
     }
     String::new()
 }
 
-fn require_string(map: &HashMap<String, Value>, key: &str, ctx: &str) -> Result<String, ParseError> {
+/// Strip the indentation shared by every non-blank line after the first.
+fn dedent(text: &str) -> String {
+    let common = text
+        .lines()
+        .skip(1)
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    text.lines()
+        .enumerate()
+        .map(|(i, l)| {
+            if i == 0 || l.trim().is_empty() {
+                l.trim_end()
+            } else {
+                l[common..].trim_end()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn require_string(map: &KvMap, key: &str, ctx: &str) -> Result<String, ParseError> {
     match map.get(key) {
         Some(Value::String(s)) => Ok(unquote(s)),
         Some(Value::Ident(s)) => Ok(s.clone()),
         Some(_) => Err(ParseError::InvalidValue {
             field: key.to_string(),
             message: format!("expected string in {ctx}"),
+            span: map.value_span(key),
         }),
         None => Err(ParseError::MissingField {
             context: ctx.to_string(),
             field: key.to_string(),
+            span: map.span,
         }),
     }
 }
 
-fn require_status(map: &HashMap<String, Value>, key: &str, ctx: &str) -> Result<Status, ParseError> {
+fn require_status(map: &KvMap, key: &str, ctx: &str) -> Result<Status, ParseError> {
     match map.get(key) {
         Some(Value::Status(s)) => Ok(*s),
         Some(_) => Err(ParseError::InvalidValue {
             field: key.to_string(),
             message: format!("expected status (green/yellow/red) in {ctx}"),
+            span: map.value_span(key),
         }),
         None => Err(ParseError::MissingField {
             context: ctx.to_string(),
             field: key.to_string(),
+            span: map.span,
         }),
     }
 }
 
-fn opt_string(map: &HashMap<String, Value>, key: &str) -> Option<String> {
+/// A `YYYY-MM-DD` date, written bare or quoted, normalized to its canonical form.
+fn require_date(map: &KvMap, key: &str, ctx: &str) -> Result<String, ParseError> {
+    let date = match map.get(key) {
+        Some(Value::Date(d)) => Some(*d),
+        Some(Value::String(s)) => parse_date(&unquote(s)),
+        Some(_) => None,
+        None => {
+            return Err(ParseError::MissingField {
+                context: ctx.to_string(),
+                field: key.to_string(),
+                span: map.span,
+            });
+        }
+    };
+    date.map(|d| d.format("%Y-%m-%d").to_string())
+        .ok_or_else(|| ParseError::InvalidValue {
+            field: key.to_string(),
+            message: format!("expected date (YYYY-MM-DD) in {ctx}"),
+            span: map.value_span(key),
+        })
+}
+
+fn opt_string(map: &KvMap, key: &str) -> Option<String> {
     match map.get(key) {
         Some(Value::String(s)) => Some(unquote(s)),
         Some(Value::Ident(s)) => Some(s.clone()),
@@ -171,7 +483,7 @@ fn opt_string(map: &HashMap<String, Value>, key: &str) -> Option<String> {
     }
 }
 
-fn extract_string_list(map: &HashMap<String, Value>, key: &str) -> Vec<String> {
+fn extract_string_list(map: &KvMap, key: &str) -> Vec<String> {
     match map.get(key) {
         Some(Value::List(items)) => items
             .iter()
@@ -186,10 +498,85 @@ fn extract_string_list(map: &HashMap<String, Value>, key: &str) -> Vec<String> {
     }
 }
 
-fn unquote(s: &str) -> String {
-    let s = s.strip_prefix('"').unwrap_or(s);
+/// The text of a string literal as written in source, with quotes and escapes removed.
+/// Literals that fail to decode (only possible for hand-built values) come back as-is.
+pub fn unquote(s: &str) -> String {
+    decode_string(s).unwrap_or_else(|_| s.to_string())
+}
+
+/// Decode a basic, triple-quoted or raw string literal.
+fn decode_string(raw: &str) -> Result<String, String> {
+    if let Some(inner) = raw
+        .strip_prefix("\"\"\"")
+        .and_then(|s| s.strip_suffix("\"\"\""))
+    {
+        return Ok(dedent_block(inner));
+    }
+    if let Some(rest) = raw.strip_prefix('r') {
+        let hashes = rest.len() - rest.trim_start_matches('#').len();
+        let inner = &rest[hashes..rest.len() - hashes];
+        return Ok(inner
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .unwrap_or(inner)
+            .to_string());
+    }
+
+    let s = raw.strip_prefix('"').unwrap_or(raw);
     let s = s.strip_suffix('"').unwrap_or(s);
-    s.replace("\\\"", "\"").replace("\\\\", "\\")
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('0') => out.push('\0'),
+            Some('u') => {
+                let digits = chars.as_str();
+                let hex = digits
+                    .strip_prefix('{')
+                    .and_then(|d| d.split_once('}'))
+                    .map(|(hex, _)| hex)
+                    .ok_or("unicode escapes are written \\u{XXXX}")?;
+                let code = u32::from_str_radix(hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("invalid unicode escape \\u{{{hex}}}"))?;
+                out.push(code);
+                chars = digits[hex.len() + 2..].chars();
+            }
+            Some(other) => return Err(format!("unknown escape \\{other}")),
+            None => return Err("trailing backslash".to_string()),
+        }
+    }
+    Ok(out)
+}
+
+/// Text of a triple-quoted string: a newline right after the opening quotes and
+/// whitespace before the closing ones are dropped, then the common indent is removed.
+fn dedent_block(text: &str) -> String {
+    let text = text.strip_prefix('\n').unwrap_or(text);
+    let text = match text.rfind('\n') {
+        Some(i) if text[i + 1..].trim().is_empty() => &text[..i],
+        _ => text,
+    };
+    let common = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    text.lines()
+        .map(|l| if l.trim().is_empty() { "" } else { &l[common..] })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 // --- Value parsing ---
@@ -197,36 +584,7 @@ fn unquote(s: &str) -> String {
 fn parse_value(pair: Pair<Rule>) -> Result<Value, ParseError> {
     let inner = pair.into_inner().next().unwrap();
     match inner.as_rule() {
-        Rule::string_literal => Ok(Value::String(inner.as_str().to_string())),
-        Rule::status_literal => {
-            let status = match inner.as_str() {
-                "green" => Status::Green,
-                "yellow" => Status::Yellow,
-                "red" => Status::Red,
-                _ => unreachable!(),
-            };
-            Ok(Value::Status(status))
-        }
-        Rule::bool_literal => Ok(Value::Bool(inner.as_str() == "true")),
-        Rule::number_literal => {
-            let n: i64 = inner.as_str().parse().map_err(|_| ParseError::InvalidValue {
-                field: "number".to_string(),
-                message: format!("invalid number: {}", inner.as_str()),
-            })?;
-            Ok(Value::Number(n))
-        }
-        Rule::fn_ref => {
-            // fn_ref children are: fn_keyword, ident — skip fn_keyword
-            let mut fn_inner = inner.into_inner();
-            fn_inner.next(); // skip fn_keyword
-            let name = fn_inner.next().unwrap().as_str().to_string();
-            Ok(Value::FnRef(name))
-        }
-        Rule::ident_path => {
-            let parts: Vec<String> = inner.as_str().split("::").map(|s| s.to_string()).collect();
-            Ok(Value::Path(parts))
-        }
-        Rule::ident => Ok(Value::Ident(inner.as_str().to_string())),
+        Rule::fn_ref => parse_fn_ref(inner),
         Rule::list => {
             let items: Result<Vec<Value>, _> = inner
                 .into_inner()
@@ -237,32 +595,51 @@ fn parse_value(pair: Pair<Rule>) -> Result<Value, ParseError> {
                 .collect();
             Ok(Value::List(items?))
         }
-        Rule::tuple => {
-            let items: Result<Vec<Value>, ParseError> = inner
-                .into_inner()
-                .map(|item| -> Result<Value, ParseError> {
-                    let inner_val = item.into_inner().next().unwrap();
-                    match inner_val.as_rule() {
-                        Rule::string_literal => Ok(Value::String(inner_val.as_str().to_string())),
-                        Rule::ident => Ok(Value::Ident(inner_val.as_str().to_string())),
-                        _ => Ok(Value::Ident(inner_val.as_str().to_string())),
-                    }
-                })
-                .collect();
-            Ok(Value::Tuple(items?))
-        }
         Rule::nested_block => {
-            let kv_map = extract_kv_map(inner.into_inner().next().unwrap().into_inner())?;
-            let pairs: Vec<(String, Value)> = kv_map.into_iter().collect();
+            // Keep source order so blocks re-serialize the way they were written
+            let kv_list = inner.into_inner().next().unwrap();
+            let mut pairs = Vec::new();
+            for kv in kv_list.into_inner() {
+                let mut kv_inner = kv.into_inner();
+                let key = kv_inner.next().unwrap().as_str().to_string();
+                pairs.push((key, parse_value(kv_inner.next().unwrap())?));
+            }
             Ok(Value::Block(pairs))
         }
-        _ => Ok(Value::Ident(inner.as_str().to_string())),
+        _ => parse_list_item_value(inner),
+    }
+}
+
+fn parse_fn_ref(pair: Pair<Rule>) -> Result<Value, ParseError> {
+    // fn_ref children are: fn_keyword, then the name — skip fn_keyword
+    let name = pair.into_inner().nth(1).unwrap();
+    Ok(Value::FnRef(fn_name(&name)?))
+}
+
+/// A function name from an `ident` or `fn_path` pair, in canonical `FnPath` form.
+fn fn_name(pair: &Pair<Rule>) -> Result<String, ParseError> {
+    if pair.as_rule() == Rule::ident {
+        return Ok(pair.as_str().to_string());
     }
+    FnPath::parse(pair.as_str())
+        .map(|path| path.to_string())
+        .map_err(|message| ParseError::InvalidValue {
+            field: "fn".to_string(),
+            message,
+            span: Span::from(pair.as_span()),
+        })
 }
 
 fn parse_list_item_value(pair: Pair<Rule>) -> Result<Value, ParseError> {
     match pair.as_rule() {
-        Rule::string_literal => Ok(Value::String(pair.as_str().to_string())),
+        Rule::string_literal => {
+            decode_string(pair.as_str()).map_err(|message| ParseError::InvalidValue {
+                field: "string".to_string(),
+                message,
+                span: pair.as_span().into(),
+            })?;
+            Ok(Value::String(pair.as_str().to_string()))
+        }
         Rule::status_literal => {
             let status = match pair.as_str() {
                 "green" => Status::Green,
@@ -273,17 +650,28 @@ fn parse_list_item_value(pair: Pair<Rule>) -> Result<Value, ParseError> {
             Ok(Value::Status(status))
         }
         Rule::bool_literal => Ok(Value::Bool(pair.as_str() == "true")),
-        Rule::number_literal => {
-            let n: i64 = pair.as_str().parse().map_err(|_| ParseError::InvalidValue {
-                field: "number".to_string(),
-                message: format!("invalid number: {}", pair.as_str()),
+        Rule::date_literal => {
+            let date = parse_date(pair.as_str()).ok_or_else(|| ParseError::InvalidValue {
+                field: "date".to_string(),
+                message: format!("invalid date: {}", pair.as_str()),
+                span: pair.as_span().into(),
             })?;
-            Ok(Value::Number(n))
+            Ok(Value::Date(date))
         }
-        Rule::fn_ref => {
-            let name = pair.into_inner().next().unwrap().as_str().to_string();
-            Ok(Value::FnRef(name))
+        Rule::number_literal => {
+            let text = pair.as_str();
+            let invalid = || ParseError::InvalidValue {
+                field: "number".to_string(),
+                message: format!("invalid number: {text}"),
+                span: pair.as_span().into(),
+            };
+            if text.contains(['.', 'e', 'E']) {
+                text.parse().map(Value::Float).map_err(|_| invalid())
+            } else {
+                text.parse().map(Value::Number).map_err(|_| invalid())
+            }
         }
+        Rule::fn_ref => parse_fn_ref(pair),
         Rule::ident_path => {
             let parts: Vec<String> = pair.as_str().split("::").map(|s| s.to_string()).collect();
             Ok(Value::Path(parts))
@@ -292,14 +680,7 @@ fn parse_list_item_value(pair: Pair<Rule>) -> Result<Value, ParseError> {
         Rule::tuple => {
             let items: Result<Vec<Value>, ParseError> = pair
                 .into_inner()
-                .map(|item| -> Result<Value, ParseError> {
-                    let inner_val = item.into_inner().next().unwrap();
-                    match inner_val.as_rule() {
-                        Rule::string_literal => Ok(Value::String(inner_val.as_str().to_string())),
-                        Rule::ident => Ok(Value::Ident(inner_val.as_str().to_string())),
-                        _ => Ok(Value::Ident(inner_val.as_str().to_string())),
-                    }
-                })
+                .map(|item| parse_list_item_value(item.into_inner().next().unwrap()))
                 .collect();
             Ok(Value::Tuple(items?))
         }
@@ -307,23 +688,27 @@ fn parse_list_item_value(pair: Pair<Rule>) -> Result<Value, ParseError> {
     }
 }
 
+fn parse_date(s: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+}
+
 // --- Annotation type parsers ---
 
-fn parse_repo(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
-    let map = get_kv_list_from_parens(&mut pairs)?;
+fn parse_repo(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
+    let map = get_kv_list_from_parens(&mut pairs, span)?;
     Ok(Annotation::Repo(RepoAnnotation {
         name: require_string(&map, "name", "repo")?,
         version: require_string(&map, "version", "repo")?,
-        updated: require_string(&map, "updated", "repo")?,
+        updated: require_date(&map, "updated", "repo")?,
     }))
 }
 
-fn parse_file(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
-    let map = get_kv_list_from_parens(&mut pairs)?;
+fn parse_file(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
+    let map = get_kv_list_from_parens(&mut pairs, span)?;
     Ok(Annotation::File(FileAnnotation {
         owner: require_string(&map, "owner", "file")?,
         subsystem: require_string(&map, "subsystem", "file")?,
-        updated: require_string(&map, "updated", "file")?,
+        updated: require_date(&map, "updated", "file")?,
         status: require_status(&map, "status", "file")?,
     }))
 }
@@ -333,10 +718,10 @@ fn parse_description(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
     Ok(Annotation::Description(text))
 }
 
-fn parse_health(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
-    let map = get_kv_list_from_parens(&mut pairs)?;
+fn parse_health(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
+    let map = get_kv_list_from_parens(&mut pairs, span)?;
     let mut dimensions = HashMap::new();
-    for (key, val) in map {
+    for (key, val) in map.values {
         if let Value::Status(s) = val {
             dimensions.insert(key, s);
         }
@@ -344,13 +729,14 @@ fn parse_health(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
     Ok(Annotation::Health(HealthAnnotation { dimensions }))
 }
 
-fn parse_fn(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
-    let name = get_ident_from_parens(&mut pairs)
+fn parse_fn(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
+    let name = get_fn_name_from_parens(&mut pairs)?
         .ok_or_else(|| ParseError::MissingField {
             context: "fn".to_string(),
             field: "name".to_string(),
+            span,
         })?;
-    let map = get_body_kv_map(&mut pairs)?;
+    let map = get_body_kv_map(&mut pairs, span)?;
 
     let contract = if let Some(Value::Block(block_pairs)) = map.get("contract") {
         let block_map: HashMap<String, Value> = block_pairs.iter().cloned().collect();
@@ -358,20 +744,20 @@ fn parse_fn(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
             Some(Value::List(items)) => items
                 .iter()
                 .filter_map(|v| {
-                    if let Value::Tuple(parts) = v {
-                        if parts.len() == 2 {
-                            let name = match &parts[0] {
-                                Value::Ident(s) => s.clone(),
-                                Value::String(s) => unquote(s),
-                                _ => return None,
-                            };
-                            let ty = match &parts[1] {
-                                Value::Ident(s) => s.clone(),
-                                Value::String(s) => unquote(s),
-                                _ => return None,
-                            };
-                            return Some((name, ty));
-                        }
+                    if let Value::Tuple(parts) = v
+                        && parts.len() == 2
+                    {
+                        let name = match &parts[0] {
+                            Value::Ident(s) => s.clone(),
+                            Value::String(s) => unquote(s),
+                            _ => return None,
+                        };
+                        let ty = match &parts[1] {
+                            Value::Ident(s) => s.clone(),
+                            Value::String(s) => unquote(s),
+                            _ => return None,
+                        };
+                        return Some((name, ty));
                     }
                     None
                 })
@@ -413,13 +799,33 @@ fn parse_fn(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
     }))
 }
 
-fn parse_subsystem(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
+fn parse_item(kind: &str, mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
+    let name = get_ident_from_parens(&mut pairs)
+        .ok_or_else(|| ParseError::MissingField {
+            context: kind.to_string(),
+            field: "name".to_string(),
+            span,
+        })?;
+    let map = get_body_kv_map(&mut pairs, span)?;
+
+    Ok(Annotation::Item(ItemAnnotation {
+        kind: ItemKind::from_annotation(kind).unwrap(),
+        name,
+        status: require_status(&map, "status", kind)?,
+        refs: extract_string_list(&map, "refs"),
+        invariants: extract_string_list(&map, "invariants"),
+        description: opt_string(&map, "description"),
+    }))
+}
+
+fn parse_subsystem(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
     let name = get_ident_from_parens(&mut pairs)
         .ok_or_else(|| ParseError::MissingField {
             context: "subsystem".to_string(),
             field: "name".to_string(),
+            span,
         })?;
-    let map = get_body_kv_map(&mut pairs)?;
+    let map = get_body_kv_map(&mut pairs, span)?;
 
     Ok(Annotation::Subsystem(SubsystemDecl {
         name,
@@ -431,13 +837,14 @@ fn parse_subsystem(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
     }))
 }
 
-fn parse_skimsystem(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
+fn parse_skimsystem(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
     let name = get_ident_from_parens(&mut pairs)
         .ok_or_else(|| ParseError::MissingField {
             context: "skimsystem".to_string(),
             field: "name".to_string(),
+            span,
         })?;
-    let map = get_body_kv_map(&mut pairs)?;
+    let map = get_body_kv_map(&mut pairs, span)?;
 
     let targets = match map.get("targets") {
         Some(Value::Ident(s)) if s == "all" => SkimTargets::All,
@@ -466,6 +873,7 @@ fn parse_skimsystem(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
                         return Err(ParseError::MissingField {
                             context: format!("skimsystem integration '{int_name}'"),
                             field: "command".to_string(),
+                            span: map.value_span(&format!("integrations.{int_name}")),
                         })
                     }
                 };
@@ -477,12 +885,14 @@ fn parse_skimsystem(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
                         return Err(ParseError::InvalidValue {
                             field: "format".to_string(),
                             message: format!("unknown integration format: {other:?}"),
+                            span: map.value_span(&format!("integrations.{int_name}.format")),
                         })
                     }
                     None => {
                         return Err(ParseError::MissingField {
                             context: format!("skimsystem integration '{int_name}'"),
                             field: "format".to_string(),
+                            span: map.value_span(&format!("integrations.{int_name}")),
                         })
                     }
                 };
@@ -510,13 +920,14 @@ fn parse_skimsystem(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
     }))
 }
 
-fn parse_skim(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
+fn parse_skim(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
     let skimsystem = get_ident_from_parens(&mut pairs)
         .ok_or_else(|| ParseError::MissingField {
             context: "skim".to_string(),
             field: "skimsystem name".to_string(),
+            span,
         })?;
-    let map = get_body_kv_map(&mut pairs)?;
+    let map = get_body_kv_map(&mut pairs, span)?;
 
     let target = match map.get("target") {
         Some(Value::FnRef(name)) => Some(SkimTarget::Fn(name.clone())),
@@ -532,36 +943,35 @@ fn parse_skim(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
     }))
 }
 
-fn parse_policies(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
-    let map = get_body_kv_map(&mut pairs)?;
-    Ok(Annotation::Policies(PoliciesAnnotation { fields: map }))
+fn parse_policies(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
+    let map = get_body_kv_map(&mut pairs, span)?;
+    Ok(Annotation::Policies(PoliciesAnnotation { fields: map.values }))
 }
 
 fn parse_change_requests(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
     let mut requests = Vec::new();
-    if let Some(body) = pairs.next() {
-        if body.as_rule() == Rule::body {
-            if let Some(content) = body.into_inner().next() {
-                if content.as_rule() == Rule::body_content {
-                    for inner in content.into_inner() {
-                        if inner.as_rule() == Rule::annotation {
-                            let mut ann_inner = inner.into_inner();
-                            let ident = ann_inner.next().unwrap();
-                            if ident.as_str() == "request" {
-                                let map = get_kv_list_from_parens(&mut ann_inner)?;
-                                requests.push(ChangeRequest {
-                                    id: require_string(&map, "id", "request")?,
-                                    from: require_string(&map, "from", "request")?,
-                                    target: map.get("target").cloned().unwrap_or(Value::Ident("unknown".to_string())),
-                                    change_type: require_string(&map, "type", "request")?,
-                                    status: require_string(&map, "status", "request")?,
-                                    priority: opt_string(&map, "priority"),
-                                    created: require_string(&map, "created", "request")?,
-                                    description: require_string(&map, "description", "request")?,
-                                });
-                            }
-                        }
-                    }
+    if let Some(body) = pairs.next()
+        && body.as_rule() == Rule::body
+        && let Some(content) = body.into_inner().next()
+        && content.as_rule() == Rule::body_content
+    {
+        for inner in content.into_inner() {
+            if inner.as_rule() == Rule::annotation {
+                let request_span = Span::from(inner.as_span());
+                let mut ann_inner = inner.into_inner();
+                let ident = ann_inner.next().unwrap();
+                if ident.as_str() == "request" {
+                    let map = get_kv_list_from_parens(&mut ann_inner, request_span)?;
+                    requests.push(ChangeRequest {
+                        id: require_string(&map, "id", "request")?,
+                        from: require_string(&map, "from", "request")?,
+                        target: map.get("target").cloned().unwrap_or(Value::Ident("unknown".to_string())),
+                        change_type: require_string(&map, "type", "request")?,
+                        status: require_string(&map, "status", "request")?,
+                        priority: opt_string(&map, "priority"),
+                        created: require_date(&map, "created", "request")?,
+                        description: require_string(&map, "description", "request")?,
+                    });
                 }
             }
         }
@@ -569,12 +979,12 @@ fn parse_change_requests(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseErro
     Ok(Annotation::ChangeRequests(requests))
 }
 
-fn parse_pickled(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
-    let parens_map = get_kv_list_from_parens(&mut pairs)?;
+fn parse_pickled(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
+    let parens_map = get_kv_list_from_parens(&mut pairs, span)?;
     let agent = require_string(&parens_map, "agent", "pickled")?;
-    let updated = require_string(&parens_map, "updated", "pickled")?;
+    let updated = require_date(&parens_map, "updated", "pickled")?;
 
-    let body_map = get_body_kv_map(&mut pairs)?;
+    let body_map = get_body_kv_map(&mut pairs, span)?;
     let id = require_string(&body_map, "id", "pickled")?;
     let kind = match body_map.get("kind") {
         Some(Value::Ident(s)) => match s.as_str() {
@@ -586,17 +996,20 @@ fn parse_pickled(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
             other => return Err(ParseError::InvalidValue {
                 field: "kind".to_string(),
                 message: format!("unknown pickled kind: {other}"),
+                span: body_map.value_span("kind"),
             }),
         },
         _ => return Err(ParseError::MissingField {
             context: "pickled".to_string(),
             field: "kind".to_string(),
+            span: body_map.span,
         }),
     };
     let supersedes = opt_string(&body_map, "supersedes");
     let tags = extract_string_list(&body_map, "tags")
         .iter()
-        .map(|s| parse_pickled_tag(s))
+        .enumerate()
+        .map(|(i, s)| parse_pickled_tag(s, body_map.value_span(&format!("tags[{i}]"))))
         .collect::<Result<Vec<_>, _>>()?;
     let content = require_string(&body_map, "content", "pickled")?;
 
@@ -611,7 +1024,7 @@ fn parse_pickled(mut pairs: Pairs<Rule>) -> Result<Annotation, ParseError> {
     }))
 }
 
-fn parse_pickled_tag(s: &str) -> Result<PickledTag, ParseError> {
+fn parse_pickled_tag(s: &str, span: Span) -> Result<PickledTag, ParseError> {
     match s {
         "architecture" => Ok(PickledTag::Architecture),
         "performance" => Ok(PickledTag::Performance),
@@ -624,10 +1037,125 @@ fn parse_pickled_tag(s: &str) -> Result<PickledTag, ParseError> {
         other => Err(ParseError::InvalidValue {
             field: "tags".to_string(),
             message: format!("unknown pickled tag: {other}"),
+            span,
         }),
     }
 }
 
+fn parse_schema(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
+    let kind = get_ident_from_parens(&mut pairs).ok_or_else(|| ParseError::MissingField {
+        context: "annotation".to_string(),
+        field: "kind".to_string(),
+        span,
+    })?;
+    let map = get_body_kv_map(&mut pairs, span)?;
+
+    let mut fields = Vec::new();
+    for (key, required) in [("required", true), ("optional", false)] {
+        let Some(value) = map.get(key) else {
+            continue;
+        };
+        let Value::Block(entries) = value else {
+            return Err(ParseError::InvalidValue {
+                field: key.to_string(),
+                message: "expected a block of field = type pairs".to_string(),
+                span: map.value_span(key),
+            });
+        };
+        for (name, ty) in entries {
+            let ty_name = match ty {
+                Value::Ident(s) => s.clone(),
+                Value::String(s) => unquote(s),
+                _ => String::new(),
+            };
+            let ty = ty_name.parse().map_err(|message| ParseError::InvalidValue {
+                field: format!("{key}.{name}"),
+                message,
+                span: map.value_span(&format!("{key}.{name}")),
+            })?;
+            fields.push(FieldSpec {
+                name: name.clone(),
+                ty,
+                required,
+            });
+        }
+    }
+
+    Ok(Annotation::Schema(AnnotationSchema {
+        kind,
+        fields,
+        description: opt_string(&map, "description"),
+    }))
+}
+
+fn parse_include(mut pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
+    if let Some(parens) = pairs.next()
+        && parens.as_rule() == Rule::parens
+        && let Some(content) = parens.into_inner().next()
+        && let Some(inner) = content.into_inner().next()
+        && inner.as_rule() == Rule::string_literal
+    {
+        return Ok(Annotation::Include(unquote(inner.as_str())));
+    }
+    Err(ParseError::MissingField {
+        context: "include".to_string(),
+        field: "path".to_string(),
+        span,
+    })
+}
+
+fn parse_custom(kind: &str, pairs: Pairs<Rule>, span: Span) -> Result<Annotation, ParseError> {
+    let mut name = None;
+    let mut fields = Vec::new();
+    let mut text = None;
+
+    for pair in pairs {
+        let Some(content) = pair.clone().into_inner().next() else {
+            continue;
+        };
+        let Some(inner) = content.into_inner().next() else {
+            continue;
+        };
+        match inner.as_rule() {
+            Rule::ident => name = Some(inner.as_str().to_string()),
+            Rule::string_literal => name = Some(unquote(inner.as_str())),
+            // A qualified target, as in #[fn(Type::name)]
+            Rule::fn_path => {
+                let path = FnPath::parse(inner.as_str()).map_err(|message| {
+                    ParseError::InvalidValue {
+                        field: kind.to_string(),
+                        message,
+                        span: Span::from(inner.as_span()),
+                    }
+                })?;
+                name = Some(path.to_string());
+            }
+            Rule::kv_list => {
+                for kv in inner.into_inner() {
+                    let mut kv_inner = kv.into_inner();
+                    let key = kv_inner.next().unwrap().as_str().to_string();
+                    fields.push((key, parse_value(kv_inner.next().unwrap())?));
+                }
+            }
+            Rule::freeform_text => text = Some(dedent(inner.as_str().trim())),
+            _ => {
+                return Err(ParseError::InvalidValue {
+                    field: kind.to_string(),
+                    message: "custom annotations cannot contain nested annotations".to_string(),
+                    span,
+                });
+            }
+        }
+    }
+
+    Ok(Annotation::Custom(CustomAnnotation {
+        kind: kind.to_string(),
+        name,
+        fields,
+        text,
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -728,6 +1256,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_qualified_fn_refs() {
+        let input = r#"
+#[fn(Auth::new) { status = green }]
+#[fn(<Auth  as Default >::default) { status = green }]
+#[fn(<Wrapper<T> >::get) { status = green }]
+#[skim(q) { target = fn(<Auth as Verify>::check), status = red }]
+"#;
+        let bog = parse_bog(input).unwrap();
+        let names: Vec<&str> = bog
+            .annotations
+            .iter()
+            .filter_map(|a| match a {
+                Annotation::Fn(f) => Some(f.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, ["Auth::new", "<Auth as Default>::default", "<Wrapper<T>>::get"]);
+        assert!(matches!(
+            &bog.annotations[3],
+            Annotation::Skim(s) if s.target == Some(SkimTarget::Fn("<Auth as Verify>::check".to_string()))
+        ));
+
+        let path = FnPath::parse("<Auth as auth::Verify>::check").unwrap();
+        assert_eq!(path.self_type.as_deref(), Some("Auth"));
+        assert_eq!(path.trait_name.as_deref(), Some("auth::Verify"));
+        assert_eq!(path.name, "check");
+        assert!(parse_bog("#[fn(<as Foo>::x) { status = green }]").is_err());
+    }
+
+    #[test]
+    fn test_parse_item_annotations() {
+        let input = r#"
+#[type(Token) {
+  status = green,
+  refs = [auth::login],
+  invariants = ["never holds an expired token"],
+  description = "Signed session token"
+}]
+#[trait(Verifier) { status = yellow }]
+#[const(MAX_USERS) { status = green }]
+"#;
+        let bog = parse_bog(input).unwrap();
+        let items: Vec<&ItemAnnotation> = bog
+            .annotations
+            .iter()
+            .filter_map(|a| match a {
+                Annotation::Item(i) => Some(i),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].kind, ItemKind::Type);
+        assert_eq!(items[0].name, "Token");
+        assert_eq!(items[0].refs, ["auth::login"]);
+        assert_eq!(items[0].invariants.len(), 1);
+        assert_eq!(items[1].kind, ItemKind::Trait);
+        assert_eq!(items[1].status, Status::Yellow);
+        assert_eq!(items[2].kind, ItemKind::Const);
+
+        let err = parse_bog("#[type(Token) { description = \"x\" }]").unwrap_err();
+        assert!(err.message().contains("status"));
+    }
+
     #[test]
     fn test_parse_repo() {
         let input = r#"
@@ -1217,4 +1809,305 @@ mod tests {
             other => panic!("expected Skimsystem, got {:?}", other),
         }
     }
+
+    #[test]
+    fn test_parse_custom_annotation_forms() {
+        let input = r#"#[runbook(deploy) { url = "https://wiki/deploy", oncall = platform }]
+#[slo(target = 999, window = "30d")]
+#[feature_flag(checkout_v2) {
+  Gates the new checkout flow.
+}]"#;
+        let bog = parse_bog(input).unwrap();
+        assert_eq!(bog.annotations.len(), 3);
+        match &bog.annotations[0] {
+            Annotation::Custom(c) => {
+                assert_eq!(c.kind, "runbook");
+                assert_eq!(c.name.as_deref(), Some("deploy"));
+                assert_eq!(c.fields[0].0, "url");
+                assert_eq!(c.get("oncall"), Some(&Value::Ident("platform".to_string())));
+            }
+            other => panic!("expected Custom, got {:?}", other),
+        }
+        match &bog.annotations[1] {
+            Annotation::Custom(c) => {
+                assert_eq!(c.name, None);
+                assert_eq!(c.get("target"), Some(&Value::Number(999)));
+            }
+            other => panic!("expected Custom, got {:?}", other),
+        }
+        match &bog.annotations[2] {
+            Annotation::Custom(c) => {
+                assert!(c.fields.is_empty());
+                assert_eq!(c.text.as_deref(), Some("Gates the new checkout flow."));
+            }
+            other => panic!("expected Custom, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_custom_annotation_with_qualified_target() {
+        let bog = parse_bog(
+            "#[runbook(Auth::verify) { url = \"https://wiki/verify\" }]\n\
+             #[runbook(< Auth as Verify >::check)]",
+        )
+        .unwrap();
+        let names: Vec<Option<&str>> = bog
+            .annotations
+            .iter()
+            .map(|a| match a {
+                Annotation::Custom(c) => c.name.as_deref(),
+                other => panic!("expected Custom, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(names, [Some("Auth::verify"), Some("<Auth as Verify>::check")]);
+    }
+
+    #[test]
+    fn test_parse_unknown_kind_is_custom() {
+        let bog = parse_bog("#[fnn(login) { status = green }]").unwrap();
+        match &bog.annotations[0] {
+            Annotation::Custom(c) => assert_eq!(c.kind, "fnn"),
+            other => panic!("expected Custom, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_annotation_schema() {
+        let input = r#"#[annotation(slo) {
+  required = { target = number, window = string },
+  optional = { owner = string },
+  description = "Service level objective"
+}]"#;
+        let bog = parse_bog(input).unwrap();
+        match &bog.annotations[0] {
+            Annotation::Schema(schema) => {
+                assert_eq!(schema.kind, "slo");
+                assert_eq!(schema.fields.len(), 3);
+                assert_eq!(schema.fields[0].name, "target");
+                assert_eq!(schema.fields[0].ty, FieldType::Number);
+                assert!(schema.fields[0].required);
+                assert!(!schema.fields[2].required);
+            }
+            other => panic!("expected Schema, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_string_escapes_and_raw_strings() {
+        let input = r##"#[policies {
+  esc = "line\nnext \"q\" tab\t\\ \u{e9}",
+  raw = r"C:\tmp\new",
+  hashed = r#"say "hi""#
+}]"##;
+        let bog = parse_bog(input).unwrap();
+        let Annotation::Policies(p) = &bog.annotations[0] else {
+            panic!("expected Policies");
+        };
+        let text = |key: &str| match &p.fields[key] {
+            Value::String(s) => unquote(s),
+            other => panic!("expected string, got {:?}", other),
+        };
+        assert_eq!(text("esc"), "line\nnext \"q\" tab\t\\ é");
+        assert_eq!(text("raw"), r"C:\tmp\new");
+        assert_eq!(text("hashed"), r#"say "hi""#);
+    }
+
+    #[test]
+    fn test_parse_triple_quoted_string_is_dedented() {
+        let input = r#"#[pickled(agent = "a", updated = "2026-01-01") {
+  id = "p1",
+  kind = context,
+  content = """
+    Line one with "quotes".
+
+      Indented line.
+    """
+}]"#;
+        let bog = parse_bog(input).unwrap();
+        let Annotation::Pickled(p) = &bog.annotations[0] else {
+            panic!("expected Pickled");
+        };
+        assert_eq!(p.content, "Line one with \"quotes\".\n\n  Indented line.");
+    }
+
+    #[test]
+    fn test_parse_signed_and_float_numbers() {
+        let input = "#[policies { a = -3, b = 0.25, c = -1.5e3, d = [1, -2, 2.5] }]";
+        let bog = parse_bog(input).unwrap();
+        let Annotation::Policies(p) = &bog.annotations[0] else {
+            panic!("expected Policies");
+        };
+        assert_eq!(p.fields["a"], Value::Number(-3));
+        assert_eq!(p.fields["b"], Value::Float(0.25));
+        assert_eq!(p.fields["c"], Value::Float(-1500.0));
+        assert_eq!(
+            p.fields["d"],
+            Value::List(vec![Value::Number(1), Value::Number(-2), Value::Float(2.5)])
+        );
+    }
+
+    #[test]
+    fn test_parse_dates() {
+        let input = "#[file(owner = \"a\", subsystem = \"s\", updated = 2026-03-01, status = green)]\n\
+                     #[policies { since = 2026-01-31 }]";
+        let bog = parse_bog(input).unwrap();
+        match &bog.annotations[0] {
+            Annotation::File(f) => assert_eq!(f.updated, "2026-03-01"),
+            other => panic!("expected File, got {:?}", other),
+        }
+        let Annotation::Policies(p) = &bog.annotations[1] else {
+            panic!("expected Policies");
+        };
+        let expected = chrono::NaiveDate::from_ymd_opt(2026, 1, 31).unwrap();
+        assert_eq!(p.fields["since"], Value::Date(expected));
+    }
+
+    #[test]
+    fn test_parse_include() {
+        let bog = parse_bog("#[include(\"subsystems/*.bog\")]").unwrap();
+        assert_eq!(
+            bog.annotations[0],
+            Annotation::Include("subsystems/*.bog".to_string())
+        );
+        assert!(parse_bog("#[include(subsystems)]").is_err());
+    }
+
+    fn error_text(input: &str) -> String {
+        let err = parse_bog(input).unwrap_err();
+        let span = err.span();
+        input[span.start..span.end].to_string()
+    }
+
+    #[test]
+    fn test_error_span_bad_status() {
+        let input = "#[fn(login) {\n  status = gren\n}]";
+        assert_eq!(error_text(input), "gren");
+    }
+
+    #[test]
+    fn test_error_span_missing_field_covers_annotation() {
+        let input = "#[description { ok }]\n#[fn(login) {\n  description = \"x\"\n}]";
+        let err = parse_bog(input).unwrap_err();
+        assert!(matches!(err, ParseError::MissingField { .. }));
+        assert!(error_text(input).starts_with("#[fn(login)"));
+    }
+
+    #[test]
+    fn test_error_span_unknown_pickled_tag() {
+        let input = r#"#[pickled(agent = "a", updated = "2026-01-01") {
+  id = "p1", kind = decision, tags = [testing, bogus], content = "c"
+}]"#;
+        assert_eq!(error_text(input), "bogus");
+    }
+
+    #[test]
+    fn test_error_span_unknown_integration_format() {
+        let input = r#"#[skimsystem(q) {
+  owner = "a", status = green,
+  integrations = { clippy = { command = "c", format = weird } }
+}]"#;
+        assert_eq!(error_text(input), "weird");
+    }
+
+    #[test]
+    fn test_error_span_unknown_field_type() {
+        let input = "#[annotation(slo) { required = { target = percent } }]";
+        assert_eq!(error_text(input), "percent");
+    }
+
+    #[test]
+    fn test_error_span_invalid_dates_and_escapes() {
+        let input = r#"#[file(owner = "a", subsystem = "s", updated = "last week", status = green)]"#;
+        assert_eq!(error_text(input), "\"last week\"");
+        let input = r#"#[repo(name = "x", version = "1", updated = 2026-02-30)]"#;
+        assert_eq!(error_text(input), "2026-02-30");
+        let input = r#"#[policies { path = "C:\dir" }]"#;
+        assert_eq!(error_text(input), r#""C:\dir""#);
+    }
+
+    #[test]
+    fn test_error_span_syntax_error_position() {
+        let input = "#[file(owner = )]";
+        let err = parse_bog(input).unwrap_err();
+        assert_eq!(err.span().start, input.find(')').unwrap());
+        assert!(!err.message().contains('\n'));
+    }
+
+    #[test]
+    fn test_recovering_skips_bad_annotation() {
+        let input = r#"#[file(owner = "a", subsystem = "s", updated = "2026-01-01", status = green)]
+
+#[pickled(agent = "a", updated = "2026-01-01") {
+  id = "p1", kind = decision, tags = [bogus], content = "c"
+}]
+
+#[fn(login) {
+  status = gren
+}]
+
+#[fn(logout) {
+  status = green
+}]
+"#;
+        let (bog, errors) = parse_bog_recovering(input);
+        assert_eq!(bog.annotations.len(), 2);
+        assert!(matches!(&bog.annotations[1], Annotation::Fn(f) if f.name == "logout"));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(&input[errors[0].span().start..errors[0].span().end], "bogus");
+        assert_eq!(&input[errors[1].span().start..errors[1].span().end], "gren");
+    }
+
+    #[test]
+    fn test_recovering_resumes_after_syntax_error() {
+        let input = r#"// header
+#[description { ok }]
+#[fn(broken) {
+  status = green,,
+}]
+// between
+#[fn(after) {
+  status = green
+}]"#;
+        let (bog, errors) = parse_bog_recovering(input);
+        assert_eq!(bog.annotations.len(), 2);
+        assert!(matches!(&bog.annotations[1], Annotation::Fn(f) if f.name == "after"));
+        assert_eq!(errors.len(), 1);
+        let (line, _) = crate::diagnostic::line_col(input, errors[0].span().start);
+        assert_eq!(line, 4);
+    }
+
+    #[test]
+    fn test_recovering_resumes_at_indented_and_same_line_annotations() {
+        let input = r#"#[fn(a) { status = green,, note = "]} #[fn(x)" }]
+  #[fn(b) { status = green }]
+#[fn(c) { status = green,, }] #[fn(d) { status = green }]
+#[change_requests {
+  #[request(id = "r1",, )]
+  #[request(id = "r2", from = "a")]
+}]
+    #[fn(e) { status = green }]
+#[fn(f) { status = green
+  #[fn(g) { status = green }]
+"#;
+        let (bog, errors) = parse_bog_recovering(input);
+        let names: Vec<&str> = bog
+            .annotations
+            .iter()
+            .filter_map(|a| match a {
+                Annotation::Fn(f) => Some(f.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        // An unclosed annotation falls back to the next line starting with `#[`
+        assert_eq!(names, ["b", "d", "e", "g"]);
+        assert_eq!(errors.len(), 3, "{errors:?}");
+    }
+
+    #[test]
+    fn test_recovering_matches_strict_on_valid_input() {
+        let input = "#[description { a }]\n\n#[health(staleness = green)]\n";
+        let (bog, errors) = parse_bog_recovering(input);
+        assert!(errors.is_empty());
+        assert_eq!(bog.annotations.len(), parse_bog(input).unwrap().annotations.len());
+    }
 }
@@ -0,0 +1,374 @@
+use chrono::NaiveDate;
+
+use crate::ast::{Annotation, BogFile, FnPath, PoliciesAnnotation, Status, Value};
+use crate::treesitter::{self, Symbol};
+
+/// How a policy violation is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn parse(value: &Value) -> Option<Level> {
+        match value {
+            Value::Ident(s) if s == "warning" => Some(Level::Warning),
+            Value::Ident(s) if s == "error" => Some(Level::Error),
+            _ => None,
+        }
+    }
+}
+
+/// The policies of repo.bog that the validator enforces. Other keys in
+/// `#[policies]` are left to the agents reading them.
+///
+/// ```text
+/// #[policies {
+///   require_contracts = true,       // or warning / error
+///   require_owner = error,
+///   health_thresholds = { red_max_days = 7, stale_after_days = 30 },
+///   levels = { stale_after_days = error }
+/// }]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Policies {
+    /// Every `pub` fn needs a `#[fn]` annotation with a contract
+    pub require_contracts: Option<Level>,
+    /// Every sidecar needs a `#[file]` annotation naming its owner
+    pub require_owner: Option<Level>,
+    /// A file, or a `#[fn]` in it, may be red for at most this many days. Sidecars do
+    /// not record when something turned red, so the file's `updated` date stands in:
+    /// a file edited since it turned red counts from the edit.
+    pub red_max_days: Option<(i64, Level)>,
+    /// A sidecar not updated for this many days is stale
+    pub stale_after_days: Option<(i64, Level)>,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("policy '{policy}': {message}")]
+pub struct PolicyError {
+    pub policy: String,
+    pub message: String,
+}
+
+/// An enforced policy, named as in `#[policies]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Policy {
+    RequireContracts,
+    RequireOwner,
+    RedMaxDays,
+    StaleAfterDays,
+}
+
+impl Policy {
+    pub fn key(self) -> &'static str {
+        match self {
+            Policy::RequireContracts => "require_contracts",
+            Policy::RequireOwner => "require_owner",
+            Policy::RedMaxDays => "red_max_days",
+            Policy::StaleAfterDays => "stale_after_days",
+        }
+    }
+}
+
+impl std::fmt::Display for Policy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+/// One file breaking one policy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub policy: Policy,
+    pub level: Level,
+    /// The `#[fn]` the violation is about, for locating it in the sidecar
+    pub function: Option<String>,
+    pub message: String,
+}
+
+impl Policies {
+    /// Read the enforced policies from a `#[policies]` block. A policy set to `true`
+    /// is a warning; `warning` or `error` (directly or in `levels`) picks the level.
+    ///
+    /// # Errors
+    ///
+    /// Returns every enforced policy whose value is not understood.
+    pub fn from_annotation(p: &PoliciesAnnotation) -> Result<Policies, Vec<PolicyError>> {
+        let mut errors = Vec::new();
+        let levels = block(p, "levels", &mut errors);
+        let thresholds = block(p, "health_thresholds", &mut errors);
+
+        let policies = Policies {
+            require_contracts: toggle(p, &levels, "require_contracts", &mut errors),
+            require_owner: toggle(p, &levels, "require_owner", &mut errors),
+            red_max_days: days(&thresholds, &levels, "red_max_days", &mut errors),
+            stale_after_days: days(&thresholds, &levels, "stale_after_days", &mut errors),
+        };
+        if errors.is_empty() {
+            Ok(policies)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The enforced policies of a repo.bog, if it has a `#[policies]` block.
+    ///
+    /// # Errors
+    ///
+    /// See `from_annotation`.
+    pub fn from_repo(repo: &BogFile) -> Result<Policies, Vec<PolicyError>> {
+        repo.annotations
+            .iter()
+            .find_map(|a| match a {
+                Annotation::Policies(p) => Some(Policies::from_annotation(p)),
+                _ => None,
+            })
+            .unwrap_or_else(|| Ok(Policies::default()))
+    }
+
+    /// Check one sidecar, together with the symbols of its source file when that
+    /// could be parsed, as of `today`.
+    pub fn check_file(
+        &self,
+        bog: &BogFile,
+        symbols: Option<&[Symbol]>,
+        today: NaiveDate,
+    ) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let file = bog.annotations.iter().find_map(|a| match a {
+            Annotation::File(f) => Some(f),
+            _ => None,
+        });
+
+        if let Some(level) = self.require_owner
+            && file.is_none_or(|f| f.owner.trim().is_empty())
+        {
+            violations.push(Violation {
+                policy: Policy::RequireOwner,
+                level,
+                function: None,
+                message: "no #[file] annotation naming an owner".to_string(),
+            });
+        }
+
+        if let Some(symbols) = symbols {
+            violations.extend(self.check_contracts(bog, symbols));
+        }
+
+        let updated = file.and_then(|f| NaiveDate::parse_from_str(&f.updated, "%Y-%m-%d").ok());
+        if let (Some(f), Some(updated)) = (file, updated) {
+            let age = (today - updated).num_days();
+            if let Some((max, level)) = self.red_max_days
+                && age > max
+            {
+                let red_fns = bog.annotations.iter().filter_map(|a| match a {
+                    Annotation::Fn(func) if func.status == Status::Red => Some(Some(&func.name)),
+                    _ => None,
+                });
+                let whole_file = (f.status == Status::Red).then_some(None);
+                for function in whole_file.into_iter().chain(red_fns) {
+                    let what = function.map(|name| format!("fn '{name}' ")).unwrap_or_default();
+                    violations.push(Violation {
+                        policy: Policy::RedMaxDays,
+                        level,
+                        function: function.cloned(),
+                        message: format!(
+                            "{what}red since at least {updated} ({age} days, limit {max})"
+                        ),
+                    });
+                }
+            }
+            if let Some((max, level)) = self.stale_after_days
+                && age > max
+            {
+                violations.push(Violation {
+                    policy: Policy::StaleAfterDays,
+                    level,
+                    function: None,
+                    message: format!("last updated {updated} ({age} days ago, limit {max})"),
+                });
+            }
+        }
+
+        violations
+    }
+
+    /// `require_contracts` alone, for a source file's symbols and the annotations
+    /// describing it; an empty `bog` for a source file without a sidecar.
+    pub fn check_contracts(&self, bog: &BogFile, symbols: &[Symbol]) -> Vec<Violation> {
+        let Some(level) = self.require_contracts else {
+            return Vec::new();
+        };
+        symbols
+            .iter()
+            .filter(|s| s.kind.is_fn() && s.is_public() && !has_contract(bog, symbols, s))
+            .map(|symbol| {
+                let name = symbol.qualified_name();
+                Violation {
+                    policy: Policy::RequireContracts,
+                    level,
+                    message: format!("public fn '{name}' has no contract"),
+                    function: Some(name),
+                }
+            })
+            .collect()
+    }
+}
+
+fn policy_error(policy: &str, message: &str) -> PolicyError {
+    PolicyError {
+        policy: policy.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn block(
+    p: &PoliciesAnnotation,
+    key: &str,
+    errors: &mut Vec<PolicyError>,
+) -> Vec<(String, Value)> {
+    match p.fields.get(key) {
+        Some(Value::Block(pairs)) => pairs.clone(),
+        Some(_) => {
+            errors.push(policy_error(key, "expected a { ... } block"));
+            Vec::new()
+        }
+        None => Vec::new(),
+    }
+}
+
+/// The level from `levels`, warning by default.
+fn level_of(levels: &[(String, Value)], policy: &str, errors: &mut Vec<PolicyError>) -> Level {
+    match levels.iter().find(|(k, _)| k == policy) {
+        Some((_, v)) => Level::parse(v).unwrap_or_else(|| {
+            errors.push(policy_error(policy, "level must be warning or error"));
+            Level::Warning
+        }),
+        None => Level::Warning,
+    }
+}
+
+fn toggle(
+    p: &PoliciesAnnotation,
+    levels: &[(String, Value)],
+    policy: &str,
+    errors: &mut Vec<PolicyError>,
+) -> Option<Level> {
+    match p.fields.get(policy) {
+        None | Some(Value::Bool(false)) => None,
+        Some(Value::Bool(true)) => Some(level_of(levels, policy, errors)),
+        Some(v) => Level::parse(v).or_else(|| {
+            errors.push(policy_error(policy, "expected true, false, warning or error"));
+            None
+        }),
+    }
+}
+
+fn days(
+    thresholds: &[(String, Value)],
+    levels: &[(String, Value)],
+    policy: &str,
+    errors: &mut Vec<PolicyError>,
+) -> Option<(i64, Level)> {
+    match thresholds.iter().find(|(k, _)| k == policy) {
+        None => None,
+        Some((_, Value::Number(n))) if *n >= 0 => Some((*n, level_of(levels, policy, errors))),
+        Some(_) => {
+            errors.push(policy_error(policy, "expected a number of days"));
+            None
+        }
+    }
+}
+
+/// Whether some `#[fn]` annotation with a contract resolves to `target`.
+fn has_contract(bog: &BogFile, symbols: &[Symbol], target: &Symbol) -> bool {
+    bog.annotations.iter().any(|a| match a {
+        Annotation::Fn(f) if f.contract.is_some() => FnPath::parse(&f.name).is_ok_and(|path| {
+            treesitter::resolve_fn(symbols, &path)
+                .iter()
+                .any(|s| std::ptr::eq(*s, target))
+        }),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn policies(body: &str) -> Result<Policies, Vec<PolicyError>> {
+        let bog = parser::parse_bog(&format!("#[policies {{ {body} }}]")).unwrap();
+        Policies::from_repo(&bog)
+    }
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_policies_from_annotation() {
+        let p = policies(
+            "require_contracts = true, require_owner = error, max_fn_lines = 50,
+             health_thresholds = { red_max_days = 7, stale_after_days = 30 },
+             levels = { stale_after_days = error }",
+        )
+        .unwrap();
+        assert_eq!(p.require_contracts, Some(Level::Warning));
+        assert_eq!(p.require_owner, Some(Level::Error));
+        assert_eq!(p.red_max_days, Some((7, Level::Warning)));
+        assert_eq!(p.stale_after_days, Some((30, Level::Error)));
+
+        let errors = policies("require_owner = 3, health_thresholds = { red_max_days = soon }")
+            .unwrap_err();
+        let names: Vec<&str> = errors.iter().map(|e| e.policy.as_str()).collect();
+        assert_eq!(names, ["require_owner", "red_max_days"]);
+    }
+
+    #[test]
+    fn test_check_file_reports_each_policy() {
+        let p = policies(
+            "require_contracts = true, require_owner = true,
+             health_thresholds = { red_max_days = 7, stale_after_days = 30 }",
+        )
+        .unwrap();
+        let bog = parser::parse_bog(
+            r#"#[file(owner = "a", subsystem = "s", updated = "2026-01-01", status = red)]
+#[fn(login) { status = green, contract = { in = [(name, str)] } }]
+#[fn(logout) { status = green }]"#,
+        )
+        .unwrap();
+        let symbols = treesitter::extract_symbols(
+            "pub fn login(name: &str) {}\npub fn logout() {}\npub(crate) fn helper() {}\nfn private() {}
+mod inner { pub fn hidden() {} }",
+        )
+        .unwrap();
+
+        let violations = p.check_file(&bog, Some(&symbols), day("2026-01-20"));
+        let names: Vec<Policy> = violations.iter().map(|v| v.policy).collect();
+        assert_eq!(names, [Policy::RequireContracts, Policy::RedMaxDays]);
+        assert!(violations[0].message.contains("'logout'"));
+        assert_eq!(violations[0].function.as_deref(), Some("logout"));
+
+        let violations = p.check_file(&BogFile::default(), None, day("2026-01-20"));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].policy, Policy::RequireOwner);
+
+        // A red fn in a green file counts too
+        let red_fn = parser::parse_bog(
+            r#"#[file(owner = "a", subsystem = "s", updated = "2026-01-01", status = green)]
+#[fn(login) { status = red }]"#,
+        )
+        .unwrap();
+        let violations = p.check_file(&red_fn, None, day("2026-01-20"));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.starts_with("fn 'login' red since at least 2026-01-01"));
+
+        let fresh = p.check_file(&bog, Some(&symbols), day("2026-01-05"));
+        assert_eq!(fresh.len(), 1);
+        let stale = p.check_file(&bog, Some(&symbols), day("2026-03-01"));
+        assert_eq!(stale.last().unwrap().policy, Policy::StaleAfterDays);
+    }
+}
@@ -0,0 +1,471 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::cst;
+use crate::diagnostic::{self, Span};
+use crate::inline::InlineError;
+use crate::policy::Policy;
+use crate::validator::{ValidationError, ValidationReport, ValidationWarning};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReportError {
+    #[error("Unknown report format '{0}': expected json, sarif or junit")]
+    UnknownFormat(String),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Json,
+    Sarif,
+    Junit,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = ReportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ReportFormat::Json),
+            "sarif" => Ok(ReportFormat::Sarif),
+            "junit" => Ok(ReportFormat::Junit),
+            other => Err(ReportError::UnknownFormat(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 1-based, inclusive line and column range in a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Region {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// One error or warning, located as precisely as the validator allows.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub code: &'static str,
+    pub severity: Severity,
+    /// Path relative to the project root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Region>,
+    /// The message without the leading file name
+    pub message: String,
+}
+
+/// The diagnostics of a report, errors first, each in the validator's order.
+/// Paths are made relative to `root`, which is also used to read files for
+/// line numbers.
+pub fn diagnostics(report: &ValidationReport, root: &Path) -> Vec<Diagnostic> {
+    let errors = report.errors.iter().map(|error| {
+        diagnostic(
+            root,
+            error.code(),
+            Severity::Error,
+            error.file(),
+            &error.to_string(),
+            "In ",
+            |text| locate(error, text),
+        )
+    });
+    let warnings = report.warnings.iter().map(|warning| {
+        diagnostic(
+            root,
+            warning.code(),
+            Severity::Warning,
+            warning.file(),
+            &warning.to_string(),
+            "",
+            |text| locate_warning(warning, text),
+        )
+    });
+    errors.chain(warnings).collect()
+}
+
+/// One diagnostic, with the `{prefix}{file}: ` its message starts with removed and
+/// its span found by `locate` in the file's contents.
+fn diagnostic(
+    root: &Path,
+    code: &'static str,
+    severity: Severity,
+    file: Option<&str>,
+    message: &str,
+    prefix: &str,
+    locate: impl FnOnce(&str) -> Option<Region>,
+) -> Diagnostic {
+    let Some(file) = file else {
+        return Diagnostic {
+            code,
+            severity,
+            file: None,
+            span: None,
+            message: message.to_string(),
+        };
+    };
+    let rel = relative(root, file);
+    let span = std::fs::read_to_string(root.join(&rel))
+        .ok()
+        .and_then(|text| locate(&text));
+    Diagnostic {
+        code,
+        severity,
+        message: message
+            .strip_prefix(&format!("{prefix}{file}: "))
+            .unwrap_or(message)
+            .to_string(),
+        file: Some(rel),
+        span,
+    }
+}
+
+/// Render a report in a machine-readable format.
+///
+/// # Errors
+///
+/// Fails only if JSON serialization fails.
+pub fn render(
+    report: &ValidationReport,
+    root: &Path,
+    format: ReportFormat,
+) -> Result<String, ReportError> {
+    let diagnostics = diagnostics(report, root);
+    match format {
+        ReportFormat::Json => {
+            let doc = json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "files_checked": report.files_checked,
+                "errors": report.errors.len(),
+                "warnings": report.warnings.len(),
+                "diagnostics": diagnostics,
+            });
+            Ok(serde_json::to_string_pretty(&doc)? + "\n")
+        }
+        ReportFormat::Sarif => Ok(serde_json::to_string_pretty(&sarif(&diagnostics))? + "\n"),
+        ReportFormat::Junit => Ok(junit(report, &diagnostics)),
+    }
+}
+
+fn sarif(diagnostics: &[Diagnostic]) -> serde_json::Value {
+    let rules: BTreeSet<&str> = diagnostics.iter().map(|d| d.code).collect();
+    let results: Vec<serde_json::Value> = diagnostics
+        .iter()
+        .map(|d| {
+            let mut result = json!({
+                "ruleId": d.code,
+                "level": match d.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                },
+                "message": { "text": d.message },
+            });
+            if let Some(file) = &d.file {
+                let mut location = json!({ "artifactLocation": { "uri": file } });
+                if let Some(r) = d.span {
+                    location["region"] = json!({
+                        "startLine": r.start_line,
+                        "startColumn": r.start_column,
+                        "endLine": r.end_line,
+                        "endColumn": r.end_column,
+                    });
+                }
+                result["locations"] = json!([{ "physicalLocation": location }]);
+            }
+            result
+        })
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "bog",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// One failing test case per error, or a single passing one when there are none.
+/// Warnings go to the suite's system-out.
+fn junit(report: &ValidationReport, diagnostics: &[Diagnostic]) -> String {
+    let errors: Vec<&Diagnostic> =
+        diagnostics.iter().filter(|d| d.severity == Severity::Error).collect();
+    let tests = errors.len().max(1);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"bog validate\" tests=\"{tests}\" failures=\"{}\">",
+        errors.len()
+    );
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"bog validate\" tests=\"{tests}\" failures=\"{}\">",
+        errors.len()
+    );
+    let _ = writeln!(
+        out,
+        "    <properties><property name=\"files_checked\" value=\"{}\"/></properties>",
+        report.files_checked
+    );
+    if errors.is_empty() {
+        out.push_str("    <testcase classname=\"bog\" name=\"validate\"/>\n");
+    }
+    for d in errors {
+        let file = d.file.as_deref().unwrap_or("repo.bog");
+        let location = match d.span {
+            Some(r) => format!("{file}:{}:{}", r.start_line, r.start_column),
+            None => file.to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "    <testcase classname=\"{}\" name=\"{} {}\">",
+            xml_escape(file),
+            d.code,
+            xml_escape(&location)
+        );
+        let _ = writeln!(
+            out,
+            "      <failure type=\"{}\" message=\"{}\">{}: {}</failure>",
+            d.code,
+            xml_escape(&d.message),
+            xml_escape(&location),
+            xml_escape(&d.message)
+        );
+        out.push_str("    </testcase>\n");
+    }
+    let warnings: Vec<String> = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Warning)
+        .map(|d| match &d.file {
+            Some(file) => format!("warning: {file}: {}", d.message),
+            None => format!("warning: {}", d.message),
+        })
+        .collect();
+    if !warnings.is_empty() {
+        let _ = writeln!(
+            out,
+            "    <system-out>{}</system-out>",
+            xml_escape(&warnings.join("\n"))
+        );
+    }
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    out
+}
+
+/// Where in `text` (the contents of the error's file) the error points: the parse
+/// span, the inline annotation's line, or the sidecar annotation it names.
+fn locate(error: &ValidationError, text: &str) -> Option<Region> {
+    match error {
+        ValidationError::Parse {
+            span: Some(span), ..
+        } => Some(span_region(text, *span)),
+        ValidationError::Inline { error, .. } => {
+            let (InlineError::Parse { line, .. } | InlineError::Conflict { line, .. }) = error;
+            line_region(text, *line, *line)
+        }
+        ValidationError::MissingFunction { function, .. }
+        | ValidationError::AmbiguousFunction { function, .. }
+        | ValidationError::ContractDrift { function, .. }
+        | ValidationError::StubAnnotation { function, .. } => annotation_region(text, "fn", function),
+        ValidationError::MissingItem { kind, name, .. }
+        | ValidationError::ItemKindMismatch { kind, name, .. } => {
+            annotation_region(text, &kind.to_string(), name)
+        }
+        ValidationError::UndeclaredSubsystem { .. }
+        | ValidationError::OwnerMismatch { .. }
+        | ValidationError::Policy { .. } => annotation_region(text, "file", ""),
+        _ => None,
+    }
+}
+
+/// Where in `text` (the contents of the warning's file) the warning points: the
+/// `#[fn]` or other annotation it is about.
+fn locate_warning(warning: &ValidationWarning, text: &str) -> Option<Region> {
+    match warning {
+        ValidationWarning::StaleDep { function, .. }
+        | ValidationWarning::UndeclaredCall { function, .. } => {
+            annotation_region(text, "fn", function)
+        }
+        ValidationWarning::ComputedHealth { .. } => annotation_region(text, "health", ""),
+        ValidationWarning::IncludeOutsideRepo { .. } => annotation_region(text, "include", ""),
+        ValidationWarning::Policy { violation, .. } => match (&violation.function, violation.policy) {
+            (Some(function), _) => annotation_region(text, "fn", function),
+            (None, Policy::RequireContracts) => None,
+            (None, _) => annotation_region(text, "file", ""),
+        },
+        ValidationWarning::SourceNotFound { .. } => annotation_region(text, "file", ""),
+        _ => None,
+    }
+}
+
+fn span_region(text: &str, span: Span) -> Region {
+    let (start_line, start_column) = diagnostic::line_col(text, span.start);
+    let (end_line, end_column) = diagnostic::line_col(text, span.end.max(span.start));
+    Region {
+        start_line,
+        start_column,
+        end_line,
+        end_column,
+    }
+}
+
+/// Whole lines `first..=last`, ending after the last character of `last`.
+fn line_region(text: &str, first: usize, last: usize) -> Option<Region> {
+    let last_line = text.lines().nth(last.checked_sub(1)?)?;
+    Some(Region {
+        start_line: first,
+        start_column: 1,
+        end_line: last,
+        end_column: last_line.chars().count() + 1,
+    })
+}
+
+/// The lines of the first `#[name(arg)]` annotation, or of the first `#[name]`
+/// when `arg` is empty.
+fn annotation_region(text: &str, name: &str, arg: &str) -> Option<Region> {
+    let tree = cst::parse_cst(text).ok()?;
+    let (_, first, last) = tree.annotation_lines().into_iter().find(|(node, _, _)| {
+        node.annotation_name() == Some(name)
+            && (arg.is_empty() || node.annotation_arg() == Some(arg))
+    })?;
+    line_region(text, first, last)
+}
+
+/// `file` relative to `root`, without a leading `./`. Paths the validator already
+/// reports relative to the root are returned as they are.
+fn relative(root: &Path, file: &str) -> String {
+    let path = Path::new(file);
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    let rel = rel.strip_prefix(".").unwrap_or(rel);
+    rel.to_string_lossy().to_string()
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIDECAR: &str = "#[file(owner = \"a\", subsystem = \"s\", updated = \"2026-01-01\", status = green)]\n\
+#[fn(login) {\n  status = green\n}]\n";
+
+    fn report() -> ValidationReport {
+        ValidationReport {
+            errors: vec![
+                ValidationError::MissingFunction {
+                    file: "src/a.rs.bog".to_string(),
+                    function: "login".to_string(),
+                    renamed_to: None,
+                },
+                ValidationError::UnmatchedSubsystemGlob {
+                    subsystem: "core".to_string(),
+                    pattern: "lib/*.rs".to_string(),
+                },
+            ],
+            warnings: vec![
+                ValidationWarning::UndeclaredCall {
+                    file: "src/a.rs.bog".to_string(),
+                    function: "login".to_string(),
+                    call: "audit".to_string(),
+                },
+                ValidationWarning::NoRepoBog,
+            ],
+            files_checked: 1,
+        }
+    }
+
+    fn with_sidecar<T>(f: impl FnOnce(&Path) -> T) -> T {
+        let root = std::env::temp_dir().join(format!("bog-report-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.rs.bog"), SIDECAR).unwrap();
+        let out = f(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_diagnostics_are_located_and_relative() {
+        let diagnostics = with_sidecar(|root| {
+            let mut report = report();
+            report.errors[0] = ValidationError::MissingFunction {
+                file: root.join("src/a.rs.bog").display().to_string(),
+                function: "login".to_string(),
+                renamed_to: None,
+            };
+            diagnostics(&report, root)
+        });
+        assert_eq!(diagnostics.len(), 4);
+        assert_eq!(diagnostics[0].code, "BOG002");
+        assert_eq!(diagnostics[0].file.as_deref(), Some("src/a.rs.bog"));
+        assert_eq!(
+            diagnostics[0].message,
+            "function 'login' declared in .bog but not found in source"
+        );
+        let span = diagnostics[0].span.unwrap();
+        assert_eq!((span.start_line, span.end_line, span.end_column), (2, 4, 3));
+        assert_eq!(diagnostics[1].file, None);
+        assert_eq!(diagnostics[2].severity, Severity::Warning);
+        assert_eq!(diagnostics[2].code, "BOG902");
+        assert_eq!(diagnostics[2].file.as_deref(), Some("src/a.rs.bog"));
+        assert!(diagnostics[2].message.starts_with("fn 'login' calls"));
+        let span = diagnostics[2].span.unwrap();
+        assert_eq!((span.start_line, span.end_line), (2, 4));
+        assert_eq!(diagnostics[3].code, "BOG910");
+        assert_eq!(diagnostics[3].file, None);
+        assert_eq!(diagnostics[3].message, "No repo.bog found");
+    }
+
+    #[test]
+    fn test_render_sarif_and_junit() {
+        let (sarif, junit) = with_sidecar(|root| {
+            let report = report();
+            (
+                render(&report, root, ReportFormat::Sarif).unwrap(),
+                render(&report, root, ReportFormat::Junit).unwrap(),
+            )
+        });
+        let sarif: serde_json::Value = serde_json::from_str(&sarif).unwrap();
+        let results = &sarif["runs"][0]["results"];
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(results[0]["ruleId"], "BOG002");
+        assert_eq!(results[0]["level"], "error");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/a.rs.bog");
+        assert_eq!(location["region"]["startLine"], 2);
+        assert!(results[1].get("locations").is_none());
+        assert_eq!(results[2]["level"], "warning");
+
+        assert!(junit.contains("<testsuite name=\"bog validate\" tests=\"2\" failures=\"2\">"));
+        assert!(junit.contains("<failure type=\"BOG002\" message=\"function 'login'"));
+        assert!(junit.contains("<system-out>warning: src/a.rs.bog: fn 'login'"));
+        assert!("yaml".parse::<ReportFormat>().is_err());
+    }
+}
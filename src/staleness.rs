@@ -0,0 +1,216 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::{DateTime, NaiveDate};
+
+use crate::ast::{Annotation, BogFile, FnPath, Status};
+use crate::cst;
+use crate::lang;
+use crate::treesitter;
+
+/// Days a sidecar may lag behind its source before staleness turns red, when
+/// repo.bog sets no `stale_after_days` threshold.
+pub const DEFAULT_STALE_AFTER_DAYS: i64 = 30;
+
+/// The date each line of a file last changed, from `git blame`. Lines not yet
+/// committed count as changed today.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blame {
+    dates: Vec<NaiveDate>,
+}
+
+impl Blame {
+    /// Blame a file with the `git` CLI. `None` when the file is not tracked, is not
+    /// in a git repository, or git is not installed.
+    pub fn of(path: &Path) -> Option<Blame> {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let output = Command::new("git")
+            .args(["blame", "--line-porcelain", "--"])
+            .arg(path.file_name()?)
+            .current_dir(dir)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(Blame {
+            dates: parse_blame(&String::from_utf8_lossy(&output.stdout)),
+        })
+    }
+
+    /// When any line of the file last changed.
+    pub fn last_change(&self) -> Option<NaiveDate> {
+        self.dates.iter().max().copied()
+    }
+
+    /// When any line in the 1-based, inclusive range last changed.
+    pub fn last_change_in(&self, first: usize, last: usize) -> Option<NaiveDate> {
+        let first = first.max(1) - 1;
+        let last = last.min(self.dates.len());
+        self.dates.get(first..last)?.iter().max().copied()
+    }
+}
+
+/// A function whose code changed after its `#[fn]` annotation last did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FnStaleness {
+    pub name: String,
+    pub code_changed: NaiveDate,
+    pub annotation_changed: NaiveDate,
+}
+
+/// How far a sidecar has fallen behind its source, according to git.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileStaleness {
+    pub bog_path: PathBuf,
+    /// Last change to the source file
+    pub source_changed: NaiveDate,
+    /// `#[file(updated)]`, or the last change to the sidecar when that is missing
+    pub updated: NaiveDate,
+    pub stale_fns: Vec<FnStaleness>,
+}
+
+impl FileStaleness {
+    /// Days the source changed after the sidecar was last updated; 0 if it did not.
+    pub fn lag_days(&self) -> i64 {
+        (self.source_changed - self.updated).num_days().max(0)
+    }
+
+    /// Green when nothing is behind, red when the source is more than
+    /// `stale_after_days` ahead of the sidecar, yellow otherwise.
+    pub fn status(&self, stale_after_days: i64) -> Status {
+        if self.lag_days() > stale_after_days {
+            Status::Red
+        } else if self.lag_days() > 0 || !self.stale_fns.is_empty() {
+            Status::Yellow
+        } else {
+            Status::Green
+        }
+    }
+}
+
+/// Compare a sidecar with the git history of its source: the source file against
+/// `#[file(updated)]`, and the lines of each annotated function against the lines
+/// of its `#[fn]` annotation. `None` when either file has no history.
+pub fn file_staleness(
+    bog_path: &Path,
+    bog_text: &str,
+    bog: &BogFile,
+    source_path: &Path,
+) -> Option<FileStaleness> {
+    let source_blame = Blame::of(source_path)?;
+    let bog_blame = Blame::of(bog_path)?;
+    let source_changed = source_blame.last_change()?;
+
+    let updated = bog
+        .annotations
+        .iter()
+        .find_map(|a| match a {
+            Annotation::File(f) => NaiveDate::parse_from_str(&f.updated, "%Y-%m-%d").ok(),
+            _ => None,
+        })
+        .or_else(|| bog_blame.last_change())?;
+
+    let mut stale_fns = Vec::new();
+    let symbols = lang::backend_for(source_path).and_then(|backend| {
+        let source = std::fs::read_to_string(source_path).ok()?;
+        backend.extract_symbols(&source).ok()
+    });
+    if let (Some(symbols), Ok(tree)) = (symbols, cst::parse_cst(bog_text)) {
+        for (node, first, last) in tree.annotation_lines() {
+            if node.annotation_name() != Some("fn") {
+                continue;
+            }
+            let Some(name) = node.annotation_arg() else {
+                continue;
+            };
+            let Ok(path) = FnPath::parse(name) else {
+                continue;
+            };
+            let [symbol] = treesitter::resolve_fn(&symbols, &path)[..] else {
+                continue;
+            };
+            let (Some(code_changed), Some(annotation_changed)) = (
+                source_blame.last_change_in(symbol.start_line, symbol.end_line),
+                bog_blame.last_change_in(first, last),
+            ) else {
+                continue;
+            };
+            if code_changed > annotation_changed {
+                stale_fns.push(FnStaleness {
+                    name: name.to_string(),
+                    code_changed,
+                    annotation_changed,
+                });
+            }
+        }
+    }
+
+    Some(FileStaleness {
+        bog_path: bog_path.to_path_buf(),
+        source_changed,
+        updated,
+        stale_fns,
+    })
+}
+
+/// One date per line of `git blame --line-porcelain` output, from each line's
+/// `committer-time` header.
+fn parse_blame(output: &str) -> Vec<NaiveDate> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("committer-time "))
+        .filter_map(|ts| DateTime::from_timestamp(ts.trim().parse().ok()?, 0))
+        .map(|t| t.date_naive())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_blame_and_ranges() {
+        // 2026-01-01, 2026-03-01 and 2026-02-01 at midnight UTC
+        let output = "\
+abc 1 1 1\nauthor a\ncommitter-time 1767225600\ncommitter-tz +0000\n\tfn a() {\n\
+def 2 2 1\nauthor a\ncommitter-time 1772323200\ncommitter-tz +0000\n\t    b();\n\
+abc 3 3 1\nauthor a\ncommitter-time 1769904000\ncommitter-tz +0000\n\t}\n";
+        let blame = Blame {
+            dates: parse_blame(output),
+        };
+        assert_eq!(blame.dates, [day("2026-01-01"), day("2026-03-01"), day("2026-02-01")]);
+        assert_eq!(blame.last_change(), Some(day("2026-03-01")));
+        assert_eq!(blame.last_change_in(3, 3), Some(day("2026-02-01")));
+        assert_eq!(blame.last_change_in(1, 9), Some(day("2026-03-01")));
+        assert_eq!(blame.last_change_in(5, 9), None);
+    }
+
+    #[test]
+    fn test_status_from_lag_and_stale_fns() {
+        let mut s = FileStaleness {
+            bog_path: PathBuf::from("a.rs.bog"),
+            source_changed: day("2026-02-01"),
+            updated: day("2026-02-10"),
+            stale_fns: Vec::new(),
+        };
+        assert_eq!(s.lag_days(), 0);
+        assert_eq!(s.status(30), Status::Green);
+        s.stale_fns.push(FnStaleness {
+            name: "a".to_string(),
+            code_changed: day("2026-02-01"),
+            annotation_changed: day("2026-01-01"),
+        });
+        assert_eq!(s.status(30), Status::Yellow);
+        s.source_changed = day("2026-04-01");
+        assert_eq!(s.lag_days(), 50);
+        assert_eq!(s.status(30), Status::Red);
+    }
+}
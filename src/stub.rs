@@ -1,114 +1,98 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-use colored::Colorize;
+use rayon::prelude::*;
 
 use crate::ast::*;
-use crate::parser;
+use crate::cache::Cache;
+use crate::cst;
+use crate::include;
+use crate::inline;
+use crate::lang;
 use crate::treesitter::{self, Symbol};
+use crate::walk::Walker;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct StubReport {
     pub stubs_generated: usize,
     pub files_modified: usize,
     pub files_created: usize,
+    /// Sidecars left untouched, with the reason
+    pub errors: Vec<(PathBuf, String)>,
 }
 
-/// Find .rs files that have functions not covered by .bog annotations.
+/// Find source files that have functions not covered by .bog annotations.
 /// Returns (source_path, bog_path, missing_symbols) tuples.
 pub fn find_missing_annotations(root: &Path) -> Vec<(PathBuf, PathBuf, Vec<Symbol>)> {
-    let mut results = Vec::new();
-
-    let pattern = root.join("**/*.rs");
-    let Ok(paths) = glob::glob(&pattern.to_string_lossy()) else {
-        return results;
-    };
-
-    for source_path in paths.flatten() {
-        // Skip build artifacts and git internals
-        let rel = source_path.strip_prefix(root).unwrap_or(&source_path);
-        if rel.components().any(|c| {
-            matches!(c.as_os_str().to_str(), Some("target" | ".git"))
-        }) {
-            continue;
-        }
-
-        let bog_path = PathBuf::from(format!("{}.bog", source_path.display()));
-
-        // Extract symbols from source
-        let source = match std::fs::read_to_string(&source_path) {
-            Ok(s) => s,
-            Err(_) => continue,
-        };
-        let symbols = match treesitter::extract_symbols(&source) {
-            Ok(s) => s,
-            Err(_) => continue,
-        };
-
-        if symbols.is_empty() {
-            continue;
-        }
-
-        // Find which functions are already annotated
-        let annotated: HashSet<String> = if bog_path.exists() {
-            let content = match std::fs::read_to_string(&bog_path) {
-                Ok(s) => s,
-                Err(_) => String::new(),
-            };
-            match parser::parse_bog(&content) {
-                Ok(bog) => bog
-                    .annotations
-                    .iter()
-                    .filter_map(|a| {
-                        if let Annotation::Fn(f) = a {
-                            Some(f.name.clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .collect(),
-                Err(_) => HashSet::new(),
-            }
-        } else {
-            HashSet::new()
-        };
-
-        let missing: Vec<Symbol> = symbols
-            .into_iter()
-            .filter(|s| {
-                // Skip already-annotated functions
-                if annotated.contains(&s.name) {
-                    return false;
-                }
-                // Skip impl methods (trait impls like fmt, eq, etc.)
-                if s.kind == treesitter::SymbolKind::Method {
-                    return false;
-                }
-                true
-            })
-            .collect();
+    let cache = Cache::open(root);
+    // Files are scanned in parallel; collecting keeps them in walk order
+    lang::source_files(&Walker::new(root))
+        .into_par_iter()
+        .filter_map(|source_path| {
+            let (bog_path, missing) = missing_in_file(&source_path, &cache)?;
+            Some((source_path, bog_path, missing))
+        })
+        .collect()
+}
 
-        if !missing.is_empty() {
-            results.push((source_path, bog_path, missing));
-        }
+/// The sidecar path of one source file and its unannotated functions, or
+/// `None` if it has none or cannot be parsed.
+fn missing_in_file(source_path: &Path, cache: &Cache) -> Option<(PathBuf, Vec<Symbol>)> {
+    let backend = lang::backend_for(source_path)?;
+    let bog_path = backend.sidecar_path(source_path);
+
+    // Extract symbols from source
+    let source = std::fs::read_to_string(source_path).ok()?;
+    let symbols = cache.extract_symbols(&source, backend).ok()?;
+    if symbols.is_empty() {
+        return None;
     }
 
-    results
+    // Find which functions are already annotated, in the sidecar or inline
+    let mut bog = if bog_path.exists() {
+        let content = std::fs::read_to_string(&bog_path).unwrap_or_default();
+        cache.parse_bog(&content).unwrap_or_default()
+    } else {
+        BogFile::default()
+    };
+    inline::merge(&mut bog, &source, backend);
+    let paths: Vec<FnPath> = bog
+        .annotations
+        .iter()
+        .filter_map(|a| match a {
+            Annotation::Fn(f) => FnPath::parse(&f.name).ok(),
+            _ => None,
+        })
+        .collect();
+    let annotated: Vec<&Symbol> = paths
+        .iter()
+        .flat_map(|path| treesitter::resolve_fn(&symbols, path))
+        .collect();
+
+    // Free functions, inherent methods and trait default methods get stubs.
+    // Trait impl methods like fmt and eq do not, and types are annotated by hand.
+    let missing: Vec<Symbol> = symbols
+        .iter()
+        .filter(|s| s.kind.is_fn() && !s.is_trait_impl())
+        .filter(|s| !annotated.iter().any(|a| std::ptr::eq(*a, *s)))
+        .cloned()
+        .collect();
+
+    (!missing.is_empty()).then_some((bog_path, missing))
 }
 
 /// Generate a stub annotation string for a symbol.
 pub fn generate_stub(symbol: &Symbol) -> String {
-    let deps_str = if symbol.calls.is_empty() {
-        String::new()
-    } else {
-        let deps: Vec<String> = symbol.calls.iter().map(|c| c.to_string()).collect();
-        format!(",\n  deps = [{}]", deps.join(", "))
-    };
-
-    format!(
-        "#[fn({}) {{\n  status = yellow,\n  stub = true{},\n  description = \"TODO\"\n}}]",
-        symbol.name, deps_str
-    )
+    FnAnnotation {
+        name: symbol.qualified_name(),
+        status: Status::Yellow,
+        stub: true,
+        deps: symbol.calls.clone(),
+        refs: Vec::new(),
+        contract: None,
+        description: Some("TODO".to_string()),
+    }
+    .to_string()
 }
 
 /// Generate a minimal file header for a new .bog sidecar.
@@ -122,32 +106,28 @@ pub fn generate_file_header(
         None => ("unknown-agent".to_string(), "unknown".to_string()),
     };
 
-    let today = chrono::Local::now().format("%Y-%m-%d");
-
-    format!(
-        r#"#[file(
-  owner = "{owner}",
-  subsystem = "{subsystem}",
-  updated = "{today}",
-  status = yellow
-)]
-
-#[description {{
-  TODO
-}}]
-
-#[health(
-  staleness = yellow
-)]
-"#
-    )
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+    BogFile {
+        annotations: vec![
+            Annotation::File(FileAnnotation {
+                owner,
+                subsystem,
+                updated: today,
+                status: Status::Yellow,
+            }),
+            Annotation::Description("TODO".to_string()),
+            Annotation::Health(HealthAnnotation {
+                dimensions: HashMap::from([("staleness".to_string(), Status::Yellow)]),
+            }),
+        ],
+    }
+    .to_string()
 }
 
 /// Match a source file path against repo.bog subsystem declarations to find owner/subsystem.
 pub fn find_subsystem_for_file(source_path: &Path, root: &Path) -> Option<(String, String)> {
-    let repo_bog_path = root.join("repo.bog");
-    let content = std::fs::read_to_string(repo_bog_path).ok()?;
-    let bog = parser::parse_bog(&content).ok()?;
+    let bog = include::load_repo_bog(root).ok()?.bog;
 
     let rel_path = source_path
         .strip_prefix(root)
@@ -157,10 +137,10 @@ pub fn find_subsystem_for_file(source_path: &Path, root: &Path) -> Option<(Strin
     for ann in &bog.annotations {
         if let Annotation::Subsystem(s) = ann {
             for pattern in &s.files {
-                if let Ok(glob) = glob::Pattern::new(pattern) {
-                    if glob.matches(&rel_path) {
-                        return Some((s.owner.clone(), s.name.clone()));
-                    }
+                if let Ok(glob) = glob::Pattern::new(pattern)
+                    && glob.matches(&rel_path)
+                {
+                    return Some((s.owner.clone(), s.name.clone()));
                 }
             }
         }
@@ -172,13 +152,9 @@ pub fn find_subsystem_for_file(source_path: &Path, root: &Path) -> Option<(Strin
 /// Generate stubs for all unannotated functions and write them to .bog files.
 pub fn apply_stubs(root: &Path) -> StubReport {
     let missing = find_missing_annotations(root);
-    let mut report = StubReport {
-        stubs_generated: 0,
-        files_modified: 0,
-        files_created: 0,
-    };
+    let mut report = StubReport::default();
 
-    for (source_path, bog_path, symbols) in &missing {
+    'files: for (source_path, bog_path, symbols) in &missing {
         let mut content = if bog_path.exists() {
             std::fs::read_to_string(bog_path).unwrap_or_default()
         } else {
@@ -192,28 +168,38 @@ pub fn apply_stubs(root: &Path) -> StubReport {
             content = generate_file_header(source_path, root);
         }
 
-        // Ensure trailing newline before appending stubs
-        if !content.ends_with('\n') {
-            content.push('\n');
-        }
-
-        // Append stubs
+        // Append stubs through the syntax tree so existing comments and layout survive
+        let mut tree = match cst::parse_cst(&content) {
+            Ok(tree) => tree,
+            Err(e) => {
+                report.errors.push((bog_path.clone(), e.to_string()));
+                continue;
+            }
+        };
+        let mut generated = 0;
         for sym in symbols {
-            content.push('\n');
-            content.push_str(&generate_stub(sym));
-            content.push('\n');
-            report.stubs_generated += 1;
+            // The writer's output must always reparse; a failure is a bug, so the
+            // sidecar is left as it was rather than written with stubs missing
+            match cst::parse_annotation_cst(&generate_stub(sym)) {
+                Ok(node) => tree.append_annotation(node, None),
+                Err(e) => {
+                    report.errors.push((
+                        bog_path.clone(),
+                        format!("generated stub for `{}` does not parse (bug): {e}", sym.name),
+                    ));
+                    continue 'files;
+                }
+            }
+            generated += 1;
         }
+        let content = tree.to_string();
 
         if let Err(e) = std::fs::write(bog_path, &content) {
-            eprintln!(
-                "  {} failed to write {}: {e}",
-                "error:".red(),
-                bog_path.display()
-            );
+            report.errors.push((bog_path.clone(), e.to_string()));
             continue;
         }
 
+        report.stubs_generated += generated;
         if created {
             report.files_created += 1;
         } else {
@@ -226,40 +212,26 @@ pub fn apply_stubs(root: &Path) -> StubReport {
 
 /// Find all stub annotations across the project. Returns (file_path, fn_name) pairs.
 pub fn list_stubs(root: &Path) -> Vec<(String, String)> {
-    let mut stubs = Vec::new();
-
-    let pattern = root.join("**/*.bog");
-    let Ok(paths) = glob::glob(&pattern.to_string_lossy()) else {
-        return stubs;
-    };
-
-    for bog_path in paths.flatten() {
-        let rel = bog_path.strip_prefix(root).unwrap_or(&bog_path);
-        if rel.components().any(|c| {
-            matches!(c.as_os_str().to_str(), Some("target" | ".git"))
-        }) {
-            continue;
-        }
-
-        let content = match std::fs::read_to_string(&bog_path) {
-            Ok(s) => s,
-            Err(_) => continue,
-        };
-
-        let bog = match parser::parse_bog(&content) {
-            Ok(b) => b,
-            Err(_) => continue,
-        };
-
-        let file_str = rel.to_string_lossy().to_string();
-        for ann in &bog.annotations {
-            if let Annotation::Fn(f) = ann {
-                if f.stub {
-                    stubs.push((file_str.clone(), f.name.clone()));
-                }
-            }
-        }
-    }
-
-    stubs
+    let cache = Cache::open(root);
+    Walker::new(root)
+        .files_with_suffix(".bog")
+        .into_par_iter()
+        .flat_map_iter(|bog_path| {
+            let file_str = bog_path
+                .strip_prefix(root)
+                .unwrap_or(&bog_path)
+                .to_string_lossy()
+                .to_string();
+            let bog = std::fs::read_to_string(&bog_path)
+                .ok()
+                .and_then(|content| cache.parse_bog(&content).ok())
+                .unwrap_or_default();
+            bog.annotations
+                .into_iter()
+                .filter_map(move |ann| match ann {
+                    Annotation::Fn(f) if f.stub => Some((file_str.clone(), f.name)),
+                    _ => None,
+                })
+        })
+        .collect()
 }
@@ -1,8 +1,9 @@
-use std::collections::BTreeSet;
+use serde::{Deserialize, Serialize};
 
-use tree_sitter::Parser;
+use crate::ast::{FnPath, ItemKind};
+use crate::lang::{self, LanguageBackend};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
     pub name: String,
     pub kind: SymbolKind,
@@ -11,160 +12,222 @@ pub struct Symbol {
     pub start_line: usize,
     pub end_line: usize,
     pub calls: Vec<String>,
+    /// Self type of the enclosing impl block, as written (e.g. `Wrapper<T>`)
+    pub impl_type: Option<String>,
+    /// Trait of the enclosing `impl Trait for Type` block, as written, or the trait
+    /// that declares a default method (which has no `impl_type`)
+    pub impl_trait: Option<String>,
+    /// `///` doc comment lines above the item, as (1-based line, text after `///`)
+    pub doc: Vec<(usize, String)>,
+    /// Visibility modifier as written (`pub`, `pub(crate)`), `None` for private.
+    /// Languages without modifiers report exported names as `pub`. Trait default
+    /// methods take the visibility of their trait.
+    pub visibility: Option<String>,
+    pub is_async: bool,
+    pub is_unsafe: bool,
+    /// A `const fn`; const items have `SymbolKind::Const` instead
+    pub is_const: bool,
+    /// Generic parameters as written (e.g. `<T: Clone, const N: usize>`)
+    pub generics: Option<String>,
+    /// `where` clause as written, including the keyword
+    pub where_clause: Option<String>,
+    /// Inline `mod` blocks around the item within its file, outermost first
+    pub module_path: Vec<String>,
+    /// Whether every enclosing `mod` is plain `pub`. Items declared inside a
+    /// function body are never reachable, so they count as in a private module.
+    pub module_public: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum SymbolKind {
-    Function,
-    Method,
-}
+impl Symbol {
+    /// Plain `pub` in plain `pub` modules: part of the public API. `pub(crate)`
+    /// and narrower, or `pub` inside a private module, are not.
+    pub fn is_public(&self) -> bool {
+        self.visibility.as_deref() == Some("pub") && self.module_public
+    }
 
-#[derive(Debug, thiserror::Error)]
-pub enum TreeSitterError {
-    #[error("Failed to initialize parser: {0}")]
-    Init(String),
+    /// A method of an `impl Trait for Type` block. These implement an API declared
+    /// elsewhere, so they are not stubbed and need no contract of their own.
+    pub fn is_trait_impl(&self) -> bool {
+        self.impl_type.is_some() && self.impl_trait.is_some()
+    }
 
-    #[error("Failed to parse source file")]
-    ParseFailed,
-}
+    /// A default method declared in a trait body.
+    pub fn is_trait_default(&self) -> bool {
+        self.impl_type.is_none() && self.impl_trait.is_some()
+    }
 
-pub fn extract_symbols(source: &str) -> Result<Vec<Symbol>, TreeSitterError> {
-    let mut parser = Parser::new();
-    let language = tree_sitter_rust::LANGUAGE;
-    parser
-        .set_language(&language.into())
-        .map_err(|e| TreeSitterError::Init(e.to_string()))?;
-
-    let tree = parser
-        .parse(source, None)
-        .ok_or(TreeSitterError::ParseFailed)?;
-
-    let mut symbols = Vec::new();
-    collect_symbols(tree.root_node(), source.as_bytes(), &mut symbols);
-    Ok(symbols)
-}
-
-fn collect_symbols(node: tree_sitter::Node, source: &[u8], symbols: &mut Vec<Symbol>) {
-    match node.kind() {
-        "function_item" => {
-            if let Some(sym) = extract_function(node, source) {
-                symbols.push(sym);
-            }
+    /// The unambiguous way to refer to this symbol: `name`, `Type::name`,
+    /// `<Type as Trait>::name` or `Trait::name` for a default method, with the
+    /// path of its inline modules in front (`net::tcp::connect`, `net::Auth::new`).
+    pub fn qualified_name(&self) -> String {
+        let in_module = |s: &str| {
+            self.module_path
+                .iter()
+                .map(String::as_str)
+                .chain([s])
+                .collect::<Vec<_>>()
+                .join("::")
+        };
+        let (self_type, trait_name) = match (&self.impl_type, &self.impl_trait) {
+            (Some(ty), tr) => (Some(in_module(ty)), tr.clone()),
+            (None, Some(tr)) => (Some(in_module(tr)), None),
+            (None, None) if self.module_path.is_empty() => (None, None),
+            (None, None) => (Some(self.module_path.join("::")), None),
+        };
+        FnPath {
+            self_type,
+            trait_name,
+            name: self.name.clone(),
         }
-        "impl_item" => {
-            for i in 0..node.child_count() {
-                let child = node.child(i).unwrap();
-                if child.kind() == "declaration_list" {
-                    for j in 0..child.child_count() {
-                        let item = child.child(j).unwrap();
-                        if item.kind() == "function_item" {
-                            if let Some(mut sym) = extract_function(item, source) {
-                                sym.kind = SymbolKind::Method;
-                                symbols.push(sym);
-                            }
-                        }
-                    }
-                }
-            }
+        .to_string()
+    }
+}
+
+/// The function symbols a reference can mean. A bare name matches every function
+/// or method with that name; `Type::name` prefers an inherent method over trait
+/// methods, as Rust does; `<Type as Trait>::name` matches that trait impl, or the
+/// trait's default method when the type does not override it; `Trait::name`
+/// matches a default method. Types and traits are compared by their last path
+/// segment without generics; a module path in front of a type (`net::Auth::new`)
+/// prefers methods declared in that module. `module::name` also matches a free
+/// function in an inline `mod` of that name.
+pub fn resolve_fn<'a>(symbols: &'a [Symbol], path: &FnPath) -> Vec<&'a Symbol> {
+    let mut candidates: Vec<&Symbol> = symbols
+        .iter()
+        .filter(|s| s.kind.is_fn() && s.name == path.name)
+        .filter(|s| match &path.self_type {
+            None => true,
+            Some(ty) => match (s.impl_type.as_deref(), s.impl_trait.as_deref()) {
+                (Some(t), _) => same_type(t, ty),
+                // A default method is reached through any type of the trait
+                (None, Some(tr)) => path.trait_name.is_some() || same_type(tr, ty),
+                (None, None) => path.trait_name.is_none() && in_module(s, ty),
+            },
+        })
+        .filter(|s| match &path.trait_name {
+            None => true,
+            Some(tr) => s.impl_trait.as_deref().is_some_and(|t| same_type(t, tr)),
+        })
+        .collect();
+
+    // An impl's own method hides the trait default it overrides
+    if path.trait_name.is_some() && candidates.iter().any(|s| s.impl_type.is_some()) {
+        candidates.retain(|s| s.impl_type.is_some());
+    }
+    if let Some(module) = path.self_type.as_deref().and_then(type_module) {
+        let in_path: Vec<&Symbol> = candidates
+            .iter()
+            .copied()
+            .filter(|s| s.impl_type.is_some() && in_module(s, module))
+            .collect();
+        if !in_path.is_empty() {
+            candidates = in_path;
         }
-        _ => {
-            for i in 0..node.child_count() {
-                collect_symbols(node.child(i).unwrap(), source, symbols);
-            }
+    }
+    if path.self_type.is_some() && path.trait_name.is_none() {
+        let inherent: Vec<&Symbol> = candidates
+            .iter()
+            .copied()
+            .filter(|s| s.impl_trait.is_none())
+            .collect();
+        if !inherent.is_empty() {
+            return inherent;
         }
     }
+    candidates
 }
 
-fn extract_function(node: tree_sitter::Node, source: &[u8]) -> Option<Symbol> {
-    let name_node = node.child_by_field_name("name")?;
-    let name = name_node.utf8_text(source).ok()?.to_string();
-
-    let mut params = Vec::new();
-    if let Some(param_list) = node.child_by_field_name("parameters") {
-        for i in 0..param_list.child_count() {
-            let param = param_list.child(i).unwrap();
-            if param.kind() == "parameter" {
-                let param_name = param
-                    .child_by_field_name("pattern")
-                    .map(|n| n.utf8_text(source).unwrap_or("_").to_string())
-                    .unwrap_or_else(|| "_".to_string());
-                let param_type = param
-                    .child_by_field_name("type")
-                    .map(|n| n.utf8_text(source).unwrap_or("?").to_string())
-                    .unwrap_or_else(|| "?".to_string());
-                params.push((param_name, param_type));
-            }
-        }
+/// The module part of a type path: `net` in `net::Auth` or `net::Wrapper<T>`.
+fn type_module(ty: &str) -> Option<&str> {
+    let base = ty.split('<').next().unwrap_or(ty);
+    base.rsplit_once("::").map(|(module, _)| module)
+}
+
+/// Whether `module` (`tcp` or `net::tcp`) names the innermost modules around a symbol.
+fn in_module(symbol: &Symbol, module: &str) -> bool {
+    let segments: Vec<&str> = module.split("::").collect();
+    symbol.module_path.len() >= segments.len()
+        && symbol
+            .module_path
+            .iter()
+            .rev()
+            .zip(segments.iter().rev())
+            .all(|(a, b)| a == b)
+}
+
+fn same_type(a: &str, b: &str) -> bool {
+    base_name(a) == base_name(b)
+}
+
+/// `crate::fmt::Display<T>` to `Display`, `&'a mut Foo` to `Foo`
+fn base_name(ty: &str) -> &str {
+    let ty = ty.split('<').next().unwrap_or(ty).trim();
+    ty.rsplit([':', ' ', '&']).next().unwrap_or(ty)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Struct,
+    Enum,
+    Union,
+    TypeAlias,
+    Trait,
+    Const,
+    Static,
+    Class,
+    Interface,
+}
+
+impl SymbolKind {
+    pub fn is_fn(self) -> bool {
+        matches!(self, SymbolKind::Function | SymbolKind::Method)
     }
 
-    let return_type = node
-        .child_by_field_name("return_type")
-        .map(|n| n.utf8_text(source).unwrap_or("").to_string());
-
-    // Extract function calls from the body
-    let mut calls = BTreeSet::new();
-    if let Some(body) = node.child_by_field_name("body") {
-        extract_calls(body, source, &mut calls);
-    }
-    // Remove self-references
-    calls.remove(&name);
-
-    Some(Symbol {
-        name,
-        kind: SymbolKind::Function,
-        params,
-        return_type,
-        start_line: name_node.start_position().row + 1,
-        end_line: node.end_position().row + 1,
-        calls: calls.into_iter().collect(),
-    })
-}
-
-fn extract_calls(node: tree_sitter::Node, source: &[u8], calls: &mut BTreeSet<String>) {
-    if node.kind() == "call_expression" {
-        if let Some(func) = node.child_by_field_name("function") {
-            match func.kind() {
-                "identifier" => {
-                    if let Ok(name) = func.utf8_text(source) {
-                        // Skip common non-function identifiers
-                        if !is_builtin(name) {
-                            calls.insert(name.to_string());
-                        }
-                    }
-                }
-                "scoped_identifier" => {
-                    if let Ok(path) = func.utf8_text(source) {
-                        // Skip Self::, self::, and stdlib type constructors
-                        let root_segment = path.split("::").next().unwrap_or("");
-                        if !path.starts_with("Self::")
-                            && !path.starts_with("self::")
-                            && !is_builtin(root_segment)
-                        {
-                            calls.insert(path.to_string());
-                        }
-                    }
-                }
-                // Skip field_expression (method calls on objects like obj.method())
-                _ => {}
-            }
+    /// The `#[type]`/`#[trait]`/`#[const]` annotation that describes this symbol
+    pub fn item_kind(self) -> Option<ItemKind> {
+        match self {
+            SymbolKind::Function | SymbolKind::Method => None,
+            SymbolKind::Struct
+            | SymbolKind::Enum
+            | SymbolKind::Union
+            | SymbolKind::TypeAlias
+            | SymbolKind::Class => Some(ItemKind::Type),
+            SymbolKind::Trait | SymbolKind::Interface => Some(ItemKind::Trait),
+            SymbolKind::Const | SymbolKind::Static => Some(ItemKind::Const),
         }
     }
 
-    for i in 0..node.child_count() {
-        extract_calls(node.child(i).unwrap(), source, calls);
+    /// How the symbol is written in source, for messages
+    pub fn label(self) -> &'static str {
+        match self {
+            SymbolKind::Function | SymbolKind::Method => "fn",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Union => "union",
+            SymbolKind::TypeAlias => "type alias",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Const => "const",
+            SymbolKind::Static => "static",
+            SymbolKind::Class => "class",
+            SymbolKind::Interface => "interface",
+        }
     }
 }
 
-fn is_builtin(name: &str) -> bool {
-    matches!(
-        name,
-        "Some" | "None" | "Ok" | "Err" | "Box" | "Vec" | "String" | "format" | "println"
-            | "eprintln" | "print" | "eprint" | "write" | "writeln" | "panic" | "todo"
-            | "unimplemented" | "unreachable" | "assert" | "assert_eq" | "assert_ne"
-            | "debug_assert" | "debug_assert_eq" | "debug_assert_ne" | "cfg" | "include"
-            | "include_str" | "include_bytes" | "env" | "option_env" | "concat" | "line"
-            | "column" | "file" | "stringify" | "module_path"
-    )
+#[derive(Debug, thiserror::Error)]
+pub enum TreeSitterError {
+    #[error("Failed to initialize parser: {0}")]
+    Init(String),
+
+    #[error("Failed to parse source file")]
+    ParseFailed,
+}
+
+/// Symbols of a Rust source file. Use `lang::backend_for` for other languages.
+pub fn extract_symbols(source: &str) -> Result<Vec<Symbol>, TreeSitterError> {
+    lang::rust::RUST.extract_symbols(source)
 }
 
 #[cfg(test)]
@@ -204,7 +267,11 @@ impl Auth {
     }
 }
 "#;
-        let symbols = extract_symbols(source).unwrap();
+        let symbols: Vec<_> = extract_symbols(source)
+            .unwrap()
+            .into_iter()
+            .filter(|s| s.kind.is_fn())
+            .collect();
         assert_eq!(symbols.len(), 2);
         assert_eq!(symbols[0].name, "new");
         assert_eq!(symbols[0].kind, SymbolKind::Method);
@@ -212,6 +279,239 @@ impl Auth {
         assert_eq!(symbols[1].kind, SymbolKind::Method);
     }
 
+    #[test]
+    fn test_extract_types_traits_and_consts() {
+        let source = r#"
+pub struct Token(String);
+
+enum Role { Admin, User }
+
+type Result<T> = std::result::Result<T, AuthError>;
+
+pub trait Verifier {
+    fn verify(&self) -> bool;
+}
+
+const MAX_USERS: usize = 10;
+static GREETING: &str = "hi";
+
+mod inner {
+    union Bits { a: u32, b: f32 }
+}
+"#;
+        let symbols = extract_symbols(source).unwrap();
+        let kinds: Vec<(&str, SymbolKind)> =
+            symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+        assert_eq!(
+            kinds,
+            [
+                ("Token", SymbolKind::Struct),
+                ("Role", SymbolKind::Enum),
+                ("Result", SymbolKind::TypeAlias),
+                ("Verifier", SymbolKind::Trait),
+                ("MAX_USERS", SymbolKind::Const),
+                ("GREETING", SymbolKind::Static),
+                ("Bits", SymbolKind::Union),
+            ]
+        );
+        assert_eq!(symbols[3].start_line, 8);
+        assert_eq!(symbols[3].end_line, 10);
+    }
+
+    #[test]
+    fn test_methods_record_impl_and_resolve() {
+        let source = r#"
+fn new() {}
+
+struct Auth;
+
+impl Auth {
+    fn new() -> Self { Auth }
+    fn default() -> Self { Auth }
+}
+
+impl Default for Auth {
+    fn default() -> Self { Auth }
+}
+
+impl<T> fmt::Display for Wrapper<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { Ok(()) }
+}
+"#;
+        let symbols = extract_symbols(source).unwrap();
+        let fmt = symbols.iter().find(|s| s.name == "fmt").unwrap();
+        assert_eq!(fmt.impl_type.as_deref(), Some("Wrapper<T>"));
+        assert_eq!(fmt.impl_trait.as_deref(), Some("fmt::Display"));
+        assert_eq!(fmt.qualified_name(), "<Wrapper<T> as fmt::Display>::fmt");
+
+        let resolve = |r: &str| -> Vec<String> {
+            resolve_fn(&symbols, &FnPath::parse(r).unwrap())
+                .iter()
+                .map(|s| s.qualified_name())
+                .collect()
+        };
+        assert_eq!(resolve("new"), ["new", "Auth::new"]);
+        assert_eq!(resolve("Auth::new"), ["Auth::new"]);
+        // Type::name prefers the inherent method, like Rust
+        assert_eq!(resolve("Auth::default"), ["Auth::default"]);
+        assert_eq!(resolve("<Auth as Default>::default"), ["<Auth as Default>::default"]);
+        assert_eq!(resolve("<Wrapper as Display>::fmt").len(), 1);
+        assert!(resolve("<Auth as Clone>::default").is_empty());
+    }
+
+    #[test]
+    fn test_doc_comments_are_recorded() {
+        let source = r#"
+// not a doc
+/// Logs in.
+/// bog: status = green
+#[inline]
+fn login() {}
+
+//// not a doc either
+fn logout() {}
+
+impl Auth {
+    /// Builds one.
+    fn new() -> Self { Auth }
+}
+"#;
+        let symbols = extract_symbols(source).unwrap();
+        assert_eq!(
+            symbols[0].doc,
+            [(3, "Logs in.".to_string()), (4, "bog: status = green".to_string())]
+        );
+        assert!(symbols[1].doc.is_empty());
+        assert_eq!(symbols[2].doc, [(12, "Builds one.".to_string())]);
+    }
+
+    #[test]
+    fn test_visibility_is_recorded() {
+        let source = r#"
+pub fn open() {}
+pub(crate) fn helper() {}
+fn private() {}
+pub struct Door;
+"#;
+        let symbols = extract_symbols(source).unwrap();
+        let vis: Vec<Option<&str>> = symbols.iter().map(|s| s.visibility.as_deref()).collect();
+        assert_eq!(vis, [Some("pub"), Some("pub(crate)"), None, Some("pub")]);
+        assert!(symbols[0].is_public());
+        assert!(!symbols[1].is_public());
+    }
+
+    #[test]
+    fn test_nested_items_record_their_scope() {
+        let source = r#"
+pub mod net {
+    pub mod tcp {
+        pub fn connect() {}
+    }
+    mod pool {
+        pub fn take() {}
+    }
+}
+
+pub trait Greet {
+    fn name(&self) -> String;
+    fn greet(&self) -> String { format!("hi {}", self.name()) }
+}
+
+pub fn outer() {
+    fn inner() {}
+    struct Local;
+    impl Local {
+        pub fn run() {}
+    }
+}
+"#;
+        let symbols = extract_symbols(source).unwrap();
+        let names: Vec<String> = symbols.iter().map(|s| s.qualified_name()).collect();
+        assert_eq!(
+            names,
+            [
+                "net::tcp::connect",
+                "net::pool::take",
+                "Greet",
+                "Greet::greet",
+                "outer",
+                "inner",
+                "Local",
+                "Local::run"
+            ]
+        );
+        assert_eq!(symbols[0].module_path, ["net", "tcp"]);
+        assert!(symbols[0].is_public());
+        assert_eq!(symbols[1].module_path, ["net", "pool"]);
+        assert!(!symbols[1].is_public());
+
+        // A default method is a method of its trait, as visible as the trait
+        let greet = &symbols[3];
+        assert_eq!(greet.kind, SymbolKind::Method);
+        assert_eq!(greet.impl_trait.as_deref(), Some("Greet"));
+        assert!(greet.impl_type.is_none());
+        assert!(greet.is_trait_default() && !greet.is_trait_impl());
+        assert!(greet.is_public());
+        assert!(symbols[4].is_public());
+        // Items in a function body are never public
+        assert!(symbols[5..].iter().all(|s| !s.is_public()));
+
+        let path = |s: &str| FnPath::parse(s).unwrap();
+        assert_eq!(resolve_fn(&symbols, &path("tcp::connect")).len(), 1);
+        assert_eq!(resolve_fn(&symbols, &path("net::tcp::connect")).len(), 1);
+        assert!(resolve_fn(&symbols, &path("pool::connect")).is_empty());
+        assert_eq!(resolve_fn(&symbols, &path("Greet::greet")).len(), 1);
+        assert_eq!(resolve_fn(&symbols, &path("<Person as Greet>::greet")).len(), 1);
+        assert!(resolve_fn(&symbols, &path("Person::greet")).is_empty());
+    }
+
+    #[test]
+    fn test_qualified_names_include_modules_and_resolve_back() {
+        let source = r#"
+mod a {
+    pub struct Auth;
+    impl Auth { pub fn new() -> Self { Auth } }
+}
+mod b {
+    pub struct Auth;
+    impl Auth { pub fn new() -> Self { Auth } }
+    impl Greet for Auth { fn greet(&self) -> String { String::new() } }
+}
+trait Greet {
+    fn greet(&self) -> String { String::new() }
+}
+"#;
+        let symbols = extract_symbols(source).unwrap();
+        let fns: Vec<&Symbol> = symbols.iter().filter(|s| s.kind.is_fn()).collect();
+        let names: Vec<String> = fns.iter().map(|s| s.qualified_name()).collect();
+        assert_eq!(names, ["a::Auth::new", "b::Auth::new", "<b::Auth as Greet>::greet", "Greet::greet"]);
+        for (symbol, name) in fns.iter().zip(&names) {
+            let resolved = resolve_fn(&symbols, &FnPath::parse(name).unwrap());
+            assert_eq!(resolved.len(), 1, "{name}");
+            assert!(std::ptr::eq(resolved[0], *symbol), "{name}");
+        }
+        // The override hides the default for b::Auth, while Greet::greet is the default
+        assert_eq!(resolve_fn(&symbols, &FnPath::parse("Auth::new").unwrap()).len(), 2);
+    }
+
+    #[test]
+    fn test_qualifiers_and_generics_are_recorded() {
+        let source = r#"
+pub async fn fetch<T: DeserializeOwned>(url: &str) -> T where T: Send {}
+pub const unsafe fn raw() {}
+pub struct Wrapper<T>(T) where T: Clone;
+"#;
+        let symbols = extract_symbols(source).unwrap();
+        let fetch = &symbols[0];
+        assert!(fetch.is_async && !fetch.is_unsafe && !fetch.is_const);
+        assert_eq!(fetch.generics.as_deref(), Some("<T: DeserializeOwned>"));
+        assert_eq!(fetch.where_clause.as_deref(), Some("where T: Send"));
+        assert!(!symbols[1].is_async && symbols[1].is_unsafe && symbols[1].is_const);
+        assert!(symbols[1].generics.is_none());
+        assert_eq!(symbols[2].generics.as_deref(), Some("<T>"));
+        assert_eq!(symbols[2].where_clause.as_deref(), Some("where T: Clone"));
+    }
+
     #[test]
     fn test_extract_calls() {
         let source = r#"
@@ -1,17 +1,71 @@
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
 
 use crate::ast::*;
+use crate::cache::Cache;
+use crate::changes::{ChangeSet, relative_to};
+use crate::config::{self, BogConfig};
+use crate::contract::{self, ContractDrift};
+use crate::deps::{self, DepIgnore};
+use crate::diagnostic::Span;
+use crate::fix;
+use crate::include::{self, IncludeError};
+use crate::inline::{self, InlineError};
+use crate::lang;
 use crate::parser;
+use crate::policy::{self, Level, Policies, Policy, PolicyError};
 use crate::treesitter;
+use crate::walk::Walker;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ValidationError {
     #[error("Parse error in {file}: {message}")]
-    Parse { file: String, message: String },
+    Parse {
+        file: String,
+        message: String,
+        span: Option<Span>,
+    },
+
+    #[error(
+        "In {file}: function '{function}' declared in .bog but not found in source{}",
+        renamed_to.as_ref().map(|n| format!("; renamed to '{n}'?")).unwrap_or_default()
+    )]
+    MissingFunction {
+        file: String,
+        function: String,
+        /// The unannotated function it most likely became, if any
+        renamed_to: Option<String>,
+    },
+
+    #[error(
+        "In {file}: function '{function}' is ambiguous; qualify it as one of: {}",
+        candidates.join(", ")
+    )]
+    AmbiguousFunction {
+        file: String,
+        function: String,
+        candidates: Vec<String>,
+    },
+
+    #[error("In {file}: contract of '{function}' has drifted: {drift}")]
+    ContractDrift {
+        file: String,
+        function: String,
+        drift: ContractDrift,
+    },
+
+    #[error("In {file}: {kind} '{name}' declared in .bog but not found in source")]
+    MissingItem { file: String, kind: ItemKind, name: String },
 
-    #[error("In {file}: function '{function}' declared in .bog but not found in source")]
-    MissingFunction { file: String, function: String },
+    #[error("In {file}: '{name}' is annotated as #[{kind}] but is a {found} in source")]
+    ItemKindMismatch {
+        file: String,
+        kind: ItemKind,
+        name: String,
+        found: &'static str,
+    },
 
     #[error("In {file}: subsystem '{subsystem}' not declared in repo.bog")]
     UndeclaredSubsystem { file: String, subsystem: String },
@@ -27,6 +81,49 @@ pub enum ValidationError {
     #[error("In {file}: file path does not match any glob in subsystem '{subsystem}'")]
     FileNotInSubsystem { file: String, subsystem: String },
 
+    #[error("Subsystem '{subsystem}' files glob '{pattern}' matches no files")]
+    UnmatchedSubsystemGlob { subsystem: String, pattern: String },
+
+    #[error("In {file}: source file is not matched by any subsystem")]
+    UnownedFile { file: String },
+
+    #[error("In {file}: file is matched by more than one subsystem: {}", subsystems.join(", "))]
+    FileInMultipleSubsystems { file: String, subsystems: Vec<String> },
+
+    #[error("Skimsystem '{skimsystem}' targets subsystem '{subsystem}', which owns no files")]
+    SkimTargetOwnsNoFiles { skimsystem: String, subsystem: String },
+
+    #[error(
+        "In {file}: change request '{id}' has status '{status}'; expected one of: {}",
+        RequestStatus::ALL.map(|s| s.to_string()).join(", ")
+    )]
+    InvalidRequestStatus { file: String, id: String, status: String },
+
+    #[error("In {file}: {kind} id '{id}' is already used in {first}")]
+    DuplicateId {
+        file: String,
+        kind: &'static str,
+        id: String,
+        first: String,
+    },
+
+    #[error("In {file}: {kind} '{id}' names agent '{agent}', which owns no subsystem or skimsystem")]
+    UndeclaredAgent {
+        file: String,
+        kind: &'static str,
+        id: String,
+        agent: String,
+    },
+
+    #[error("In {file}: change request '{id}' targets fn '{function}' not found in source")]
+    RequestTargetMissing { file: String, id: String, function: String },
+
+    #[error("In {file}: pickled entry '{id}' supersedes unknown entry '{supersedes}'")]
+    UnknownSupersedes { file: String, id: String, supersedes: String },
+
+    #[error("In {file}: supersedes chain forms a cycle: {}", cycle.join(" -> "))]
+    SupersedesCycle { file: String, cycle: Vec<String> },
+
     #[error("In {file}: dependency '{dep}' references unknown path")]
     UnknownDependency { file: String, dep: String },
 
@@ -41,12 +138,233 @@ pub enum ValidationError {
 
     #[error("In {file}: skim observation for '{skimsystem}' targets fn '{function}' not found in source")]
     SkimTargetFunctionMissing { file: String, skimsystem: String, function: String },
+
+    #[error("In {file}: annotation kind '{kind}' is not built in or declared in bog.toml/repo.bog")]
+    UnknownAnnotation { file: String, kind: String },
+
+    #[error("Annotation kind '{kind}' {message}")]
+    InvalidAnnotationSchema { kind: String, message: String },
+
+    #[error("In {file}: #[{kind}] is missing required field '{field}'")]
+    CustomFieldMissing { file: String, kind: String, field: String },
+
+    #[error("In {file}: #[{kind}] has undeclared field '{field}'")]
+    CustomFieldUnknown { file: String, kind: String, field: String },
+
+    #[error(transparent)]
+    Include(IncludeError),
+
+    #[error("In {file}: {error}")]
+    Inline { file: String, error: InlineError },
+
+    #[error("In repo.bog: {0}")]
+    InvalidPolicy(PolicyError),
+
+    #[error("In {file}: policy '{policy}': {message}")]
+    Policy {
+        file: String,
+        policy: Policy,
+        message: String,
+    },
+
+    #[error("In {file}: #[{kind}] field '{field}' must be of type {expected}")]
+    CustomFieldType {
+        file: String,
+        kind: String,
+        field: String,
+        expected: FieldType,
+    },
+}
+
+impl ValidationError {
+    /// A stable code per variant, for tools reading `bog validate --format json`,
+    /// `sarif` or `junit`. Codes are never reused or renumbered; new variants get
+    /// the next free number.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::Parse { .. } => "BOG001",
+            ValidationError::MissingFunction { .. } => "BOG002",
+            ValidationError::AmbiguousFunction { .. } => "BOG003",
+            ValidationError::ContractDrift { .. } => "BOG004",
+            ValidationError::MissingItem { .. } => "BOG005",
+            ValidationError::ItemKindMismatch { .. } => "BOG006",
+            ValidationError::UndeclaredSubsystem { .. } => "BOG007",
+            ValidationError::OwnerMismatch { .. } => "BOG008",
+            ValidationError::FileNotInSubsystem { .. } => "BOG009",
+            ValidationError::UnmatchedSubsystemGlob { .. } => "BOG010",
+            ValidationError::UnownedFile { .. } => "BOG011",
+            ValidationError::FileInMultipleSubsystems { .. } => "BOG012",
+            ValidationError::SkimTargetOwnsNoFiles { .. } => "BOG013",
+            ValidationError::InvalidRequestStatus { .. } => "BOG014",
+            ValidationError::DuplicateId { .. } => "BOG015",
+            ValidationError::UndeclaredAgent { .. } => "BOG016",
+            ValidationError::RequestTargetMissing { .. } => "BOG017",
+            ValidationError::UnknownSupersedes { .. } => "BOG018",
+            ValidationError::SupersedesCycle { .. } => "BOG019",
+            ValidationError::UnknownDependency { .. } => "BOG020",
+            ValidationError::StubAnnotation { .. } => "BOG021",
+            ValidationError::UndeclaredSkimsystem { .. } => "BOG022",
+            ValidationError::SkimsystemTargetNotFound { .. } => "BOG023",
+            ValidationError::SkimTargetFunctionMissing { .. } => "BOG024",
+            ValidationError::UnknownAnnotation { .. } => "BOG025",
+            ValidationError::InvalidAnnotationSchema { .. } => "BOG026",
+            ValidationError::CustomFieldMissing { .. } => "BOG027",
+            ValidationError::CustomFieldUnknown { .. } => "BOG028",
+            ValidationError::Include(_) => "BOG029",
+            ValidationError::Inline { .. } => "BOG030",
+            ValidationError::InvalidPolicy(_) => "BOG031",
+            ValidationError::Policy { .. } => "BOG032",
+            ValidationError::CustomFieldType { .. } => "BOG033",
+        }
+    }
+
+    /// The file the error is in, as the validator found it. `None` for errors about
+    /// repo-level declarations.
+    pub fn file(&self) -> Option<&str> {
+        match self {
+            ValidationError::Parse { file, .. }
+            | ValidationError::MissingFunction { file, .. }
+            | ValidationError::AmbiguousFunction { file, .. }
+            | ValidationError::ContractDrift { file, .. }
+            | ValidationError::MissingItem { file, .. }
+            | ValidationError::ItemKindMismatch { file, .. }
+            | ValidationError::UndeclaredSubsystem { file, .. }
+            | ValidationError::OwnerMismatch { file, .. }
+            | ValidationError::FileNotInSubsystem { file, .. }
+            | ValidationError::UnownedFile { file }
+            | ValidationError::FileInMultipleSubsystems { file, .. }
+            | ValidationError::InvalidRequestStatus { file, .. }
+            | ValidationError::DuplicateId { file, .. }
+            | ValidationError::UndeclaredAgent { file, .. }
+            | ValidationError::RequestTargetMissing { file, .. }
+            | ValidationError::UnknownSupersedes { file, .. }
+            | ValidationError::SupersedesCycle { file, .. }
+            | ValidationError::UnknownDependency { file, .. }
+            | ValidationError::StubAnnotation { file, .. }
+            | ValidationError::UndeclaredSkimsystem { file, .. }
+            | ValidationError::SkimTargetFunctionMissing { file, .. }
+            | ValidationError::UnknownAnnotation { file, .. }
+            | ValidationError::CustomFieldMissing { file, .. }
+            | ValidationError::CustomFieldUnknown { file, .. }
+            | ValidationError::Inline { file, .. }
+            | ValidationError::Policy { file, .. }
+            | ValidationError::CustomFieldType { file, .. } => Some(file),
+            ValidationError::Include(
+                IncludeError::Read { path, .. }
+                | IncludeError::Parse { path, .. }
+                | IncludeError::Pattern { path, .. }
+                | IncludeError::NotFound { path, .. }
+                | IncludeError::Cycle { path, .. },
+            ) => path.to_str(),
+            ValidationError::UnmatchedSubsystemGlob { .. }
+            | ValidationError::SkimTargetOwnsNoFiles { .. }
+            | ValidationError::SkimsystemTargetNotFound { .. }
+            | ValidationError::InvalidAnnotationSchema { .. }
+            | ValidationError::InvalidPolicy(_) => None,
+        }
+    }
+}
+
+impl From<IncludeError> for ValidationError {
+    /// Parse errors in repo.bog or an included file keep their span for snippets.
+    fn from(e: IncludeError) -> Self {
+        match e {
+            IncludeError::Parse { path, error } => ValidationError::Parse {
+                file: path.display().to_string(),
+                message: error.message(),
+                span: Some(error.span()),
+            },
+            other => ValidationError::Include(other),
+        }
+    }
+}
+
+/// Something worth fixing that does not fail validation.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ValidationWarning {
+    #[error("{file}: fn '{function}' lists dep '{dep}' but no longer calls it")]
+    StaleDep { file: String, function: String, dep: String },
+
+    #[error("{file}: fn '{function}' calls '{call}', which is not in its deps")]
+    UndeclaredCall { file: String, function: String, call: String },
+
+    #[error("{file}: contract_compliance is computed from contracts; the #[health] value is ignored")]
+    ComputedHealth { file: String },
+
+    #[error("{file}: #[include] is only resolved in repo.bog")]
+    IncludeOutsideRepo { file: String },
+
+    #[error("Source file not found for {file}: expected {expected}")]
+    SourceNotFound { file: String, expected: String },
+
+    #[error("{file}: policy '{}': {}", violation.policy, violation.message)]
+    Policy {
+        file: String,
+        violation: policy::Violation,
+    },
+
+    #[error("No repo.bog found")]
+    NoRepoBog,
+
+    #[error("Failed to load bog.toml: {message}")]
+    Config { message: String },
+
+    #[error(
+        "bog.toml: tree_sitter.language '{language}' is not compiled in; \
+         its sidecars are not checked against source"
+    )]
+    LanguageNotCompiled { language: String },
+
+    #[error("{file}: inline bog annotations are ignored without a .rs.bog sidecar")]
+    InlineWithoutSidecar { file: String },
+}
+
+impl ValidationWarning {
+    /// A stable code per kind of warning, numbered from BOG901 apart from the error
+    /// codes, and per policy for policy warnings. Like error codes, never reused.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationWarning::StaleDep { .. } => "BOG901",
+            ValidationWarning::UndeclaredCall { .. } => "BOG902",
+            ValidationWarning::ComputedHealth { .. } => "BOG903",
+            ValidationWarning::IncludeOutsideRepo { .. } => "BOG904",
+            ValidationWarning::SourceNotFound { .. } => "BOG905",
+            ValidationWarning::Policy { violation, .. } => match violation.policy {
+                Policy::RequireContracts => "BOG906",
+                Policy::RequireOwner => "BOG907",
+                Policy::RedMaxDays => "BOG908",
+                Policy::StaleAfterDays => "BOG909",
+            },
+            ValidationWarning::NoRepoBog => "BOG910",
+            ValidationWarning::Config { .. } => "BOG911",
+            ValidationWarning::LanguageNotCompiled { .. } => "BOG912",
+            ValidationWarning::InlineWithoutSidecar { .. } => "BOG913",
+        }
+    }
+
+    /// The file the warning is about, as the validator found it. `None` for a
+    /// missing repo.bog.
+    pub fn file(&self) -> Option<&str> {
+        match self {
+            ValidationWarning::StaleDep { file, .. }
+            | ValidationWarning::UndeclaredCall { file, .. }
+            | ValidationWarning::ComputedHealth { file }
+            | ValidationWarning::IncludeOutsideRepo { file }
+            | ValidationWarning::SourceNotFound { file, .. }
+            | ValidationWarning::Policy { file, .. }
+            | ValidationWarning::InlineWithoutSidecar { file } => Some(file),
+            ValidationWarning::Config { .. } | ValidationWarning::LanguageNotCompiled { .. } => {
+                Some("bog.toml")
+            }
+            ValidationWarning::NoRepoBog => None,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct ValidationReport {
     pub errors: Vec<ValidationError>,
-    pub warnings: Vec<String>,
+    pub warnings: Vec<ValidationWarning>,
     pub files_checked: usize,
 }
 
@@ -57,57 +375,169 @@ impl ValidationReport {
 }
 
 /// Validate a single .bog file's syntax by parsing it
-pub fn validate_syntax(path: &Path) -> Result<BogFile, ValidationError> {
+pub fn validate_syntax(path: &Path, cache: &Cache) -> Result<BogFile, ValidationError> {
     let content = std::fs::read_to_string(path).map_err(|e| ValidationError::Parse {
         file: path.display().to_string(),
         message: e.to_string(),
+        span: None,
     })?;
-    parser::parse_bog(&content).map_err(|e| ValidationError::Parse {
+    cache.parse_bog(&content).map_err(|e| ValidationError::Parse {
         file: path.display().to_string(),
-        message: e.to_string(),
+        message: e.message(),
+        span: Some(e.span()),
     })
 }
 
-/// Validate function annotations against the actual source file using tree-sitter
+/// Validate fn, type, trait and const annotations against the actual source file using
+/// tree-sitter, including drift between fn contracts and signatures. A missing fn
+/// names the function it was likely renamed to, judged with `ignore` applied to calls.
 pub fn validate_functions(
     bog_path: &Path,
     bog_file: &BogFile,
     source_path: &Path,
+    ignore: &DepIgnore,
+    cache: &Cache,
 ) -> Vec<ValidationError> {
     let mut errors = Vec::new();
 
-    let source = match std::fs::read_to_string(source_path) {
-        Ok(s) => s,
-        Err(_) => return errors,
-    };
-
-    let symbols = match treesitter::extract_symbols(&source) {
-        Ok(s) => s,
-        Err(_) => return errors,
+    let Some(symbols) = cache.symbols(source_path) else {
+        return errors;
     };
-
-    let fn_names: HashSet<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+    let renames = fix::propose_renames(bog_file, &symbols, ignore);
 
     for ann in &bog_file.annotations {
-        if let Annotation::Fn(f) = ann {
-            if !fn_names.contains(f.name.as_str()) {
-                errors.push(ValidationError::MissingFunction {
-                    file: bog_path.display().to_string(),
-                    function: f.name.clone(),
-                });
+        match ann {
+            Annotation::Fn(f) => {
+                if let Some(mut e) = check_fn_ref(bog_path, &symbols, &f.name) {
+                    if let ValidationError::MissingFunction { renamed_to, .. } = &mut e {
+                        let rename = renames.iter().find(|r| r.from == f.name);
+                        *renamed_to = rename.map(|r| r.to.clone());
+                    }
+                    errors.push(e);
+                }
+                errors.extend(contract::check_fn(f, &symbols).into_iter().map(|drift| {
+                    ValidationError::ContractDrift {
+                        file: bog_path.display().to_string(),
+                        function: f.name.clone(),
+                        drift,
+                    }
+                }));
+                if f.stub {
+                    errors.push(ValidationError::StubAnnotation {
+                        file: bog_path.display().to_string(),
+                        function: f.name.clone(),
+                    });
+                }
             }
-            if f.stub {
-                errors.push(ValidationError::StubAnnotation {
-                    file: bog_path.display().to_string(),
-                    function: f.name.clone(),
+            Annotation::Item(item) => {
+                let same_name: Vec<&treesitter::Symbol> = symbols
+                    .iter()
+                    .filter(|s| s.name == item.name && !s.kind.is_fn())
+                    .collect();
+                if same_name.iter().any(|s| s.kind.item_kind() == Some(item.kind)) {
+                    continue;
+                }
+                errors.push(match same_name.first() {
+                    Some(other) => ValidationError::ItemKindMismatch {
+                        file: bog_path.display().to_string(),
+                        kind: item.kind,
+                        name: item.name.clone(),
+                        found: other.kind.label(),
+                    },
+                    None => ValidationError::MissingItem {
+                        file: bog_path.display().to_string(),
+                        kind: item.kind,
+                        name: item.name.clone(),
+                    },
                 });
             }
+            _ => {}
         }
     }
 
     errors
 }
 
+/// Compare each fn's declared deps with the calls in its body, returning one warning
+/// per stale dep and per call missing from deps. These are warnings rather than
+/// errors because method calls (`x.foo()`) are not extracted.
+pub fn validate_deps(
+    bog_path: &Path,
+    bog_file: &BogFile,
+    source_path: &Path,
+    ignore: &DepIgnore,
+    cache: &Cache,
+) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    let Some(symbols) = cache.symbols(source_path) else {
+        return warnings;
+    };
+
+    for ann in &bog_file.annotations {
+        let Annotation::Fn(f) = ann else {
+            continue;
+        };
+        let drift = deps::check_fn(f, &symbols, ignore);
+        for dep in &drift.stale {
+            warnings.push(ValidationWarning::StaleDep {
+                file: bog_path.display().to_string(),
+                function: f.name.clone(),
+                dep: dep.clone(),
+            });
+        }
+        for call in &drift.missing {
+            warnings.push(ValidationWarning::UndeclaredCall {
+                file: bog_path.display().to_string(),
+                function: f.name.clone(),
+                call: call.clone(),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Check a sidecar against the enforced policies of repo.bog. `source_path` is the
+/// file the sidecar describes; without it `require_contracts` is not checked.
+pub fn validate_policies(
+    bog_file: &BogFile,
+    source_path: Option<&Path>,
+    policies: &Policies,
+    today: chrono::NaiveDate,
+    cache: &Cache,
+) -> Vec<policy::Violation> {
+    let symbols = source_path.and_then(|p| cache.symbols(p));
+    policies.check_file(bog_file, symbols.as_deref(), today)
+}
+
+/// Resolve a (possibly qualified) function reference against the source symbols,
+/// returning an error if it matches no function or more than one.
+fn check_fn_ref(
+    bog_path: &Path,
+    symbols: &[treesitter::Symbol],
+    reference: &str,
+) -> Option<ValidationError> {
+    let path = FnPath::parse(reference).unwrap_or_else(|_| FnPath {
+        self_type: None,
+        trait_name: None,
+        name: reference.to_string(),
+    });
+    let matches = treesitter::resolve_fn(symbols, &path);
+    match matches.len() {
+        0 => Some(ValidationError::MissingFunction {
+            file: bog_path.display().to_string(),
+            function: reference.to_string(),
+            renamed_to: None,
+        }),
+        1 => None,
+        _ => Some(ValidationError::AmbiguousFunction {
+            file: bog_path.display().to_string(),
+            function: reference.to_string(),
+            candidates: matches.iter().map(|s| s.qualified_name()).collect(),
+        }),
+    }
+}
+
 /// Validate subsystem consistency: file ownership matches repo.bog declarations
 pub fn validate_subsystem_consistency(
     repo_bog: &BogFile,
@@ -151,8 +581,7 @@ pub fn validate_subsystem_consistency(
                 // Check file path matches at least one glob in subsystem
                 let matches_glob = subsys.files.iter().any(|pattern| {
                     glob::Pattern::new(pattern)
-                        .map(|p| p.matches(path))
-                        .unwrap_or(false)
+                        .is_ok_and(|p| p.matches_with(path, path_match_options()))
                 });
                 if !matches_glob {
                     errors.push(ValidationError::FileNotInSubsystem {
@@ -167,6 +596,87 @@ pub fn validate_subsystem_consistency(
     errors
 }
 
+/// Validate repo.bog against the files on disk: every subsystem glob matches
+/// something, every source file a language backend reads belongs to exactly one
+/// subsystem, and skimsystems only name subsystems that own files.
+pub fn validate_file_coverage(walker: &Walker, repo_bog: &BogFile) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let subsystems: Vec<&SubsystemDecl> = repo_bog
+        .annotations
+        .iter()
+        .filter_map(|a| match a {
+            Annotation::Subsystem(s) => Some(s),
+            _ => None,
+        })
+        .collect();
+
+    let mut owns_files: HashSet<&str> = HashSet::new();
+    for subsys in &subsystems {
+        for pattern in &subsys.files {
+            if !walker.glob(pattern).is_empty() {
+                owns_files.insert(&subsys.name);
+            } else {
+                errors.push(ValidationError::UnmatchedSubsystemGlob {
+                    subsystem: subsys.name.clone(),
+                    pattern: pattern.clone(),
+                });
+            }
+        }
+    }
+
+    for source_path in lang::source_files(walker) {
+        let rel = source_path.strip_prefix(walker.root()).unwrap_or(&source_path);
+        let rel = rel.to_string_lossy().to_string();
+        let owners: Vec<String> = subsystems
+            .iter()
+            .filter(|s| {
+                s.files.iter().any(|pattern| {
+                    glob::Pattern::new(pattern)
+                        .is_ok_and(|p| p.matches_with(&rel, path_match_options()))
+                })
+            })
+            .map(|s| s.name.clone())
+            .collect();
+        match owners.len() {
+            0 => errors.push(ValidationError::UnownedFile { file: rel }),
+            1 => {}
+            _ => errors.push(ValidationError::FileInMultipleSubsystems {
+                file: rel,
+                subsystems: owners,
+            }),
+        }
+    }
+
+    for ann in &repo_bog.annotations {
+        if let Annotation::Skimsystem(sk) = ann
+            && let SkimTargets::Named(targets) = &sk.targets
+        {
+            for target in targets {
+                // Undeclared targets are reported by validate_skimsystem_consistency
+                if subsystems.iter().any(|s| &s.name == target)
+                    && !owns_files.contains(target.as_str())
+                {
+                    errors.push(ValidationError::SkimTargetOwnsNoFiles {
+                        skimsystem: sk.name.clone(),
+                        subsystem: target.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+/// Match globs against relative paths the way `glob::glob` walks the disk: `*`
+/// stays within one directory.
+fn path_match_options() -> glob::MatchOptions {
+    glob::MatchOptions {
+        require_literal_separator: true,
+        ..glob::MatchOptions::new()
+    }
+}
+
 /// Validate skimsystem declarations and skim observations
 pub fn validate_skimsystem_consistency(
     repo_bog: &BogFile,
@@ -211,13 +721,13 @@ pub fn validate_skimsystem_consistency(
     // Check skim observations reference declared skimsystems
     for (path, bog) in file_bogs {
         for ann in &bog.annotations {
-            if let Annotation::Skim(obs) = ann {
-                if !skimsystem_names.contains(&obs.skimsystem) {
-                    errors.push(ValidationError::UndeclaredSkimsystem {
-                        file: path.clone(),
-                        skimsystem: obs.skimsystem.clone(),
-                    });
-                }
+            if let Annotation::Skim(obs) = ann
+                && !skimsystem_names.contains(&obs.skimsystem)
+            {
+                errors.push(ValidationError::UndeclaredSkimsystem {
+                    file: path.clone(),
+                    skimsystem: obs.skimsystem.clone(),
+                });
             }
         }
     }
@@ -230,6 +740,7 @@ pub fn validate_skim_targets(
     bog_path: &Path,
     bog_file: &BogFile,
     source_path: &Path,
+    cache: &Cache,
 ) -> Vec<ValidationError> {
     let mut errors = Vec::new();
 
@@ -238,10 +749,10 @@ pub fn validate_skim_targets(
         .annotations
         .iter()
         .filter_map(|a| {
-            if let Annotation::Skim(obs) = a {
-                if let Some(SkimTarget::Fn(name)) = &obs.target {
-                    return Some((obs.skimsystem.as_str(), name.as_str()));
-                }
+            if let Annotation::Skim(obs) = a
+                && let Some(SkimTarget::Fn(name)) = &obs.target
+            {
+                return Some((obs.skimsystem.as_str(), name.as_str()));
             }
             None
         })
@@ -251,110 +762,581 @@ pub fn validate_skim_targets(
         return errors;
     }
 
-    let source = match std::fs::read_to_string(source_path) {
-        Ok(s) => s,
-        Err(_) => return errors,
+    let Some(symbols) = cache.symbols(source_path) else {
+        return errors;
     };
 
-    let symbols = match treesitter::extract_symbols(&source) {
-        Ok(s) => s,
-        Err(_) => return errors,
+    for (skimsystem, function) in fn_targets {
+        match check_fn_ref(bog_path, &symbols, function) {
+            Some(ValidationError::MissingFunction { .. }) => {
+                errors.push(ValidationError::SkimTargetFunctionMissing {
+                    file: bog_path.display().to_string(),
+                    skimsystem: skimsystem.to_string(),
+                    function: function.to_string(),
+                });
+            }
+            Some(e) => errors.push(e),
+            None => {}
+        }
+    }
+
+    errors
+}
+
+/// Validate change request fn targets against the source the sidecar describes.
+/// Ambiguous targets are accepted.
+pub fn validate_request_targets(
+    bog_path: &Path,
+    bog_file: &BogFile,
+    source_path: &Path,
+    cache: &Cache,
+) -> Vec<ValidationError> {
+    let targets: Vec<(&str, &str)> = bog_file
+        .annotations
+        .iter()
+        .filter_map(|a| match a {
+            Annotation::ChangeRequests(reqs) => Some(reqs),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|r| match &r.target {
+            Value::FnRef(name) => Some((r.id.as_str(), name.as_str())),
+            _ => None,
+        })
+        .collect();
+    if targets.is_empty() {
+        return Vec::new();
+    }
+    let Some(symbols) = cache.symbols(source_path) else {
+        return Vec::new();
     };
 
-    let fn_names: HashSet<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+    // A request may name a function that several impls define; it only has to exist
+    targets
+        .into_iter()
+        .filter(|(_, function)| {
+            matches!(
+                check_fn_ref(bog_path, &symbols, function),
+                Some(ValidationError::MissingFunction { .. })
+            )
+        })
+        .map(|(id, function)| ValidationError::RequestTargetMissing {
+            file: bog_path.display().to_string(),
+            id: id.to_string(),
+            function: function.to_string(),
+        })
+        .collect()
+}
 
-    for (skimsystem, function) in fn_targets {
-        if !fn_names.contains(function) {
-            errors.push(ValidationError::SkimTargetFunctionMissing {
-                file: bog_path.display().to_string(),
-                skimsystem: skimsystem.to_string(),
-                function: function.to_string(),
+/// Validate change requests and pickled entries across the project: request
+/// statuses, unique request and pickle ids, `from` and `agent` naming declared
+/// owners, and `supersedes` chains that point at existing entries without cycles.
+/// Agents are only checked when repo.bog is given. Paths in `file_bogs` are
+/// sidecar paths.
+pub fn validate_requests_and_pickles(
+    repo_bog: Option<&BogFile>,
+    file_bogs: &[(String, &BogFile)],
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let agents = repo_bog.map(derive_agents);
+    let undeclared = |agent: &str| agents.as_ref().is_some_and(|a| !a.roles.contains_key(agent));
+
+    let mut request_ids: HashMap<&str, &str> = HashMap::new();
+    let mut pickle_ids: HashMap<&str, &str> = HashMap::new();
+    let mut pickles: Vec<(&str, &PickledAnnotation)> = Vec::new();
+
+    for (path, bog) in file_bogs {
+        for ann in &bog.annotations {
+            match ann {
+                Annotation::ChangeRequests(reqs) => {
+                    for r in reqs {
+                        if RequestStatus::from_name(&r.status).is_none() {
+                            errors.push(ValidationError::InvalidRequestStatus {
+                                file: path.clone(),
+                                id: r.id.clone(),
+                                status: r.status.clone(),
+                            });
+                        }
+                        if let Some(first) = request_ids.insert(&r.id, path) {
+                            errors.push(ValidationError::DuplicateId {
+                                file: path.clone(),
+                                kind: "change request",
+                                id: r.id.clone(),
+                                first: first.to_string(),
+                            });
+                            request_ids.insert(&r.id, first);
+                        }
+                        if undeclared(&r.from) {
+                            errors.push(ValidationError::UndeclaredAgent {
+                                file: path.clone(),
+                                kind: "change request",
+                                id: r.id.clone(),
+                                agent: r.from.clone(),
+                            });
+                        }
+                    }
+                }
+                Annotation::Pickled(p) => {
+                    if let Some(first) = pickle_ids.insert(&p.id, path) {
+                        errors.push(ValidationError::DuplicateId {
+                            file: path.clone(),
+                            kind: "pickled entry",
+                            id: p.id.clone(),
+                            first: first.to_string(),
+                        });
+                        pickle_ids.insert(&p.id, first);
+                    }
+                    if undeclared(&p.agent) {
+                        errors.push(ValidationError::UndeclaredAgent {
+                            file: path.clone(),
+                            kind: "pickled entry",
+                            id: p.id.clone(),
+                            agent: p.agent.clone(),
+                        });
+                    }
+                    pickles.push((path, p));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let supersedes: HashMap<&str, &str> = pickles
+        .iter()
+        .filter_map(|(_, p)| Some((p.id.as_str(), p.supersedes.as_deref()?)))
+        .collect();
+    for (path, p) in &pickles {
+        let Some(target) = &p.supersedes else {
+            continue;
+        };
+        if !pickle_ids.contains_key(target.as_str()) {
+            errors.push(ValidationError::UnknownSupersedes {
+                file: path.to_string(),
+                id: p.id.clone(),
+                supersedes: target.clone(),
             });
+            continue;
+        }
+        // Follow the chain from this entry; report a cycle once, from its smallest id
+        let mut chain = vec![p.id.as_str()];
+        while let Some(&next) = supersedes.get(chain[chain.len() - 1]) {
+            if let Some(pos) = chain.iter().position(|id| *id == next) {
+                if pos == 0 && chain.iter().min() == Some(&chain[0]) {
+                    let mut cycle: Vec<String> = chain.iter().map(|id| id.to_string()).collect();
+                    cycle.push(next.to_string());
+                    errors.push(ValidationError::SupersedesCycle {
+                        file: path.to_string(),
+                        cycle,
+                    });
+                }
+                break;
+            }
+            chain.push(next);
         }
     }
 
     errors
 }
 
+/// Collect user-defined annotation schemas from bog.toml and repo.bog. Kinds that
+/// shadow a built-in annotation or are declared more than once are reported.
+pub fn collect_annotation_schemas(
+    config: Option<&BogConfig>,
+    repo_bog: Option<&BogFile>,
+) -> (Vec<AnnotationSchema>, Vec<ValidationError>) {
+    let mut schemas = config.map(BogConfig::annotation_schemas).unwrap_or_default();
+    if let Some(repo) = repo_bog {
+        for ann in &repo.annotations {
+            if let Annotation::Schema(schema) = ann {
+                schemas.push(schema.clone());
+            }
+        }
+    }
+
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    for schema in &schemas {
+        if parser::BUILTIN_ANNOTATIONS.contains(&schema.kind.as_str()) {
+            errors.push(ValidationError::InvalidAnnotationSchema {
+                kind: schema.kind.clone(),
+                message: "shadows a built-in annotation".to_string(),
+            });
+        } else if !seen.insert(schema.kind.as_str()) {
+            errors.push(ValidationError::InvalidAnnotationSchema {
+                kind: schema.kind.clone(),
+                message: "is declared more than once".to_string(),
+            });
+        }
+    }
+
+    (schemas, errors)
+}
+
+/// Validate custom annotations in one .bog file against their declared schemas
+pub fn validate_custom_annotations(
+    file: &str,
+    bog_file: &BogFile,
+    schemas: &[AnnotationSchema],
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let by_kind: HashMap<&str, &AnnotationSchema> =
+        schemas.iter().map(|s| (s.kind.as_str(), s)).collect();
+
+    for ann in &bog_file.annotations {
+        let Annotation::Custom(custom) = ann else {
+            continue;
+        };
+        let Some(schema) = by_kind.get(custom.kind.as_str()) else {
+            errors.push(ValidationError::UnknownAnnotation {
+                file: file.to_string(),
+                kind: custom.kind.clone(),
+            });
+            continue;
+        };
+
+        for spec in &schema.fields {
+            match custom.get(&spec.name) {
+                Some(value) if !spec.ty.matches(value) => {
+                    errors.push(ValidationError::CustomFieldType {
+                        file: file.to_string(),
+                        kind: custom.kind.clone(),
+                        field: spec.name.clone(),
+                        expected: spec.ty,
+                    });
+                }
+                None if spec.required => {
+                    errors.push(ValidationError::CustomFieldMissing {
+                        file: file.to_string(),
+                        kind: custom.kind.clone(),
+                        field: spec.name.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        for (key, _) in &custom.fields {
+            if !schema.fields.iter().any(|spec| &spec.name == key) {
+                errors.push(ValidationError::CustomFieldUnknown {
+                    file: file.to_string(),
+                    kind: custom.kind.clone(),
+                    field: key.clone(),
+                });
+            }
+        }
+    }
+
+    errors
+}
+
+/// What validating one sidecar found, merged into the report in walk order.
+#[derive(Default)]
+struct SidecarOutcome {
+    errors: Vec<ValidationError>,
+    warnings: Vec<ValidationWarning>,
+    /// Source path relative to the root, with the sidecar and its inline annotations
+    bog: Option<(String, BogFile)>,
+}
+
+impl SidecarOutcome {
+    /// Policy violations in `file`, as errors or warnings by their level.
+    fn push_violations(&mut self, file: &str, violations: Vec<policy::Violation>) {
+        for v in violations {
+            match v.level {
+                Level::Error => self.errors.push(ValidationError::Policy {
+                    file: file.to_string(),
+                    policy: v.policy,
+                    message: v.message,
+                }),
+                Level::Warning => self.warnings.push(ValidationWarning::Policy {
+                    file: file.to_string(),
+                    violation: v,
+                }),
+            }
+        }
+    }
+}
+
+/// Everything `validate_project` checks each sidecar against.
+struct SidecarCheck<'a> {
+    root: &'a Path,
+    cache: &'a Cache,
+    schemas: &'a [AnnotationSchema],
+    policies: &'a Policies,
+    dep_ignore: &'a DepIgnore,
+    today: chrono::NaiveDate,
+}
+
+impl SidecarCheck<'_> {
+    /// `require_contracts` for a source file without a sidecar, where no fn has a
+    /// contract. Inline annotations are not read without a sidecar.
+    fn check_unannotated(&self, source_path: &Path) -> SidecarOutcome {
+        let mut out = SidecarOutcome::default();
+        if let Some(symbols) = self.cache.symbols(source_path) {
+            let violations = self.policies.check_contracts(&BogFile::default(), &symbols);
+            out.push_violations(&source_path.display().to_string(), violations);
+        }
+        out
+    }
+
+    /// Validate one sidecar on its own; cross-file checks run once all are parsed.
+    fn check(&self, entry: &Path) -> SidecarOutcome {
+        let mut out = SidecarOutcome::default();
+        let mut bog = match validate_syntax(entry, self.cache) {
+            Ok(bog) => bog,
+            Err(e) => {
+                out.errors.push(e);
+                return out;
+            }
+        };
+        if bog.annotations.iter().any(|a| {
+            matches!(a, Annotation::Health(h) if h.dimensions.contains_key("contract_compliance"))
+        }) {
+            out.warnings.push(ValidationWarning::ComputedHealth {
+                file: entry.display().to_string(),
+            });
+        }
+        if bog.annotations.iter().any(|a| matches!(a, Annotation::Include(_))) {
+            out.warnings.push(ValidationWarning::IncludeOutsideRepo {
+                file: entry.display().to_string(),
+            });
+        }
+        out.errors.extend(validate_custom_annotations(
+            &entry.display().to_string(),
+            &bog,
+            self.schemas,
+        ));
+
+        // If it describes a source file, validate functions and skim targets against it
+        let entry_str = entry.to_string_lossy().to_string();
+        let source = lang::source_for(entry).map(|(path, _)| path);
+        if let Some(source_path) = &source {
+            let source_path_str = source_path.to_string_lossy();
+            if source_path.exists() {
+                // Validate the sidecar together with the source's inline annotations
+                let inline_errors = inline::merge_from_source(&mut bog, source_path);
+                out.errors.extend(inline_errors.into_iter().map(|error| {
+                    ValidationError::Inline {
+                        file: source_path_str.to_string(),
+                        error,
+                    }
+                }));
+                let cache = self.cache;
+                out.errors.extend(validate_functions(
+                    entry,
+                    &bog,
+                    source_path,
+                    self.dep_ignore,
+                    cache,
+                ));
+                out.warnings.extend(validate_deps(
+                    entry,
+                    &bog,
+                    source_path,
+                    self.dep_ignore,
+                    cache,
+                ));
+                out.errors.extend(validate_skim_targets(entry, &bog, source_path, cache));
+                out.errors.extend(validate_request_targets(entry, &bog, source_path, cache));
+            } else {
+                out.warnings.push(ValidationWarning::SourceNotFound {
+                    file: entry_str.clone(),
+                    expected: source_path_str.to_string(),
+                });
+            }
+        }
+
+        let source_path = source.as_deref().filter(|p| p.exists());
+        let violations =
+            validate_policies(&bog, source_path, self.policies, self.today, self.cache);
+        out.push_violations(&entry_str, violations);
+
+        // Compute relative path for subsystem matching
+        let rel_path = entry
+            .strip_prefix(self.root)
+            .unwrap_or(entry)
+            .to_string_lossy()
+            .to_string();
+        // Strip .bog suffix to get the source file relative path
+        let source_rel = rel_path.strip_suffix(".bog").unwrap_or(&rel_path);
+        out.bog = Some((source_rel.to_string(), bog));
+        out
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
 /// Run full validation on a project directory
 pub fn validate_project(root: &Path) -> ValidationReport {
+    validate(root, None)
+}
+
+/// Validate only what a diff touched: sidecars that changed or whose source did,
+/// and the subsystem ownership of those files. Cross-file findings are reported
+/// when they involve a changed file; repo-wide ones (an unmatched glob, an invalid
+/// policy) only when repo.bog, an include or bog.toml changed, or a file was deleted.
+pub fn validate_changed(root: &Path, changes: &ChangeSet) -> ValidationReport {
+    validate(root, Some(changes))
+}
+
+fn validate(root: &Path, changes: Option<&ChangeSet>) -> ValidationReport {
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
     let mut files_checked = 0;
 
-    // Parse repo.bog
+    // Parse repo.bog and the files it includes. Every include error is reported, but
+    // a partially loaded repo.bog is not used for cross-checks.
     let repo_bog_path = root.join("repo.bog");
+    let mut included = HashSet::new();
+    let mut repo_files = vec!["repo.bog".to_string(), "bog.toml".to_string()];
+    let mut repo_sources = 0;
     let repo_bog = if repo_bog_path.exists() {
-        match validate_syntax(&repo_bog_path) {
-            Ok(bog) => {
-                files_checked += 1;
-                Some(bog)
-            }
-            Err(e) => {
-                errors.push(e);
-                None
-            }
+        let (repo, include_errors) = include::load_repo_bog_recovering(root);
+        repo_sources = repo.sources.len();
+        repo_files.extend(repo.sources.iter().map(|(p, _)| relative_to(root, p)));
+        included.extend(repo.sources.iter().map(|(p, _)| canonical(p)));
+        if include_errors.is_empty() {
+            Some(repo.bog)
+        } else {
+            errors.extend(include_errors.into_iter().map(ValidationError::from));
+            None
         }
     } else {
-        warnings.push("No repo.bog found".to_string());
+        warnings.push(ValidationWarning::NoRepoBog);
         None
     };
 
-    // Find and validate all .bog sidecar files
-    let mut file_bogs = Vec::new();
-    let bog_pattern = root.join("**/*.bog");
-    let pattern_str = bog_pattern.to_string_lossy();
-    if let Ok(paths) = glob::glob(&pattern_str) {
-        for entry in paths.flatten() {
-            // Skip repo.bog (already handled)
-            if entry.file_name().map(|n| n == "repo.bog").unwrap_or(false) {
-                continue;
-            }
+    // Custom annotation schemas from bog.toml and repo.bog
+    let config_path = root.join("bog.toml");
+    let config = if config_path.exists() {
+        config::load_config(&config_path)
+            .map_err(|e| {
+                warnings.push(ValidationWarning::Config {
+                    message: e.to_string(),
+                });
+            })
+            .ok()
+    } else {
+        None
+    };
+    // Sidecars of a language without a compiled-in backend are never checked against source
+    if let Some(language) = config.as_ref().map(|c| &c.tree_sitter.language)
+        && lang::backend_named(language).is_none()
+    {
+        warnings.push(ValidationWarning::LanguageNotCompiled {
+            language: language.clone(),
+        });
+    }
+    let (schemas, schema_errors) = collect_annotation_schemas(config.as_ref(), repo_bog.as_ref());
+    let policies = match repo_bog.as_ref().map(Policies::from_repo) {
+        Some(Err(policy_errors)) => {
+            errors.extend(policy_errors.into_iter().map(ValidationError::InvalidPolicy));
+            Policies::default()
+        }
+        Some(Ok(policies)) => policies,
+        None => Policies::default(),
+    };
+    let today = chrono::Local::now().date_naive();
+    let dep_ignore = config
+        .as_ref()
+        .map(|c| DepIgnore::new(&c.deps.ignore))
+        .unwrap_or_default();
+    errors.extend(schema_errors);
+    if let Some(repo) = &repo_bog {
+        errors.extend(validate_custom_annotations("repo.bog", repo, &schemas));
+    }
 
-            // Skip build artifacts and git internals
-            let rel = entry.strip_prefix(root).unwrap_or(&entry);
-            if rel.components().any(|c| {
-                matches!(c.as_os_str().to_str(), Some("target" | ".git"))
-            }) {
-                continue;
+    // A scoped run only reports what involves a changed file
+    let repo_changed = changes.is_none_or(|c| repo_files.iter().any(|f| c.contains(f)));
+    let in_scope = |e: &ValidationError| {
+        let Some(c) = changes else {
+            return true;
+        };
+        let touched = |file: &str| c.touches(&relative_to(root, Path::new(file)));
+        match (e, e.file()) {
+            (ValidationError::DuplicateId { file, first, .. }, _) => {
+                touched(file) || touched(first)
             }
+            (_, Some(file)) => touched(file),
+            (_, None) => repo_changed || c.has_deletions(root),
+        }
+    };
+    if repo_changed {
+        files_checked += repo_sources;
+    }
+    errors.retain(|e| in_scope(e));
 
-            match validate_syntax(&entry) {
-                Ok(bog) => {
-                    files_checked += 1;
-
-                    // If it's a .rs.bog file, validate functions and skim targets against source
-                    let entry_str = entry.to_string_lossy().to_string();
-                    if entry_str.ends_with(".rs.bog") {
-                        let source_path_str = entry_str.strip_suffix(".bog").unwrap();
-                        let source_path = Path::new(source_path_str);
-                        if source_path.exists() {
-                            let fn_errors = validate_functions(&entry, &bog, source_path);
-                            errors.extend(fn_errors);
-                            let skim_errors = validate_skim_targets(&entry, &bog, source_path);
-                            errors.extend(skim_errors);
-                        } else {
-                            warnings.push(format!(
-                                "Source file not found for {entry_str}: expected {source_path_str}"
-                            ));
-                        }
-                    }
+    // Find and validate all .bog sidecar files
+    let walker = Walker::new(root);
+    let cache = Cache::open(root);
+    let all_sidecars: Vec<PathBuf> = walker
+        .files_with_suffix(".bog")
+        .into_iter()
+        // Skip repo.bog and its includes (already handled)
+        .filter(|entry| {
+            entry.file_name().is_none_or(|n| n != "repo.bog")
+                && !included.contains(&canonical(entry))
+        })
+        .collect();
+    let (sidecar_paths, unchanged): (Vec<PathBuf>, Vec<PathBuf>) = all_sidecars
+        .iter()
+        .cloned()
+        .partition(|entry| changes.is_none_or(|c| c.touches(&relative_to(root, entry))));
+    let check = SidecarCheck {
+        root,
+        cache: &cache,
+        schemas: &schemas,
+        policies: &policies,
+        dep_ignore: &dep_ignore,
+        today,
+    };
+    // Sidecars are checked in parallel and merged in walk order, so the report is stable
+    let outcomes: Vec<SidecarOutcome> =
+        sidecar_paths.par_iter().map(|entry| check.check(entry)).collect();
+    let mut file_bogs = Vec::new();
+    for outcome in outcomes {
+        files_checked += 1;
+        errors.extend(outcome.errors);
+        warnings.extend(outcome.warnings);
+        file_bogs.extend(outcome.bog);
+    }
 
-                    // Compute relative path for subsystem matching
-                    let rel_path = entry
-                        .strip_prefix(root)
-                        .unwrap_or(&entry)
-                        .to_string_lossy()
-                        .to_string();
-                    // Strip .bog suffix to get the source file relative path
-                    let source_rel = rel_path.strip_suffix(".bog").unwrap_or(&rel_path);
-                    file_bogs.push((source_rel.to_string(), bog));
-                }
-                Err(e) => {
-                    errors.push(e);
-                    files_checked += 1;
-                }
+    // Inline annotations are only read alongside a sidecar, which supplies the file header
+    let sources: Vec<PathBuf> = walker
+        .files_with_suffix(".rs")
+        .into_iter()
+        .filter(|source| changes.is_none_or(|c| c.contains(&relative_to(root, source))))
+        .collect();
+    warnings.par_extend(sources.par_iter().filter_map(|source_path| {
+        if PathBuf::from(format!("{}.bog", source_path.display())).exists() {
+            return None;
+        }
+        let source = std::fs::read_to_string(source_path).ok()?;
+        let (found, inline_errors) = inline::harvest(&source);
+        (!found.is_empty() || !inline_errors.is_empty()).then(|| {
+            ValidationWarning::InlineWithoutSidecar {
+                file: source_path.display().to_string(),
             }
+        })
+    }));
+
+    // require_contracts covers every source file a backend reads, sidecar or not
+    if policies.require_contracts.is_some() {
+        let unannotated: Vec<PathBuf> = lang::source_files(&walker)
+            .into_iter()
+            .filter(|source| changes.is_none_or(|c| c.contains(&relative_to(root, source))))
+            .filter(|source| {
+                lang::backend_for(source).is_some_and(|b| !b.sidecar_path(source).exists())
+            })
+            .collect();
+        let outcomes: Vec<SidecarOutcome> = unannotated
+            .par_iter()
+            .map(|source| check.check_unannotated(source))
+            .collect();
+        for outcome in outcomes {
+            errors.extend(outcome.errors);
+            warnings.extend(outcome.warnings);
         }
     }
 
@@ -363,10 +1345,45 @@ pub fn validate_project(root: &Path) -> ValidationReport {
         let consistency_errors = validate_subsystem_consistency(repo, &file_bogs);
         errors.extend(consistency_errors);
 
-        // Skimsystem consistency check
+        // Skimsystem consistency check. Observations come from in-scope sidecars
+        // already; declarations in repo.bog are scoped like other repo-wide errors
         let skim_errors = validate_skimsystem_consistency(repo, &file_bogs);
-        errors.extend(skim_errors);
+        errors.extend(skim_errors.into_iter().filter(|e| e.file().is_some() || in_scope(e)));
+
+        // Globs, files and skim targets that have drifted apart on disk
+        errors.extend(validate_file_coverage(&walker, repo).into_iter().filter(&in_scope));
+    }
+
+    // Request and pickle ids are unique across files, so unchanged sidecars take part
+    let unchanged_bogs: Vec<(String, BogFile)> = unchanged
+        .par_iter()
+        .filter_map(|entry| {
+            let content = std::fs::read_to_string(entry).ok()?;
+            Some((relative_to(root, entry), cache.parse_bog(&content).ok()?))
+        })
+        .collect();
+    let mut parsed: HashMap<String, &BogFile> = file_bogs
+        .iter()
+        .map(|(path, bog)| (format!("{path}.bog"), bog))
+        .chain(unchanged_bogs.iter().map(|(path, bog)| (path.clone(), bog)))
+        .collect();
+    // In walk order, so a duplicate id is reported on the same file as in a full run
+    let mut sidecars: Vec<(String, &BogFile)> = all_sidecars
+        .iter()
+        .filter_map(|entry| {
+            let path = relative_to(root, entry);
+            let bog = parsed.remove(&path)?;
+            Some((path, bog))
+        })
+        .collect();
+    if let Some(repo) = &repo_bog {
+        sidecars.insert(0, ("repo.bog".to_string(), repo));
     }
+    errors.extend(
+        validate_requests_and_pickles(repo_bog.as_ref(), &sidecars)
+            .into_iter()
+            .filter(&in_scope),
+    );
 
     ValidationReport {
         errors,
@@ -0,0 +1,232 @@
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+/// Excluded from every walk: build output, git internals, orchestrator worktrees
+/// and the validation cache.
+pub const DEFAULT_EXCLUDE: &[&str] = &["target/", ".git/", ".bog-worktrees/", ".bog/"];
+
+/// Name of the gitignore-style exclude file read from the project root.
+pub const IGNORE_FILE: &str = ".bogignore";
+
+/// One line of an exclude list, with gitignore semantics: `!` re-includes, a
+/// trailing `/` matches only directories, and a pattern with no other `/` matches
+/// at any depth; otherwise it is relative to the root.
+#[derive(Debug, Clone)]
+struct ExcludeRule {
+    pattern: glob::Pattern,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl ExcludeRule {
+    fn parse(line: &str) -> Option<ExcludeRule> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        Some(ExcludeRule {
+            pattern: glob::Pattern::new(line).ok()?,
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, rel: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..glob::MatchOptions::new()
+        };
+        if self.anchored {
+            self.pattern.matches_with(rel, options)
+        } else {
+            let name = rel.rsplit('/').next().unwrap_or(rel);
+            self.pattern.matches_with(name, options)
+        }
+    }
+}
+
+/// Walks a project's files, skipping excluded paths. Every command that scans
+/// the tree goes through a `Walker`, so they all agree on what is excluded.
+#[derive(Debug, Clone)]
+pub struct Walker {
+    root: PathBuf,
+    rules: Vec<ExcludeRule>,
+}
+
+impl Walker {
+    /// The walker for a project: `DEFAULT_EXCLUDE`, then `exclude` from bog.toml,
+    /// then `.bogignore`. As in gitignore, the last matching rule wins.
+    pub fn new(root: &Path) -> Walker {
+        let mut patterns: Vec<String> = config::load_config(&root.join("bog.toml"))
+            .map(|c| c.exclude)
+            .unwrap_or_default();
+        if let Ok(ignore) = std::fs::read_to_string(root.join(IGNORE_FILE)) {
+            patterns.extend(ignore.lines().map(str::to_string));
+        }
+        Walker::with_patterns(root, &patterns)
+    }
+
+    /// A walker using `DEFAULT_EXCLUDE` followed by `patterns`.
+    pub fn with_patterns<S: AsRef<str>>(root: &Path, patterns: &[S]) -> Walker {
+        let rules = DEFAULT_EXCLUDE
+            .iter()
+            .copied()
+            .chain(patterns.iter().map(AsRef::as_ref))
+            .filter_map(ExcludeRule::parse)
+            .collect();
+        Walker {
+            root: root.to_path_buf(),
+            rules,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether a path is excluded, by its own rules or those of a parent directory.
+    /// `path` may be absolute under the root or relative to it.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let rel = path.strip_prefix(&self.root).unwrap_or(path);
+        let parts: Vec<String> = rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        (1..=parts.len()).any(|n| {
+            let prefix = parts[..n].join("/");
+            self.excludes(&prefix, is_dir || n < parts.len())
+        })
+    }
+
+    fn excludes(&self, rel: &str, is_dir: bool) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|r| r.matches(rel, is_dir))
+            .is_some_and(|r| !r.negated)
+    }
+
+    /// Every file under the root whose name ends with `suffix` (e.g. `.rs.bog`),
+    /// in sorted order. Excluded directories are not entered.
+    pub fn files_with_suffix(&self, suffix: &str) -> Vec<PathBuf> {
+        self.files_where(|name| name.ends_with(suffix))
+    }
+
+    /// Every file under the root whose name satisfies `keep`, in sorted order.
+    pub fn files_where(&self, keep: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+        let mut out = Vec::new();
+        self.walk_dir(&self.root, "", &keep, &mut out);
+        out
+    }
+
+    fn walk_dir(&self, dir: &Path, rel: &str, keep: &dyn Fn(&str) -> bool, out: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().to_string();
+            let child_rel = if rel.is_empty() {
+                name.clone()
+            } else {
+                format!("{rel}/{name}")
+            };
+            // Symlinked directories are not followed, so the walk cannot loop
+            if self.excludes(&child_rel, file_type.is_dir()) {
+                continue;
+            }
+            if file_type.is_dir() {
+                self.walk_dir(&entry.path(), &child_rel, keep, out);
+            } else if keep(&name) {
+                // `.` as root gives `src/a.rs` rather than `./src/a.rs`, as glob does
+                if self.root == Path::new(".") {
+                    out.push(PathBuf::from(child_rel));
+                } else {
+                    out.push(entry.path());
+                }
+            }
+        }
+    }
+
+    /// The files matching a glob relative to the root, such as a subsystem's
+    /// `files` entry, without excluded paths.
+    pub fn glob(&self, pattern: &str) -> Vec<PathBuf> {
+        let full = self.root.join(pattern);
+        let Ok(paths) = glob::glob(&full.to_string_lossy()) else {
+            return Vec::new();
+        };
+        paths
+            .flatten()
+            .filter(|p| p.is_file() && !self.is_excluded(p, false))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclude_rules_follow_gitignore() {
+        let w = Walker::with_patterns(
+            Path::new("/repo"),
+            &["# generated", "gen/", "*.tmp.bog", "/vendor/*", "!vendor/keep.rs"],
+        );
+        assert!(w.is_excluded(Path::new("/repo/target/debug/x.rs"), false));
+        assert!(w.is_excluded(Path::new(".bog-worktrees/run/agent/src/a.rs.bog"), false));
+        assert!(w.is_excluded(Path::new("src/gen/a.rs"), false));
+        assert!(!w.is_excluded(Path::new("src/gen"), false));
+        assert!(w.is_excluded(Path::new("src/a.tmp.bog"), false));
+        assert!(w.is_excluded(Path::new("vendor/lib.rs"), false));
+        assert!(!w.is_excluded(Path::new("vendor/keep.rs"), false));
+        assert!(!w.is_excluded(Path::new("src/vendor/lib.rs"), false));
+        assert!(!w.is_excluded(Path::new("src/main.rs"), false));
+    }
+
+    #[test]
+    fn test_walk_skips_excluded_dirs() {
+        let root = std::env::temp_dir().join(format!("bog-walk-{}", uuid::Uuid::new_v4()));
+        for dir in ["src/nested", ".bog-worktrees/r/a/src", "scratch"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "src/b.rs.bog",
+            "src/a.rs.bog",
+            "src/nested/c.rs.bog",
+            ".bog-worktrees/r/a/src/a.rs.bog",
+            "scratch/d.rs.bog",
+            "src/a.rs",
+        ] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+        std::fs::write(root.join(IGNORE_FILE), "scratch/\n").unwrap();
+
+        let files = Walker::new(&root).files_with_suffix(".rs.bog");
+        let rel: Vec<String> = files
+            .iter()
+            .map(|p| p.strip_prefix(&root).unwrap().to_string_lossy().to_string())
+            .collect();
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(rel, ["src/a.rs.bog", "src/b.rs.bog", "src/nested/c.rs.bog"]);
+    }
+}
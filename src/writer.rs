@@ -0,0 +1,580 @@
+use std::fmt;
+
+use crate::ast::*;
+use crate::formatter;
+
+// Every annotation is first written in a compact single-line form and then laid out by
+// the formatter, so serialized output always matches what `bog fmt` would produce.
+
+/// Quote a string as a single-line .bog string literal, escaping quotes, backslashes
+/// and control characters.
+pub fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            '\0' => out.push_str("\\0"),
+            c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Whether `s` can be written bare and still parse back to the same string.
+fn is_bare_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    let starts_ok = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    starts_ok
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && !matches!(s, "green" | "yellow" | "red" | "true" | "false" | "fn")
+}
+
+/// Whether `s` is an ident or a `::`-separated path of idents.
+fn is_bare_path(s: &str) -> bool {
+    s.split("::").all(is_bare_ident)
+}
+
+/// An ident when that round-trips, a quoted string otherwise.
+fn ident_or_quoted(s: &str) -> String {
+    if is_bare_ident(s) {
+        s.to_string()
+    } else {
+        quote(s)
+    }
+}
+
+/// A bare path when that round-trips, a quoted string otherwise: deps such as
+/// `<Foo as Bar>::baz` are written as strings.
+pub fn path_or_quoted(s: &str) -> String {
+    if is_bare_path(s) {
+        s.to_string()
+    } else {
+        quote(s)
+    }
+}
+
+fn list(items: impl IntoIterator<Item = String>) -> String {
+    format!("[{}]", items.into_iter().collect::<Vec<_>>().join(", "))
+}
+
+/// Ordered `key = value` pairs of a parens list, body or nested block.
+#[derive(Default)]
+struct Fields(Vec<(&'static str, String)>);
+
+impl Fields {
+    fn add(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.0.push((key, value.into()));
+        self
+    }
+
+    fn add_opt(self, key: &'static str, value: Option<String>) -> Self {
+        match value {
+            Some(v) => self.add(key, v),
+            None => self,
+        }
+    }
+
+    fn add_if(self, cond: bool, key: &'static str, value: impl Into<String>) -> Self {
+        if cond { self.add(key, value) } else { self }
+    }
+
+    fn join(&self) -> String {
+        self.0
+            .iter()
+            .map(|(k, v)| format!("{k} = {v}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn parens(&self) -> String {
+        format!("({})", self.join())
+    }
+
+    fn block(&self) -> String {
+        format!("{{ {} }}", self.join())
+    }
+}
+
+/// Lay out compact annotation text, falling back to it verbatim if it does not parse.
+fn write_formatted(f: &mut fmt::Formatter<'_>, compact: &str) -> fmt::Result {
+    match formatter::format_bog(compact) {
+        Ok(formatted) => f.write_str(formatted.trim_end()),
+        Err(_) => f.write_str(compact),
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::String(raw) => f.write_str(raw),
+            Value::Status(s) => write!(f, "{s}"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Number(n) => write!(f, "{n}"),
+            // Debug keeps a decimal point or exponent, so the value re-parses as a float
+            Value::Float(x) => write!(f, "{x:?}"),
+            Value::Date(d) => write!(f, "{d}"),
+            Value::Ident(s) => f.write_str(s),
+            Value::Path(parts) => f.write_str(&parts.join("::")),
+            Value::FnRef(name) => write!(f, "fn({name})"),
+            Value::List(items) => f.write_str(&list(items.iter().map(ToString::to_string))),
+            Value::Tuple(items) => {
+                let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+                write!(f, "({})", items.join(", "))
+            }
+            Value::Block(pairs) => {
+                let pairs: Vec<String> = pairs.iter().map(|(k, v)| format!("{k} = {v}")).collect();
+                write!(f, "{{ {} }}", pairs.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for BogFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, ann) in self.annotations.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n\n")?;
+            }
+            write!(f, "{ann}")?;
+        }
+        if !self.annotations.is_empty() {
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Annotation::Repo(a) => write!(f, "{a}"),
+            Annotation::File(a) => write!(f, "{a}"),
+            Annotation::Description(text) => {
+                if text.is_empty() {
+                    return f.write_str("#[description {}]");
+                }
+                write_formatted(f, &format!("#[description {{\n{text}\n}}]"))
+            }
+            Annotation::Health(a) => write!(f, "{a}"),
+            Annotation::Fn(a) => write!(f, "{a}"),
+            Annotation::Item(a) => write!(f, "{a}"),
+            Annotation::Subsystem(a) => write!(f, "{a}"),
+            Annotation::Skimsystem(a) => write!(f, "{a}"),
+            Annotation::Skim(a) => write!(f, "{a}"),
+            Annotation::Policies(a) => write!(f, "{a}"),
+            Annotation::ChangeRequests(requests) => {
+                if requests.is_empty() {
+                    return f.write_str("#[change_requests {}]");
+                }
+                let inner: Vec<String> = requests.iter().map(ChangeRequest::compact).collect();
+                write_formatted(f, &format!("#[change_requests {{ {} }}]", inner.join(" ")))
+            }
+            Annotation::Pickled(a) => write!(f, "{a}"),
+            Annotation::Schema(a) => write!(f, "{a}"),
+            Annotation::Custom(a) => write!(f, "{a}"),
+            Annotation::Include(pattern) => write!(f, "#[include({})]", quote(pattern)),
+        }
+    }
+}
+
+impl fmt::Display for RepoAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = Fields::default()
+            .add("name", quote(&self.name))
+            .add("version", quote(&self.version))
+            .add("updated", quote(&self.updated));
+        write_formatted(f, &format!("#[repo{}]", fields.parens()))
+    }
+}
+
+impl fmt::Display for FileAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = Fields::default()
+            .add("owner", quote(&self.owner))
+            .add("subsystem", quote(&self.subsystem))
+            .add("updated", quote(&self.updated))
+            .add("status", self.status.to_string());
+        write_formatted(f, &format!("#[file{}]", fields.parens()))
+    }
+}
+
+impl fmt::Display for HealthAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.dimensions.is_empty() {
+            return f.write_str("#[health]");
+        }
+        let mut dims: Vec<(&String, &Status)> = self.dimensions.iter().collect();
+        dims.sort_by(|a, b| a.0.cmp(b.0));
+        let pairs: Vec<String> = dims.iter().map(|(k, v)| format!("{k} = {v}")).collect();
+        write_formatted(f, &format!("#[health({})]", pairs.join(", ")))
+    }
+}
+
+impl fmt::Display for Contract {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|(name, ty)| format!("({}, {})", ident_or_quoted(name), ident_or_quoted(ty)));
+        let fields = Fields::default()
+            .add("in", list(inputs))
+            .add_opt("out", self.output.as_deref().map(quote))
+            .add_if(
+                !self.invariants.is_empty(),
+                "invariants",
+                list(self.invariants.iter().map(|s| quote(s))),
+            );
+        f.write_str(&fields.block())
+    }
+}
+
+impl fmt::Display for FnAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = Fields::default()
+            .add("status", self.status.to_string())
+            .add_if(self.stub, "stub", "true")
+            .add_if(
+                !self.deps.is_empty(),
+                "deps",
+                list(self.deps.iter().map(|d| path_or_quoted(d))),
+            )
+            .add_if(
+                !self.refs.is_empty(),
+                "refs",
+                list(self.refs.iter().map(|r| path_or_quoted(r))),
+            )
+            .add_opt("contract", self.contract.as_ref().map(ToString::to_string))
+            .add_opt("description", self.description.as_deref().map(quote));
+        write_formatted(f, &format!("#[fn({}) {}]", self.name, fields.block()))
+    }
+}
+
+impl fmt::Display for ItemAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = Fields::default()
+            .add("status", self.status.to_string())
+            .add_if(
+                !self.refs.is_empty(),
+                "refs",
+                list(self.refs.iter().map(|r| path_or_quoted(r))),
+            )
+            .add_if(
+                !self.invariants.is_empty(),
+                "invariants",
+                list(self.invariants.iter().map(|s| quote(s))),
+            )
+            .add_opt("description", self.description.as_deref().map(quote));
+        write_formatted(f, &format!("#[{}({}) {}]", self.kind, self.name, fields.block()))
+    }
+}
+
+impl fmt::Display for SubsystemDecl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = Fields::default()
+            .add("owner", quote(&self.owner))
+            .add("files", list(self.files.iter().map(|s| quote(s))))
+            .add("status", self.status.to_string())
+            .add_opt("model", self.model.as_deref().map(quote))
+            .add_opt("description", self.description.as_deref().map(quote));
+        write_formatted(f, &format!("#[subsystem({}) {}]", self.name, fields.block()))
+    }
+}
+
+impl fmt::Display for SkimsystemDecl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let targets = match &self.targets {
+            SkimTargets::All => "all".to_string(),
+            SkimTargets::Named(names) => list(names.iter().map(|n| ident_or_quoted(n))),
+        };
+        let integrations = self
+            .integrations
+            .iter()
+            .map(|spec| {
+                let format = match spec.format {
+                    IntegrationFormat::CargoDiagnostic => "cargo_diagnostic",
+                };
+                let fields = Fields::default()
+                    .add("command", quote(&spec.command))
+                    .add("format", format);
+                format!("{} = {}", spec.name, fields.block())
+            })
+            .collect::<Vec<_>>();
+        let fields = Fields::default()
+            .add("owner", quote(&self.owner))
+            .add("targets", targets)
+            .add("status", self.status.to_string())
+            .add_opt("model", self.model.as_deref().map(quote))
+            .add_if(
+                !self.principles.is_empty(),
+                "principles",
+                list(self.principles.iter().map(|p| quote(p))),
+            )
+            .add_if(
+                !integrations.is_empty(),
+                "integrations",
+                format!("{{ {} }}", integrations.join(", ")),
+            )
+            .add_opt("description", self.description.as_deref().map(quote));
+        write_formatted(f, &format!("#[skimsystem({}) {}]", self.name, fields.block()))
+    }
+}
+
+impl fmt::Display for SkimObservation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let target = self.target.as_ref().map(|t| match t {
+            SkimTarget::File => "file".to_string(),
+            SkimTarget::Fn(name) => format!("fn({name})"),
+        });
+        let fields = Fields::default()
+            .add("status", self.status.to_string())
+            .add_opt("target", target)
+            .add_opt("notes", self.notes.as_deref().map(quote));
+        write_formatted(f, &format!("#[skim({}) {}]", self.skimsystem, fields.block()))
+    }
+}
+
+impl fmt::Display for PoliciesAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.fields.is_empty() {
+            return f.write_str("#[policies {}]");
+        }
+        let mut fields: Vec<(&String, &Value)> = self.fields.iter().collect();
+        fields.sort_by(|a, b| a.0.cmp(b.0));
+        let pairs: Vec<String> = fields.iter().map(|(k, v)| format!("{k} = {v}")).collect();
+        write_formatted(f, &format!("#[policies {{ {} }}]", pairs.join(", ")))
+    }
+}
+
+impl ChangeRequest {
+    fn compact(&self) -> String {
+        let fields = Fields::default()
+            .add("id", quote(&self.id))
+            .add("from", quote(&self.from))
+            .add("target", self.target.to_string())
+            .add("type", ident_or_quoted(&self.change_type))
+            .add("status", ident_or_quoted(&self.status))
+            .add_opt("priority", self.priority.as_deref().map(ident_or_quoted))
+            .add("created", quote(&self.created))
+            .add("description", quote(&self.description));
+        format!("#[request{}]", fields.parens())
+    }
+}
+
+impl fmt::Display for ChangeRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_formatted(f, &self.compact())
+    }
+}
+
+impl fmt::Display for PickledAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parens = Fields::default()
+            .add("agent", quote(&self.agent))
+            .add("updated", quote(&self.updated));
+        let body = Fields::default()
+            .add("id", quote(&self.id))
+            .add("kind", self.kind.to_string())
+            .add_opt("supersedes", self.supersedes.as_deref().map(quote))
+            .add_if(
+                !self.tags.is_empty(),
+                "tags",
+                list(self.tags.iter().map(ToString::to_string)),
+            )
+            .add("content", quote(&self.content));
+        write_formatted(
+            f,
+            &format!("#[pickled{} {}]", parens.parens(), body.block()),
+        )
+    }
+}
+
+impl fmt::Display for AnnotationSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let group = |required: bool| {
+            let pairs: Vec<String> = self
+                .fields
+                .iter()
+                .filter(|spec| spec.required == required)
+                .map(|spec| format!("{} = {}", spec.name, spec.ty))
+                .collect();
+            (!pairs.is_empty()).then(|| format!("{{ {} }}", pairs.join(", ")))
+        };
+        let fields = Fields::default()
+            .add_opt("required", group(true))
+            .add_opt("optional", group(false))
+            .add_opt("description", self.description.as_deref().map(quote));
+        if fields.0.is_empty() {
+            return write!(f, "#[annotation({})]", self.kind);
+        }
+        write_formatted(f, &format!("#[annotation({}) {}]", self.kind, fields.block()))
+    }
+}
+
+impl fmt::Display for CustomAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs: Vec<String> = self.fields.iter().map(|(k, v)| format!("{k} = {v}")).collect();
+        let pairs = pairs.join(", ");
+        // The grammar allows an ident or key/values in parens, and key/values or text
+        // in the body, so fields go in the body only when the parens hold the name.
+        let mut out = format!("#[{}", self.kind);
+        match &self.name {
+            Some(name) => out.push_str(&format!("({})", path_or_quoted(name))),
+            None if !pairs.is_empty() => out.push_str(&format!("({pairs})")),
+            None => {}
+        }
+        match &self.text {
+            Some(text) => out.push_str(&format!(" {{\n{text}\n}}")),
+            None if self.name.is_some() && !pairs.is_empty() => {
+                out.push_str(&format!(" {{ {pairs} }}"));
+            }
+            None => {}
+        }
+        out.push(']');
+        write_formatted(f, &out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_bog;
+
+    fn roundtrip(bog: &BogFile) {
+        let text = bog.to_string();
+        let reparsed = parse_bog(&text).unwrap_or_else(|e| panic!("{e}\n{text}"));
+        assert_eq!(&reparsed, bog, "\n{text}");
+        assert_eq!(formatter::format_bog(&text).unwrap(), text);
+    }
+
+    #[test]
+    fn test_writer_roundtrips_every_variant() {
+        let input = r#"
+#[repo(name = "demo", version = "0.1.0", updated = "2026-01-01")]
+#[file(owner = "a", subsystem = "core", updated = "2026-01-01", status = green)]
+#[description {
+  First line.
+    Indented second line.
+}]
+#[health(test_coverage = green, staleness = red)]
+#[fn(login) {
+  status = yellow, stub = true, deps = [db::get_user, verify], refs = [routes::post],
+  contract = { in = [(name, "&str"), (n, usize)], out = "Result<(), Error>", invariants = ["never panics"] },
+  description = "Logs a \"user\" in"
+}]
+#[fn(<Auth as Default>::default) { status = green, deps = [Auth::new] }]
+#[type(Token) { status = green, refs = [auth::login], invariants = ["never expired"], description = "d" }]
+#[trait(Verifier) { status = yellow }]
+#[const(MAX_USERS) { status = red }]
+#[subsystem(core) { owner = "a", files = ["src/*.rs"], status = green, model = "m", description = "d" }]
+#[skimsystem(quality) {
+  owner = "q", targets = [core, cli], status = yellow, principles = ["one"],
+  integrations = { clippy = { command = "cargo clippy", format = cargo_diagnostic } }
+}]
+#[skim(quality) { target = fn(login), status = red, notes = "path C:\\tmp" }]
+#[policies { max_fn_lines = 50, require_contracts = true }]
+#[change_requests {
+  #[request(id = "cr-1", from = "b", target = fn(login), type = lint_warning, status = pending, priority = high, created = "2026-01-01", description = "uses \"quotes\"")]
+  #[request(id = "cr-2", from = "b", target = file, type = "needs review", status = "in progress", created = "2026-01-01", description = "x")]
+}]
+#[pickled(agent = "a", updated = "2026-01-01") {
+  id = "p1", kind = decision, supersedes = "p0", tags = [architecture, testing], content = "Keep it \"simple\""
+}]
+"#;
+        let bog = parse_bog(input).unwrap();
+        assert_eq!(bog.annotations.len(), 15);
+        roundtrip(&bog);
+    }
+
+    #[test]
+    fn test_writer_roundtrips_custom_annotations() {
+        let input = r#"
+#[annotation(runbook) { required = { url = string }, optional = { oncall = ident } }]
+#[annotation(marker)]
+#[runbook(deploy) { url = "https://wiki/deploy", oncall = platform }]
+#[runbook(Auth::verify) { url = "https://wiki/verify" }]
+#[slo(target = 999, window = "30d")]
+#[feature_flag(checkout_v2) {
+  Gates the new checkout flow.
+}]
+#[marker]
+#[include("subsystems/*.bog")]
+"#;
+        let bog = parse_bog(input).unwrap();
+        assert_eq!(bog.annotations.len(), 8);
+        roundtrip(&bog);
+        assert!(bog.to_string().contains("#[runbook(Auth::verify) {"));
+    }
+
+    #[test]
+    fn test_writer_roundtrips_rich_values() {
+        let input = r##"
+#[pickled(agent = "a", updated = 2026-01-02) {
+  id = "p1", kind = context, content = """
+    Multi-line
+      content
+  """
+}]
+#[policies { ratio = -0.5, big = 1e9, whole = 2.0, since = 2026-03-01, raw = r#"a\b "c""#, esc = "x\ty" }]
+"##;
+        let bog = parse_bog(input).unwrap();
+        roundtrip(&bog);
+        let text = bog.to_string();
+        assert!(text.contains(r#"content = "Multi-line\n  content""#), "{text}");
+        assert!(text.contains("whole = 2.0"), "{text}");
+    }
+
+    #[test]
+    fn test_writer_escapes_constructed_strings() {
+        let bog = BogFile {
+            annotations: vec![
+                Annotation::Fn(FnAnnotation {
+                    name: "f".to_string(),
+                    status: Status::Green,
+                    stub: false,
+                    deps: vec!["green".to_string(), "a::b".to_string()],
+                    refs: Vec::new(),
+                    contract: None,
+                    description: Some(r#"say "hi" \ bye"#.to_string()),
+                }),
+                Annotation::ChangeRequests(vec![ChangeRequest {
+                    id: "x".to_string(),
+                    from: "o".to_string(),
+                    target: Value::Ident("file".to_string()),
+                    change_type: "red".to_string(),
+                    status: "pending".to_string(),
+                    priority: None,
+                    created: "2026-01-01".to_string(),
+                    description: "a \"b\"".to_string(),
+                }]),
+            ],
+        };
+        roundtrip(&bog);
+        assert!(bog.to_string().contains(r#"description = "say \"hi\" \\ bye""#));
+    }
+
+    #[test]
+    fn test_writer_layout_matches_repo_style() {
+        let pickled = PickledAnnotation {
+            id: "p1".to_string(),
+            agent: "core-agent".to_string(),
+            updated: "2026-02-26".to_string(),
+            kind: PickledKind::Context,
+            supersedes: None,
+            tags: vec![PickledTag::Domain],
+            content: "c".to_string(),
+        };
+        assert_eq!(
+            pickled.to_string(),
+            "#[pickled(agent = \"core-agent\", updated = \"2026-02-26\") {\n  id = \"p1\",\n  kind = context,\n  tags = [domain],\n  content = \"c\"\n}]"
+        );
+    }
+}
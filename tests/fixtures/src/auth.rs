@@ -5,3 +5,7 @@ pub fn login(username: &str, password: &str) -> Result<String, String> {
 pub fn logout(token: &str) {
     let _ = token;
 }
+
+pub struct Session {
+    pub token: String,
+}
@@ -1,18 +1,61 @@
 use std::path::{Path, PathBuf};
 
-use bog::ast::{Annotation, IntegrationFormat};
+use bog::ast::{Annotation, IntegrationFormat, ItemKind, Status};
+use bog::cache::{self, Cache};
+use bog::changes::{ChangeSet, DiffBase};
 use bog::config;
 use bog::context;
+use bog::contract;
+use bog::deps;
+use bog::cst;
+use bog::export;
+use bog::fix;
+use bog::formatter;
 use bog::health;
 use bog::parser;
+use bog::report;
+use bog::stub;
 use bog::treesitter;
 use bog::validator;
+use bog::walk;
 
 /// Resolve the project root from CARGO_MANIFEST_DIR.
 fn workspace_root() -> PathBuf {
     Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf()
 }
 
+/// A scratch project under the system temp dir, removed on drop so a failing
+/// assertion doesn't leave it behind.
+struct TempProject(PathBuf);
+
+impl TempProject {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("bog-{name}-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        TempProject(root)
+    }
+}
+
+impl std::ops::Deref for TempProject {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempProject {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempProject {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
 // --- Config ---
 
 #[test]
@@ -73,8 +116,8 @@ fn test_fixture_bog_parsing() {
     let root = workspace_root();
     let content = std::fs::read_to_string(root.join("tests/fixtures/src/auth.rs.bog")).unwrap();
     let bog = parser::parse_bog(&content).unwrap();
-    // file, description, health, skim(tracing), fn(login), fn(logout)
-    assert_eq!(bog.annotations.len(), 6);
+    // file, description, health, skim(tracing), fn(login), fn(logout), type(Session)
+    assert_eq!(bog.annotations.len(), 7);
 }
 
 // --- Tree-sitter ---
@@ -96,8 +139,14 @@ fn test_validate_functions_match() {
     let root = workspace_root();
     let bog_path = root.join("tests/fixtures/src/auth.rs.bog");
     let source_path = root.join("tests/fixtures/src/auth.rs");
-    let bog = validator::validate_syntax(&bog_path).unwrap();
-    let errors = validator::validate_functions(&bog_path, &bog, &source_path);
+    let bog = validator::validate_syntax(&bog_path, &Cache::disabled()).unwrap();
+    let errors = validator::validate_functions(
+        &bog_path,
+        &bog,
+        &source_path,
+        &deps::DepIgnore::default(),
+        &Cache::disabled(),
+    );
     assert!(errors.is_empty(), "Expected no errors, got: {errors:?}");
 }
 
@@ -119,7 +168,13 @@ fn test_validate_functions_catches_mismatch() {
     let root = workspace_root();
     let source_path = root.join("tests/fixtures/src/auth.rs");
     let bog_path = root.join("test.rs.bog");
-    let errors = validator::validate_functions(&bog_path, &bog, &source_path);
+    let errors = validator::validate_functions(
+        &bog_path,
+        &bog,
+        &source_path,
+        &deps::DepIgnore::default(),
+        &Cache::disabled(),
+    );
     assert_eq!(errors.len(), 1);
     match &errors[0] {
         validator::ValidationError::MissingFunction { function, .. } => {
@@ -129,6 +184,292 @@ fn test_validate_functions_catches_mismatch() {
     }
 }
 
+#[test]
+fn test_validate_items_catch_drift() {
+    let input = r#"
+#[type(Session) { status = green }]
+#[type(Token) { status = green }]
+#[trait(Session) { status = green }]
+#[fn(Session) { status = green }]
+#[const(login) { status = green }]
+"#;
+    let bog = parser::parse_bog(input).unwrap();
+    let root = workspace_root();
+    let source_path = root.join("tests/fixtures/src/auth.rs");
+    let bog_path = root.join("test.rs.bog");
+    let errors = validator::validate_functions(
+        &bog_path,
+        &bog,
+        &source_path,
+        &deps::DepIgnore::default(),
+        &Cache::disabled(),
+    );
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(errors.len(), 4, "{messages:?}");
+    assert!(matches!(
+        &errors[0],
+        validator::ValidationError::MissingItem { kind: ItemKind::Type, name, .. } if name == "Token"
+    ));
+    assert!(matches!(
+        &errors[1],
+        validator::ValidationError::ItemKindMismatch { kind: ItemKind::Trait, found: "struct", .. }
+    ));
+    assert!(matches!(
+        &errors[2],
+        validator::ValidationError::MissingFunction { function, .. } if function == "Session"
+    ));
+    assert!(messages[3].contains("const 'login' declared in .bog but not found"));
+}
+
+#[test]
+fn test_validate_contract_drift() {
+    let input = r#"
+#[fn(login) {
+  status = green,
+  contract = { in = [(user, "&str"), (password, String)], out = "Result<String, String>" }
+}]
+#[fn(logout) {
+  status = green,
+  contract = { in = [(token, "& str")], out = "()" }
+}]
+"#;
+    let bog = parser::parse_bog(input).unwrap();
+    let root = workspace_root();
+    let source_path = root.join("tests/fixtures/src/auth.rs");
+    let bog_path = root.join("test.rs.bog");
+    let errors = validator::validate_functions(
+        &bog_path,
+        &bog,
+        &source_path,
+        &deps::DepIgnore::default(),
+        &Cache::disabled(),
+    );
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(errors.len(), 2, "{messages:?}");
+    assert!(messages[0].contains("parameter 'user' was renamed to 'username'"));
+    assert!(messages[1].contains("'password' is '&str' in source but 'String' in the contract"));
+
+    // One of two contracts drifted: compliance is yellow, not red
+    let source = std::fs::read_to_string(&source_path).unwrap();
+    let symbols = treesitter::extract_symbols(&source).unwrap();
+    assert_eq!(contract::compliance(&bog, &symbols), Some(Status::Yellow));
+}
+
+#[test]
+fn test_validate_warns_on_dep_drift_and_fix_rewrites() {
+    let root = TempProject::new("deps");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("bog.toml"),
+        "[bog]\nversion = \"0.1.0\"\n\n[deps]\nignore = [\"log::*\"]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/lib.rs"),
+        "pub struct Db;\n\nimpl Db {\n    pub fn commit(&self) {}\n}\n\npub fn retry() {}\n\n\
+         pub fn sync(db: &Db) {\n    log::info();\n    store::save();\n    db.commit();\n    \
+         let _ = Vec::new();\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        "// keep me\n#[fn(sync) {\n  status = green,\n  deps = [retry, Db::commit]\n}]\n",
+    )
+    .unwrap();
+
+    let report = validator::validate_project(&root);
+    let dep_warnings: Vec<String> = report
+        .warnings
+        .iter()
+        .filter(|w| w.code() == "BOG901" || w.code() == "BOG902")
+        .map(|w| w.to_string())
+        .collect();
+    assert_eq!(dep_warnings.len(), 3, "{:?}", report.warnings);
+    assert!(dep_warnings[0].contains("fn 'sync' lists dep 'retry' but no longer calls it"));
+    // Method calls are not extracted, so a correct dep on one looks stale too
+    assert!(dep_warnings[1].contains("fn 'sync' lists dep 'Db::commit' but no longer calls it"));
+    assert!(dep_warnings[2].contains("fn 'sync' calls 'store::save', which is not in its deps"));
+
+    let fix = fix::fix_project(&root, &deps::load_ignore(&root), None);
+    let sidecar = std::fs::read_to_string(root.join("src/lib.rs.bog")).unwrap();
+    let after = validator::validate_project(&root);
+
+    assert!(fix.failed.is_empty());
+    assert_eq!(fix.fixed.len(), 1);
+    assert_eq!(
+        sidecar,
+        "// keep me\n#[fn(sync) {\n  status = green,\n  deps = [Db::commit, store::save]\n}]\n"
+    );
+    let left: Vec<String> = after
+        .warnings
+        .iter()
+        .map(|w| w.to_string())
+        .filter(|w| w.contains("fn 'sync'"))
+        .collect();
+    assert_eq!(left.len(), 1, "{left:?}");
+    assert!(left[0].contains("lists dep 'Db::commit'"));
+}
+
+#[test]
+fn test_fix_renames_functions_and_corrects_owners() {
+    let root = TempProject::new("fix");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "fix", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(store) { owner = "store-agent", files = ["src/*.rs"], status = green }]
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/lib.rs"),
+        "pub fn open() {}\n\npub fn save_all(items: &[Item], force: bool) -> usize {\n    \
+         disk::write(items, force)\n}\n\npub fn close() {}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        r#"#[file(owner = "someone-else", subsystem = "store", updated = "2026-01-01", status = green)]
+#[fn(open) { status = green }]
+#[fn(persist) {
+  status = green,
+  deps = [disk::write],
+  contract = { in = [(items, "&[Item]"), (force, bool)], out = "usize" }
+}]
+#[fn(close) { status = green }]
+"#,
+    )
+    .unwrap();
+
+    let before = validator::validate_project(&root);
+    // A diff that does not touch lib.rs leaves its sidecar alone
+    let elsewhere = ChangeSet::new(&["src/other.rs"]);
+    let scoped = fix::fix_project(&root, &deps::load_ignore(&root), Some(&elsewhere));
+    let untouched = std::fs::read_to_string(root.join("src/lib.rs.bog")).unwrap();
+    let fix = fix::fix_project(&root, &deps::load_ignore(&root), None);
+    let sidecar = std::fs::read_to_string(root.join("src/lib.rs.bog")).unwrap();
+    let after = validator::validate_project(&root);
+
+    let messages: Vec<String> = before.errors.iter().map(ToString::to_string).collect();
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("function 'persist'") && m.contains("renamed to 'save_all'?")),
+        "{messages:?}"
+    );
+    assert!(messages.iter().any(|m| m.contains("owner 'someone-else' does not match")));
+    assert!(scoped.fixed.is_empty() && scoped.failed.is_empty());
+    assert!(untouched.contains("#[fn(persist)"));
+    assert!(fix.failed.is_empty());
+    assert_eq!(fix.fixed.len(), 1);
+    let fixes: Vec<String> = fix.fixed[0].1.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        fixes,
+        ["owner 'someone-else' -> 'store-agent'", "renamed fn(persist) to fn(save_all)"]
+    );
+    assert!(sidecar.starts_with("#[file(owner = \"store-agent\", subsystem = \"store\""));
+    assert!(sidecar.contains("#[fn(save_all) {\n  status = green,\n  deps = [disk::write],"));
+    assert!(after.errors.is_empty(), "{:?}", after.errors);
+}
+
+#[test]
+fn test_validate_enforces_policies_at_their_level() {
+    let root = TempProject::new("policy");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "p", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "core-agent", files = ["src/*.rs"], status = green }]
+#[policies {
+  require_contracts = true,
+  require_owner = error,
+  health_thresholds = { stale_after_days = 36500 }
+}]
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("src/lib.rs"), "pub fn open() {}
+fn helper() {}
+").unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        "#[file(owner = \"core-agent\", subsystem = \"core\", updated = \"2026-01-01\", status = green)]\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("src/util.rs"), "fn x() {}
+").unwrap();
+    std::fs::write(root.join("src/util.rs.bog"), "#[description { no header }]\n").unwrap();
+    // A source file without a sidecar still needs contracts
+    std::fs::write(root.join("src/bare.rs"), "pub fn close() {}\n").unwrap();
+
+    let report = validator::validate_project(&root);
+
+    let policy_errors: Vec<&validator::ValidationError> = report
+        .errors
+        .iter()
+        .filter(|e| matches!(e, validator::ValidationError::Policy { .. }))
+        .collect();
+    assert_eq!(policy_errors.len(), 1, "{:?}", report.errors);
+    assert!(policy_errors[0].to_string().contains("util.rs.bog: policy 'require_owner'"));
+    let contract_warnings: Vec<String> = report
+        .warnings
+        .iter()
+        .filter(|w| w.code() == "BOG906")
+        .map(|w| w.to_string())
+        .collect();
+    assert_eq!(contract_warnings.len(), 2, "{contract_warnings:?}");
+    assert!(contract_warnings[0].contains("lib.rs.bog: policy 'require_contracts': public fn 'open' has no contract"));
+    assert!(contract_warnings[1].contains("bare.rs: policy 'require_contracts': public fn 'close' has no contract"));
+    assert!(!report.warnings.iter().any(|w| w.code() == "BOG909"));
+}
+
+#[test]
+fn test_validate_qualified_fn_refs_and_ambiguity() {
+    let dir = TempProject::new("qualified");
+    let source_path = dir.join("lib.rs");
+    std::fs::write(
+        &source_path,
+        r#"
+struct Auth;
+struct Session;
+impl Auth { fn new() -> Self { Auth } }
+impl Session { fn new() -> Self { Session } }
+impl Default for Auth { fn default() -> Self { Auth } }
+"#,
+    )
+    .unwrap();
+    let bog = parser::parse_bog(
+        r#"
+#[fn(new) { status = green }]
+#[fn(Auth::new) { status = green }]
+#[fn(<Auth as Default>::default) { status = green }]
+#[fn(<Session as Default>::default) { status = green }]
+#[skim(q) { target = fn(new), status = red }]
+"#,
+    )
+    .unwrap();
+    let bog_path = dir.join("lib.rs.bog");
+    let cache = Cache::disabled();
+    let ignore = deps::DepIgnore::default();
+    let errors = validator::validate_functions(&bog_path, &bog, &source_path, &ignore, &cache);
+    let skim_errors = validator::validate_skim_targets(&bog_path, &bog, &source_path, &cache);
+
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    match &errors[0] {
+        validator::ValidationError::AmbiguousFunction { function, candidates, .. } => {
+            assert_eq!(function, "new");
+            assert_eq!(candidates, &["Auth::new", "Session::new"]);
+        }
+        other => panic!("expected AmbiguousFunction, got {other}"),
+    }
+    assert!(matches!(
+        &errors[1],
+        validator::ValidationError::MissingFunction { function, .. }
+            if function == "<Session as Default>::default"
+    ));
+    assert!(matches!(&skim_errors[..], [validator::ValidationError::AmbiguousFunction { .. }]));
+}
+
 // --- Dogfood: bog validates itself ---
 
 #[test]
@@ -169,6 +510,139 @@ fn test_dogfood_health() {
     assert!(names.contains(&"cli"));
     assert!(names.contains(&"orchestrate"));
     assert!(names.contains(&"test-fixtures"));
+
+    // contract_compliance is computed, and bog's own contracts match its signatures
+    for sub in &health.subsystems {
+        if let Some(counts) = sub.dimensions.get("contract_compliance") {
+            assert_eq!(counts.red + counts.yellow, 0, "drift in {}", sub.name);
+        }
+    }
+}
+
+/// Run git in `dir` with both commit dates pinned to `date`.
+fn git_at(dir: &Path, date: &str, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_AUTHOR_DATE", format!("{date}T12:00:00Z"))
+        .env("GIT_COMMITTER_DATE", format!("{date}T12:00:00Z"))
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success(), "git {args:?} failed");
+}
+
+#[test]
+fn test_health_staleness_from_git_history() {
+    let root = TempProject::new("stale");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "s", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "core-agent", files = ["src/*.rs"], status = green }]
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("src/lib.rs"), "fn a() {}\n\nfn b() {\n    a();\n}\n").unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+#[health(staleness = green)]
+#[fn(a) { status = green }]
+#[fn(b) { status = green, deps = [a] }]
+"#,
+    )
+    .unwrap();
+    git_at(&root, "2026-01-01", &["init", "-q"]);
+    git_at(&root, "2026-01-01", &["add", "."]);
+    git_at(&root, "2026-01-01", &["commit", "-q", "-m", "init"]);
+    std::fs::write(root.join("src/lib.rs"), "fn a() {}\n\nfn b() {\n    a();\n    a();\n}\n")
+        .unwrap();
+    git_at(&root, "2026-01-10", &["commit", "-q", "-am", "change b"]);
+
+    let health = health::compute_health(&root);
+
+    assert_eq!(health.stale_files.len(), 1);
+    let (status, stale) = &health.stale_files[0];
+    assert_eq!(*status, Status::Yellow);
+    assert_eq!(stale.bog_path, Path::new("src/lib.rs.bog"));
+    assert_eq!(stale.lag_days(), 9);
+    let names: Vec<&str> = stale.stale_fns.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["b"]);
+    // The computed value replaces the hand-written green
+    let counts = &health.subsystems[0].dimensions["staleness"];
+    assert_eq!((counts.green, counts.yellow), (0, 1));
+    assert!(health::format_health_report(&health).contains("Changed since annotated: b"));
+}
+
+#[test]
+fn test_validate_changed_is_scoped_to_the_diff() {
+    let root = TempProject::new("changed");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "c", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "core-agent", files = ["src/a.rs", "src/b.rs"], status = green }]
+#[skimsystem(q) { owner = "q-agent", targets = [nonexistent], status = green }]
+"#,
+    )
+    .unwrap();
+    let header = r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]"#;
+    std::fs::write(root.join("src/a.rs"), "fn a() {}\n").unwrap();
+    // Already broken before the diff, as is the skimsystem, so a scoped run must not report it
+    std::fs::write(
+        root.join("src/a.rs.bog"),
+        format!(
+            "{header}\n#[fn(old) {{ status = green }}]\n#[change_requests {{\n  \
+             #[request(id = \"r1\", from = \"core-agent\", target = fn(a), type = lint_warning, \
+             status = pending, created = \"2026-01-01\", description = \"x\")]\n}}]\n"
+        ),
+    )
+    .unwrap();
+    std::fs::write(root.join("src/b.rs"), "fn b() {}\n").unwrap();
+    std::fs::write(root.join("src/b.rs.bog"), format!("{header}\n#[fn(b) {{ status = green }}]\n"))
+        .unwrap();
+    git_at(&root, "2026-01-01", &["init", "-q"]);
+    git_at(&root, "2026-01-01", &["add", "."]);
+    git_at(&root, "2026-01-01", &["commit", "-q", "-m", "init"]);
+
+    // Rename b in source, reuse a's request id, and add an unowned file
+    std::fs::write(root.join("src/b.rs"), "fn b2() {}\n").unwrap();
+    std::fs::write(
+        root.join("src/b.rs.bog"),
+        format!(
+            "{header}\n#[fn(b) {{ status = green }}]\n#[change_requests {{\n  \
+             #[request(id = \"r1\", from = \"core-agent\", target = fn(b), type = lint_warning, \
+             status = pending, created = \"2026-01-01\", description = \"y\")]\n}}]\n"
+        ),
+    )
+    .unwrap();
+    std::fs::write(root.join("src/c.rs"), "fn c() {}\n").unwrap();
+    let since = ChangeSet::from_git(&root, &DiffBase::Since("HEAD".to_string())).unwrap();
+    let scoped = validator::validate_changed(&root, &since);
+    git_at(&root, "2026-01-02", &["add", "src/b.rs"]);
+    let staged = ChangeSet::from_git(&root, &DiffBase::Staged).unwrap();
+    let staged_report = validator::validate_changed(&root, &staged);
+    let full = validator::validate_project(&root);
+
+    assert_eq!(
+        since.paths().collect::<Vec<_>>(),
+        ["src/b.rs", "src/b.rs.bog", "src/c.rs"]
+    );
+    let messages: Vec<String> = scoped.errors.iter().map(ToString::to_string).collect();
+    assert_eq!(scoped.files_checked, 1);
+    assert_eq!(messages.len(), 4, "{messages:?}");
+    assert!(messages[0].contains("function 'b' declared in .bog but not found"));
+    assert!(messages[1].contains("change request 'r1' targets fn 'b' not found"));
+    assert!(messages[2].contains("src/c.rs: source file is not matched by any subsystem"));
+    assert!(messages[3].contains("In src/b.rs.bog: change request id 'r1' is already used"));
+    assert!(!messages.iter().any(|m| m.contains("'old'") || m.contains("nonexistent")));
+
+    assert_eq!(staged.paths().collect::<Vec<_>>(), ["src/b.rs"]);
+    assert_eq!(staged_report.errors.len(), 3, "{:?}", staged_report.errors);
+    assert!(full.errors.iter().any(|e| e.to_string().contains("'old'")));
+    assert!(full.errors.iter().any(|e| e.to_string().contains("nonexistent")));
 }
 
 #[test]
@@ -339,6 +813,7 @@ fn test_context_section_filter() {
         health: false,
         contracts: false,
         skims: false,
+        custom: false,
     };
     let output = context::load_context(
         &root,
@@ -386,3 +861,761 @@ fn test_dogfood_every_subsystem_has_files() {
         );
     }
 }
+
+#[test]
+fn test_validate_orphaned_globs_and_unowned_files() {
+    let root = TempProject::new("orphans");
+    std::fs::create_dir_all(root.join("src/net")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "o", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "core-agent", files = ["src/*.rs", "src/deleted.rs"], status = green }]
+#[subsystem(lib) { owner = "lib-agent", files = ["src/lib.rs"], status = green }]
+#[subsystem(gone) { owner = "gone-agent", files = ["src/gone/*.rs"], status = green }]
+#[skimsystem(review) { owner = "review-agent", targets = [core, gone], status = green }]
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("src/lib.rs"), "").unwrap();
+    std::fs::write(root.join("src/main.rs"), "").unwrap();
+    std::fs::write(root.join("src/net/http.rs"), "").unwrap();
+    // src/*.rs does not reach into src/net, so core does not own this file either
+    std::fs::write(
+        root.join("src/net/http.rs.bog"),
+        r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+"#,
+    )
+    .unwrap();
+
+    let report = validator::validate_project(&root);
+
+    let mut messages: Vec<String> = report
+        .errors
+        .iter()
+        .filter(|e| {
+            matches!(
+                e,
+                validator::ValidationError::UnmatchedSubsystemGlob { .. }
+                    | validator::ValidationError::UnownedFile { .. }
+                    | validator::ValidationError::FileInMultipleSubsystems { .. }
+                    | validator::ValidationError::SkimTargetOwnsNoFiles { .. }
+                    | validator::ValidationError::FileNotInSubsystem { .. }
+            )
+        })
+        .map(|e| e.to_string())
+        .collect();
+    messages.sort();
+    assert_eq!(
+        messages,
+        [
+            "In src/lib.rs: file is matched by more than one subsystem: core, lib",
+            "In src/net/http.rs: file path does not match any glob in subsystem 'core'",
+            "In src/net/http.rs: source file is not matched by any subsystem",
+            "Skimsystem 'review' targets subsystem 'gone', which owns no files",
+            "Subsystem 'core' files glob 'src/deleted.rs' matches no files",
+            "Subsystem 'gone' files glob 'src/gone/*.rs' matches no files",
+        ]
+    );
+}
+
+#[test]
+fn test_validate_change_requests_and_pickled_integrity() {
+    let root = TempProject::new("requests");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "r", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "core-agent", files = ["src/*.rs"], status = green }]
+#[pickled(agent = "core-agent", updated = "2026-01-01") {
+  id = "p1", kind = decision, content = "Start"
+}]
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("src/lib.rs"), "fn a() {}\n").unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+#[fn(a) { status = green }]
+#[change_requests {
+  #[request(id = "r1", from = "core-agent", target = fn(a), type = refactor,
+    status = open, created = "2026-01-01", description = "x")]
+  #[request(id = "r1", from = "ghost-agent", target = fn(gone), type = refactor,
+    status = resolved, created = "2026-01-01", description = "y")]
+}]
+#[pickled(agent = "core-agent", updated = "2026-01-02") {
+  id = "p2", kind = reversal, supersedes = "p9", content = "Dangling"
+}]
+#[pickled(agent = "core-agent", updated = "2026-01-02") {
+  id = "p3", kind = reversal, supersedes = "p4", content = "Loop"
+}]
+#[pickled(agent = "core-agent", updated = "2026-01-02") {
+  id = "p4", kind = reversal, supersedes = "p3", content = "Loop"
+}]
+#[pickled(agent = "ghost-agent", updated = "2026-01-02") {
+  id = "p1", kind = context, content = "Copy"
+}]
+"#,
+    )
+    .unwrap();
+
+    let report = validator::validate_project(&root);
+
+    let mut messages: Vec<String> = report.errors.iter().map(|e| e.to_string()).collect();
+    messages.sort();
+    let sidecar = root.join("src/lib.rs.bog").display().to_string();
+    let mut expected = vec![
+        "In src/lib.rs.bog: change request 'r1' has status 'open'; expected one of: \
+         pending, accepted, in_progress, resolved, denied"
+            .to_string(),
+        "In src/lib.rs.bog: change request id 'r1' is already used in src/lib.rs.bog".to_string(),
+        "In src/lib.rs.bog: change request 'r1' names agent 'ghost-agent', which owns no \
+         subsystem or skimsystem"
+            .to_string(),
+        format!("In {sidecar}: change request 'r1' targets fn 'gone' not found in source"),
+        "In src/lib.rs.bog: pickled entry 'p2' supersedes unknown entry 'p9'".to_string(),
+        "In src/lib.rs.bog: supersedes chain forms a cycle: p3 -> p4 -> p3".to_string(),
+        "In src/lib.rs.bog: pickled entry id 'p1' is already used in repo.bog".to_string(),
+        "In src/lib.rs.bog: pickled entry 'p1' names agent 'ghost-agent', which owns no \
+         subsystem or skimsystem"
+            .to_string(),
+    ];
+    expected.sort();
+    assert_eq!(messages, expected);
+}
+
+#[test]
+fn test_validate_report_formats_locate_errors() {
+    let root = TempProject::new("report");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "x", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "core-agent", files = ["src/*.rs"], status = green }]
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("src/lib.rs"), "fn a() {}\n").unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        "#[file(owner = \"core-agent\", subsystem = \"core\", updated = \"2026-01-01\", status = green)]\n\
+         #[fn(a) { status = green }]\n\n#[fn(gone) {\n  status = green\n}]\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("src/bad.rs.bog"), "#[fn(x) { status = gren }]\n").unwrap();
+    std::fs::write(root.join("src/bad.rs"), "fn x() {}\n").unwrap();
+
+    let validation = validator::validate_project(&root);
+    let json = report::render(&validation, &root, report::ReportFormat::Json).unwrap();
+    let sarif = report::render(&validation, &root, report::ReportFormat::Sarif).unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let diagnostics = json["diagnostics"].as_array().unwrap();
+    let find = |code: &str| diagnostics.iter().find(|d| d["code"] == code).unwrap();
+    let parse = find("BOG001");
+    assert_eq!(parse["file"], "src/bad.rs.bog");
+    assert_eq!(parse["severity"], "error");
+    assert_eq!(parse["span"]["start_line"], 1);
+    let missing = find("BOG002");
+    assert_eq!(missing["file"], "src/lib.rs.bog");
+    assert_eq!(missing["span"]["start_line"], 4);
+    assert_eq!(missing["span"]["end_line"], 6);
+    assert_eq!(json["errors"], validation.errors.len());
+
+    let sarif: serde_json::Value = serde_json::from_str(&sarif).unwrap();
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), diagnostics.len());
+    assert!(results.iter().any(|r| r["ruleId"] == "BOG002"
+        && r["locations"][0]["physicalLocation"]["region"]["startLine"] == 4));
+}
+
+#[test]
+fn test_parallel_scans_report_in_walk_order() {
+    let root = TempProject::new("parallel");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "x", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "core-agent", files = ["src/*.rs"], status = green }]
+"#,
+    )
+    .unwrap();
+    let mut names: Vec<String> = (0..40).map(|i| format!("m{i}")).collect();
+    for name in &names {
+        std::fs::write(root.join(format!("src/{name}.rs")), "fn here() {}\nfn bare() {}\n")
+            .unwrap();
+        std::fs::write(
+            root.join(format!("src/{name}.rs.bog")),
+            "#[file(owner = \"core-agent\", subsystem = \"core\", updated = \"2026-01-01\", status = green)]\n\
+             #[fn(here) { status = green }]\n#[fn(gone) { status = green }]\n",
+        )
+        .unwrap();
+    }
+    names.sort();
+
+    let first = validator::validate_project(&root);
+    let second = validator::validate_project(&root);
+    let missing = stub::find_missing_annotations(&root);
+
+    let messages = |report: &validator::ValidationReport| -> Vec<String> {
+        report.errors.iter().map(|e| e.to_string()).collect()
+    };
+    assert_eq!(first.files_checked, 41);
+    assert_eq!(first.errors.len(), 40);
+    assert_eq!(messages(&first), messages(&second));
+    for (error, name) in first.errors.iter().zip(&names) {
+        assert!(error.to_string().contains(&format!("src/{name}.rs.bog")), "{error}");
+    }
+    let stubbed: Vec<String> = missing
+        .iter()
+        .map(|(source, _, _)| source.file_stem().unwrap().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(stubbed, names);
+}
+
+#[test]
+fn test_validate_reuses_and_invalidates_the_cache() {
+    let root = TempProject::new("cache");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "x", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "core-agent", files = ["src/*.rs"], status = green }]
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        "#[file(owner = \"core-agent\", subsystem = \"core\", updated = \"2026-01-01\", status = green)]\n\
+         #[fn(a) { status = green }]\n#[fn(b) { status = green }]\n",
+    )
+    .unwrap();
+    let entries = |kind: &str| {
+        let dir = root.join(cache::CACHE_DIR).join(cache::version_dir()).join(kind);
+        std::fs::read_dir(dir).map(|d| d.count()).unwrap_or(0)
+    };
+
+    let cold = validator::validate_project(&root);
+    let (sidecars, symbols) = (entries("sidecars"), entries("symbols"));
+    let warm = validator::validate_project(&root);
+    let warm_entries = (entries("sidecars"), entries("symbols"));
+    // An edited source hashes differently, so its stale entry is not used
+    std::fs::write(root.join("src/lib.rs"), "fn a() {}\n").unwrap();
+    let edited = validator::validate_project(&root);
+    let edited_symbols = entries("symbols");
+
+    assert!(cold.is_ok(), "{:?}", cold.errors);
+    assert_eq!((sidecars, symbols), (1, 1));
+    assert_eq!(warm_entries, (1, 1));
+    assert_eq!(warm.errors.len(), cold.errors.len());
+    assert_eq!(warm.warnings, cold.warnings);
+    assert_eq!(edited_symbols, 2);
+    assert!(edited.errors.iter().any(|e| matches!(
+        e,
+        validator::ValidationError::MissingFunction { function, .. } if function == "b"
+    )));
+}
+
+#[test]
+fn test_excluded_paths_are_skipped_by_every_scan() {
+    let root = TempProject::new("exclude");
+    for dir in ["src", ".bog-worktrees/run-1/core-agent/src", "generated", "scratch"] {
+        std::fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    std::fs::write(
+        root.join("bog.toml"),
+        "exclude = [\"generated/\"]\n\n[bog]\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    std::fs::write(root.join(".bogignore"), "# local experiments\nscratch/\n").unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "x", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "core-agent", files = ["src/*.rs"], status = green }]
+"#,
+    )
+    .unwrap();
+    let sidecar = r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+#[fn(a) { status = green }]
+"#;
+    std::fs::write(root.join("src/lib.rs"), "fn a() {}\n").unwrap();
+    std::fs::write(root.join("src/lib.rs.bog"), sidecar).unwrap();
+    // A stale worktree copy whose function has since been removed
+    let stale = root.join(".bog-worktrees/run-1/core-agent/src");
+    std::fs::write(stale.join("lib.rs"), "fn old() {}\n").unwrap();
+    std::fs::write(stale.join("lib.rs.bog"), sidecar).unwrap();
+    std::fs::write(root.join("generated/out.rs"), "fn gen() {}\n").unwrap();
+    std::fs::write(root.join("scratch/notes.bog"), "#[fn(broken").unwrap();
+
+    let report = validator::validate_project(&root);
+    let missing = stub::find_missing_annotations(&root);
+    let walked = walk::Walker::new(&root).files_with_suffix(".bog");
+
+    assert!(report.is_ok(), "{:?}", report.errors);
+    assert_eq!(report.files_checked, 2);
+    assert!(missing.is_empty(), "{missing:?}");
+    let names: Vec<String> = walked
+        .iter()
+        .map(|p| p.strip_prefix(&root).unwrap().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(names, ["repo.bog", "src/lib.rs.bog"]);
+}
+
+#[test]
+fn test_sidecars_are_checked_by_the_backend_for_their_extension() {
+    let root = TempProject::new("lang");
+    for dir in ["src", "scripts"] {
+        std::fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "x", version = "0.1.0", updated = "2026-01-01")]
+#[subsystem(core) { owner = "core-agent", files = ["src/*.rs", "scripts/*.py"], status = green }]
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("bog.toml"),
+        "[bog]\nversion = \"0.1.0\"\n\n[tree_sitter]\nlanguage = \"python\"\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("src/lib.rs"), "fn a() {}\n").unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+#[fn(a) { status = green }]
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("scripts/tool.py"),
+        "import db\n\ndef run(name: str):\n    db.save(name)\n\ndef untracked():\n    pass\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("scripts/tool.py.bog"),
+        r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+#[fn(run) { status = green, deps = [db::save] }]
+#[fn(gone) { status = green }]
+"#,
+    )
+    .unwrap();
+
+    let report = validator::validate_project(&root);
+    let missing = stub::find_missing_annotations(&root);
+
+    assert_eq!(report.files_checked, 3);
+    let not_compiled = report
+        .warnings
+        .iter()
+        .any(|w| matches!(w, validator::ValidationWarning::LanguageNotCompiled { .. }));
+    assert_eq!(not_compiled, !cfg!(feature = "python"), "{:?}", report.warnings);
+    let missing: Vec<(String, Vec<String>)> = missing
+        .iter()
+        .map(|(source, _, symbols)| {
+            let rel = source.strip_prefix(&root).unwrap().to_string_lossy().to_string();
+            (rel, symbols.iter().map(|s| s.name.clone()).collect())
+        })
+        .collect();
+    if cfg!(feature = "python") {
+        // The Python sidecar is checked against its source like a Rust one
+        assert_eq!(report.errors.len(), 1, "{:?}", report.errors);
+        assert!(matches!(
+            &report.errors[0],
+            validator::ValidationError::MissingFunction { function, .. } if function == "gone"
+        ));
+        assert!(
+            !report
+                .warnings
+                .iter()
+                .any(|w| matches!(w, validator::ValidationWarning::UndeclaredCall { .. })),
+            "{:?}",
+            report.warnings
+        );
+        assert_eq!(missing, [("scripts/tool.py".to_string(), vec!["untracked".to_string()])]);
+    } else {
+        // Without the backend the sidecar is parsed but there is no source to check
+        assert!(report.is_ok(), "{:?}", report.errors);
+        assert!(missing.is_empty(), "{missing:?}");
+    }
+}
+
+#[test]
+fn test_stubs_cover_methods_but_not_trait_impls() {
+    let root = TempProject::new("stub");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("src/lib.rs"),
+        r#"pub struct Auth;
+
+impl Auth {
+    pub fn new() -> Self { Auth }
+    pub fn verify(&self) -> bool { true }
+}
+
+impl Default for Auth {
+    fn default() -> Self { Auth::new() }
+}
+
+pub trait Check {
+    fn check(&self) -> bool { true }
+}
+
+mod net {
+    pub fn connect() {}
+}
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+#[fn(Auth::new) { status = green }]
+"#,
+    )
+    .unwrap();
+
+    let missing = stub::find_missing_annotations(&root);
+
+    assert_eq!(missing.len(), 1);
+    let stubs: Vec<String> = missing[0].2.iter().map(stub::generate_stub).collect();
+    let names: Vec<&str> = stubs
+        .iter()
+        .map(|s| s.trim_start_matches("#[fn(").split(')').next().unwrap())
+        .collect();
+    assert_eq!(names, ["Auth::verify", "Check::check", "net::connect"]);
+}
+
+// --- CST / formatter ---
+
+#[test]
+fn test_dogfood_cst_and_fmt_roundtrip() {
+    let root = workspace_root();
+    let pattern = root.join("**/*.bog");
+    let mut checked = 0;
+    for path in glob::glob(&pattern.to_string_lossy()).unwrap().flatten() {
+        // `**/*.bog` also matches the .bog/ cache directory
+        if !path.is_file() || path.components().any(|c| c.as_os_str() == "target") {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path).unwrap();
+        let tree = cst::parse_cst(&content).unwrap();
+        assert_eq!(tree.to_string(), content, "CST not lossless for {}", path.display());
+
+        let formatted = formatter::format_bog(&content).unwrap();
+        assert_eq!(
+            formatter::format_bog(&formatted).unwrap(),
+            formatted,
+            "fmt not idempotent for {}",
+            path.display()
+        );
+        let before = parser::parse_bog(&content).unwrap();
+        let after = parser::parse_bog(&formatted).unwrap();
+        assert_eq!(before.annotations.len(), after.annotations.len());
+        assert_eq!(tree.comments(), cst::parse_cst(&formatted).unwrap().comments());
+        checked += 1;
+    }
+    assert!(checked >= 25);
+}
+
+#[test]
+fn test_dogfood_sidecars_are_fmt_clean() {
+    let report = formatter::format_path(&workspace_root(), true);
+    assert!(report.files_checked >= 25);
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert!(report.changed.is_empty(), "run `bog fmt .`: {:?}", report.changed);
+}
+
+// --- Export / import ---
+
+#[test]
+fn test_dogfood_export_import_roundtrip() {
+    let root = workspace_root();
+    let exported = export::export_project(&root).unwrap();
+    assert!(exported.files.contains_key("repo.bog"));
+    assert!(exported.files.contains_key("src/export.rs.bog"));
+
+    let json = export::render(&exported, export::ExportFormat::Json).unwrap();
+    let dir = TempProject::new("export");
+    let written = export::import_project(&dir, &json).unwrap();
+    assert_eq!(written.len(), exported.files.len());
+
+    let reimported = export::export_project(&dir).unwrap();
+    assert_eq!(reimported.files, exported.files);
+}
+
+// --- Recovering parse ---
+
+#[test]
+fn test_bad_annotation_does_not_hide_sidecar() {
+    let root = TempProject::new("recover");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "demo", version = "0.1.0", updated = "2026-01-01")]
+
+#[subsystem(core) {
+  owner = "core-agent",
+  files = ["src/*.rs"],
+  status = green
+}]
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("src/lib.rs"), "pub fn login() {}\n").unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+
+#[pickled(agent = "core-agent", updated = "2026-01-01") {
+  id = "p1", kind = decision, tags = [not_a_tag], content = "x"
+}]
+
+#[fn(login) {
+  status = green,
+  contract = { in = [], out = "()" }
+}]
+"#,
+    )
+    .unwrap();
+
+    let health = health::compute_health(&root);
+    let output = context::load_context(
+        &root,
+        context::ContextScope::All,
+        context::SectionFilter::all(),
+        None,
+        None,
+    );
+
+    assert_eq!(health.subsystems[0].file_count, 1);
+    let output = output.unwrap();
+    assert_eq!(output.files.len(), 1);
+    let file = &output.files[0];
+    assert_eq!(file.fn_contracts.len(), 1);
+    assert_eq!(file.fn_contracts[0].name, "login");
+    assert_eq!(file.parse_errors.len(), 1);
+    assert!(file.parse_errors[0].starts_with("4:"));
+}
+
+// --- Custom annotations ---
+
+#[test]
+fn test_custom_annotations_validated_against_schema() {
+    let root = TempProject::new("custom");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("bog.toml"),
+        r#"[bog]
+version = "0.1.0"
+
+[annotations.runbook]
+required = { url = "string" }
+optional = { oncall = "ident" }
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "demo", version = "0.1.0", updated = "2026-01-01")]
+
+#[subsystem(core) {
+  owner = "core-agent",
+  files = ["src/*.rs"],
+  status = green
+}]
+
+#[annotation(slo) {
+  required = { target = number, window = string }
+}]
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("src/lib.rs"), "pub fn login() {}\n").unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+
+#[runbook(deploy) { url = "https://wiki/deploy", oncall = platform }]
+
+#[slo(target = "high", window = "30d", owner = "me")]
+
+#[feature_flag(checkout_v2)]
+"#,
+    )
+    .unwrap();
+
+    let report = validator::validate_project(&root);
+    let output = context::load_context(
+        &root,
+        context::ContextScope::All,
+        context::SectionFilter::all(),
+        None,
+        None,
+    );
+
+    let errors: Vec<String> = report.errors.iter().map(ToString::to_string).collect();
+    assert_eq!(errors.len(), 3, "{errors:#?}");
+    assert!(matches!(
+        &report.errors[0],
+        validator::ValidationError::CustomFieldType { field, .. } if field == "target"
+    ));
+    assert!(errors[1].contains("undeclared field 'owner'"));
+    assert!(errors[2].contains("'feature_flag' is not built in or declared"));
+
+    let file = &output.unwrap().files[0];
+    assert_eq!(file.custom.len(), 3);
+    assert_eq!(file.custom[0].name.as_deref(), Some("deploy"));
+    assert_eq!(
+        file.custom[0].fields[0],
+        ("url".to_string(), "https://wiki/deploy".to_string())
+    );
+}
+
+// --- repo.bog includes ---
+
+#[test]
+fn test_repo_bog_includes_resolved_everywhere() {
+    let root = TempProject::new("include");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("subsystems")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "demo", version = "0.1.0", updated = "2026-01-01")]
+
+#[include("subsystems/*.bog")]
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("subsystems/core.bog"),
+        r#"#[subsystem(core) {
+  owner = "core-agent",
+  files = ["src/*.rs"],
+  status = green
+}]
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("src/lib.rs"), "pub fn login() {}\n").unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+
+#[fn(login) {
+  status = green
+}]
+"#,
+    )
+    .unwrap();
+
+    let report = validator::validate_project(&root);
+    let health = health::compute_health(&root);
+    let output = context::load_context(
+        &root,
+        context::ContextScope::Subsystem("core".to_string()),
+        context::SectionFilter::all(),
+        None,
+        None,
+    );
+
+    // A broken include is reported against the included file, with its span
+    std::fs::write(
+        root.join("subsystems/broken.bog"),
+        "#[skimsystem(q) { owner = \"q\", targets = all, status = gren }]\n",
+    )
+    .unwrap();
+    let broken = validator::validate_project(&root);
+
+    assert!(report.is_ok(), "{:#?}", report.errors);
+    assert_eq!(report.files_checked, 3);
+    assert_eq!(health.subsystems.len(), 1);
+    assert_eq!(health.subsystems[0].file_count, 1);
+    assert_eq!(output.unwrap().files.len(), 1);
+
+    assert_eq!(broken.errors.len(), 1);
+    match &broken.errors[0] {
+        validator::ValidationError::Parse { file, span, .. } => {
+            assert!(file.ends_with("subsystems/broken.bog"));
+            assert!(span.is_some());
+        }
+        other => panic!("expected parse error, got {other}"),
+    }
+}
+
+// --- Inline annotations ---
+
+#[test]
+fn test_inline_annotations_merge_with_sidecar() {
+    let root = TempProject::new("inline");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("repo.bog"),
+        r#"#[repo(name = "demo", version = "0.1.0", updated = "2026-01-01")]
+
+#[subsystem(core) {
+  owner = "core-agent",
+  files = ["src/*.rs"],
+  status = green
+}]
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/lib.rs"),
+        r#"/// Logs a user in.
+/// bog: status = red,
+/// bog: contract = { in = [(name, "&str")], out = "bool" }
+pub fn login(name: &str) -> bool { !name.is_empty() }
+
+/// bog: status = green
+pub fn logout() {}
+
+/// bog: status = green, invariants = ["token is never empty"]
+pub struct Session { pub token: String }
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("src/lib.rs.bog"),
+        r#"#[file(owner = "core-agent", subsystem = "core", updated = "2026-01-01", status = green)]
+
+#[fn(logout) {
+  status = yellow
+}]
+"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("src/orphan.rs"), "/// bog: status = green\npub fn f() {}\n").unwrap();
+
+    let report = validator::validate_project(&root);
+    let health = health::compute_health(&root);
+    let output = context::load_context(
+        &root,
+        context::ContextScope::All,
+        context::SectionFilter::all(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    // Both places annotate logout: reported, and the sidecar entry wins
+    assert_eq!(report.errors.len(), 1, "{:?}", report.errors);
+    let message = report.errors[0].to_string();
+    assert!(message.contains("line 6: 'logout' is annotated both inline and in the sidecar"), "{message}");
+    assert!(report.warnings.iter().any(|w| matches!(
+        w,
+        validator::ValidationWarning::InlineWithoutSidecar { file } if file.ends_with("orphan.rs")
+    )));
+
+    let fns = &health.subsystems[0].fn_statuses;
+    assert_eq!((fns.green, fns.yellow, fns.red), (0, 1, 1));
+
+    let file = &output.files[0];
+    let names: Vec<&str> = file.fn_contracts.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["logout", "login"]);
+    assert_eq!(file.fn_contracts[1].contract.as_ref().unwrap().output.as_deref(), Some("bool"));
+    assert_eq!(file.item_contracts[0].invariants, ["token is never empty"]);
+    assert!(file.parse_errors[0].starts_with("src/lib.rs line 6:"));
+}